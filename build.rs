@@ -14,6 +14,42 @@ fn compile_gresources(resources_directory: &str, gresources_xml: &str) {
     );
 }
 
+fn compile_schemas(schemas_directory: &str) {
+    let status = std::process::Command::new("glib-compile-schemas")
+        .arg(schemas_directory)
+        .status();
+    if !status.is_ok_and(|s| s.success()) {
+        println!("cargo:warning=Failed to compile GSettings schemas in {}", schemas_directory);
+    }
+    println!("cargo:rustc-env=APP_SCHEMAS={}", schemas_directory);
+}
+
+fn read_languages(po_directory: &str) -> Vec<String> {
+    std::fs::read_to_string(format!("{}/LINGUAS", po_directory))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+fn compile_translations(po_directory: &str, locale_directory: &str) {
+    for language in read_languages(po_directory) {
+        let messages_directory = format!("{}/{}/LC_MESSAGES", locale_directory, language);
+        std::fs::create_dir_all(&messages_directory).unwrap();
+        let status = std::process::Command::new("msgfmt")
+            .arg("-o")
+            .arg(format!("{}/{}.mo", messages_directory, std::env::var("CARGO_PKG_NAME").unwrap()))
+            .arg(format!("{}/{}.po", po_directory, language))
+            .status();
+        if !status.is_ok_and(|s| s.success()) {
+            println!("cargo:warning=Failed to compile the {} translation", language);
+        }
+    }
+    println!("cargo:rustc-env=APP_LOCALEDIR={}", locale_directory);
+}
+
 fn export_variables(app_id: &str, app_title: &str, app_icon: &str, app_g_resources_id: &str) {
     println!("cargo:rustc-env=APP_ID={}", app_id);
     println!("cargo:rustc-env=APP_TITLE={}", app_title);
@@ -23,6 +59,9 @@ fn export_variables(app_id: &str, app_title: &str, app_icon: &str, app_g_resourc
 
 fn main() {
     println!("cargo:rerun-if-env-changed=APP_G_RESOURCES_XML");
+    println!("cargo:rerun-if-env-changed=APP_SCHEMAS");
+    println!("cargo:rerun-if-env-changed=APP_LOCALEDIR");
+    println!("cargo:rerun-if-changed=po");
     println!("cargo:rerun-if-env-changed=resources");
 
     if let (
@@ -45,6 +84,8 @@ fn main() {
 
         compile_gresources(&app_resources, &app_g_resources_xml);
         export_variables(&app_id, &app_title, &app_icon, &app_g_resources_id);
+        println!("cargo:rustc-env=APP_SCHEMAS={}", std::env::var("APP_SCHEMAS").unwrap_or_default());
+        println!("cargo:rustc-env=APP_LOCALEDIR={}", std::env::var("APP_LOCALEDIR").unwrap_or_default());
     } else if cfg!(debug_assertions) {
 
         let g_resources_template = include_str!("resources/templates/gresources.xml.template")
@@ -55,6 +96,17 @@ fn main() {
         std::fs::write(&g_resources_file_path, g_resources_template).unwrap();
 
         compile_gresources("resources", &g_resources_file_path);
+
+        let schema_template = include_str!("resources/templates/gschema.xml.template")
+            .replace("@APP_ID@", "com.app.debug")
+            .replace("@APP_G_RESOURCES_ID@", "/com/app/debug");
+
+        let schemas_directory = format!("{}/schemas", std::env::var("OUT_DIR").unwrap());
+        std::fs::create_dir_all(&schemas_directory).unwrap();
+        std::fs::write(format!("{}/com.app.debug.gschema.xml", schemas_directory), schema_template).unwrap();
+
+        compile_schemas(&schemas_directory);
+        compile_translations("po", &format!("{}/locale", std::env::var("OUT_DIR").unwrap()));
         export_variables("com.app.debug", "Debug", "icon", "com/app/debug");
     } else {
         panic!("Missing environment variables");
@@ -1,30 +1,73 @@
 use crate::metadata;
-use crate::pages::Pages;
+use crate::pages::{OpenPages, Pages};
+use crate::reminders::{self, Reminders, Target};
+use crate::settings::Settings;
 use crate::window::Window;
 use crate::context::*;
 use crate::prelude::*;
 
+use gtk::{gio, glib};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
 #[derive(Clone)]
 pub struct App {
     application: adw::Application,
+    directory: DataDirectory,
+    settings: Settings,
+    open_pages: OpenPages,
+    hold_guard: Rc<RefCell<Option<gio::ApplicationHoldGuard>>>,
 }
 
 impl App {
 
     pub fn new() -> Self {
         let application = adw::Application::new(Some(&metadata::APP_ID), Default::default());
+        let directory = DataDirectory::from_user_data_dir();
+        directory.ensure_exists().expect("Failed to ensure that the data directory exists");
+        let settings = Settings::new();
         Self {
             application,
+            directory,
+            settings,
+            open_pages: OpenPages::default(),
+            hold_guard: Rc::new(RefCell::new(None)),
         }
     }
 
     fn setup_context(&self, application: &adw::Application) {
-        let pages = Pages::new();
-        let window = Window::new(application, &pages);
-        let directory = DataDirectory::from_user_data_dir();
-        directory.ensure_exists().expect("Failed to ensure that the data directory exists");
-        let context = Context::new(directory, window, pages);
+        if let Some(window) = application.active_window() {
+            return window.present();
+        }
+        self.open_window(application);
+    }
+
+    /// Opens a window on the status page, with its own pages and context.
+    fn open_window(&self, application: &adw::Application) -> Rc<Pages> {
+        let pages = Pages::new(&self.open_pages, &self.settings);
+        let window = Window::new(application, &pages, &self.settings);
+        let context = Context::new(self.directory.clone(), pages.background(), window, pages.clone());
         context.with_navigation_action(NavigationAction::NavigateToStatus).propagate();
+
+        if let Some(window) = pages.window() {
+            let pages = pages.clone();
+            window.connect_close_request(move |_| {
+                pages.close();
+                gtk::glib::Propagation::Proceed
+            });
+        }
+        pages
+    }
+
+    fn setup_actions(&self, application: &adw::Application) {
+        let new_window_action = Shortcut::NewWindow.build_action();
+        let this = self.clone();
+        let application_clone = application.clone();
+        new_window_action.connect_activate(move |_, _| {
+            this.open_window(&application_clone);
+        });
+        application.add_action(&new_window_action);
     }
 
     fn setup_resources(application: &adw::Application) {
@@ -36,9 +79,6 @@ impl App {
         let css_provider = gtk::CssProvider::new();
             css_provider.load_from_resource(&format!("{}/styles.css", metadata::APP_G_RESOURCES_ID));
 
-        let style_manager = adw::StyleManager::default();
-            style_manager.set_color_scheme(adw::ColorScheme::PreferDark);
-
         gtk::style_context_add_provider_for_display(
             &gtk::gdk::Display::default().expect("Could not connect to a display."),
             &css_provider,
@@ -46,15 +86,173 @@ impl App {
         );
     }
 
+    fn setup_accelerators(application: &adw::Application) {
+        for shortcut in Shortcut::as_slice() {
+            application.set_accels_for_action(&shortcut.detailed_name(), &[shortcut.accelerator()]);
+        }
+    }
+
+    fn setup_settings(&self) {
+        self.settings.apply();
+        self.settings.connect_changed(Settings::apply);
+    }
+
+    fn find_pages(&self, predicate: impl Fn(&Pages) -> bool) -> Option<Rc<Pages>> {
+        self.open_pages
+            .borrow()
+            .iter()
+            .filter_map(|pages| pages.upgrade())
+            .find(|pages| predicate(pages))
+    }
+
+    fn find_target_pages(&self, target: &Target) -> Option<Rc<Pages>> {
+        let file = target.file();
+        self.find_pages(|pages| pages.is_file_open(&file))
+    }
+
+    /// The window showing the document of the target, else one showing no
+    /// document, else a new one, so no other document gets replaced.
+    fn target_pages(&self, target: &Target) -> Rc<Pages> {
+        self.find_target_pages(target)
+            .or_else(|| self.find_pages(|pages| !pages.is_data_open()))
+            .unwrap_or_else(|| self.open_window(&self.application))
+    }
+
+    fn is_target_file_loaded(context: &Context, target: &Target) -> bool {
+        !context.data().is_empty() && context.file().path == target.path
+    }
+
+    /// Shows the transaction of the target, reading its document without marking it changed.
+    fn open_occurrence(&self, target: &Target) {
+        let pages = self.target_pages(target);
+        if let Some(window) = pages.window() {
+            window.present();
+        }
+        let context = pages.current_context();
+
+        if Self::is_target_file_loaded(&context, target) {
+            return Self::navigate_to_target(context, target);
+        }
+
+        let file = target.file();
+        let target = target.clone();
+        context.background().clone().spawn(
+            {
+                let file = file.clone();
+                move || file.load()
+            },
+            move |result| match result {
+                Ok(data) => Self::navigate_to_target(context.with_file(file).with_loaded_data(data), &target),
+                Err(e) => context
+                    .with_ui_action(UiAction::push_error(gettext("Failed to load data"), e))
+                    .propagate(),
+            }
+        );
+    }
+
+    fn navigate_to_target(context: Context, target: &Target) {
+        if !target.exists_in(context.data()) {
+            return context
+                .with_ui_action(UiAction::push_notification(gettext("Transaction not found")))
+                .propagate();
+        }
+
+        context
+            .clone()
+            .with_navigation_action(NavigationAction::NavigateToOverview)
+            .propagate();
+        context
+            .with_navigation_action(NavigationAction::navigate_to_transaction(target.wallet, target.transaction))
+            .propagate();
+    }
+
+    /// Marks the occurrence paid through the window showing its document, so the
+    /// change is saved like any other edit. A document no window shows is updated
+    /// on a worker thread, in turn with the other saves of its file.
+    fn pay_occurrence(&self, target: &Target, reminders: &Reminders) {
+        reminders.withdraw(target);
+
+        if let Some(context) = self.find_target_pages(target).map(|pages| pages.current_context()) {
+            if Self::is_target_file_loaded(&context, target) {
+                let mut data = context.data().clone();
+                if target.mark_paid(&mut data) {
+                    context
+                        .with_data(data)
+                        .with_ui_action(UiAction::push_notification(gettext("Marked as paid")))
+                        .with_navigation_action(NavigationAction::NavigateToCurrent)
+                        .propagate();
+                }
+                return;
+            }
+        }
+
+        let file = target.file();
+        let target = target.clone();
+        glib::spawn_future_local(async move {
+            let result = gio::spawn_blocking(move || {
+                let mut data = file.load()?;
+                if !target.mark_paid(&mut data) {
+                    return Ok(());
+                }
+                let number = SaveQueue::enqueue(&file.path);
+                SaveQueue::write(&file.path, number, || file.save(&data))
+            }).await;
+            if let Ok(Err(e)) = result {
+                glib::g_warning!(metadata::APP_NAME, "Failed to save data: {}", e);
+            }
+        });
+    }
+
+    fn setup_reminder_actions(&self, application: &adw::Application, reminders: &Reminders) {
+        let open_action = gio::SimpleAction::new(reminders::OPEN_ACTION, Some(Target::variant_type()));
+        let this = self.clone();
+        open_action.connect_activate(move |_, parameter| {
+            if let Some(target) = Target::from_variant(parameter) {
+                this.open_occurrence(&target);
+            }
+        });
+
+        let pay_action = gio::SimpleAction::new(reminders::PAY_ACTION, Some(Target::variant_type()));
+        let this = self.clone();
+        let reminders_clone = reminders.clone();
+        pay_action.connect_activate(move |_, parameter| {
+            if let Some(target) = Target::from_variant(parameter) {
+                this.pay_occurrence(&target, &reminders_clone);
+            }
+        });
+
+        application.add_action(&open_action);
+        application.add_action(&pay_action);
+    }
+
+    fn setup_reminders(&self, application: &adw::Application) {
+        let reminders = Reminders::new(application, self.directory.clone());
+        self.setup_reminder_actions(application, &reminders);
+
+        if application.flags().contains(gio::ApplicationFlags::IS_SERVICE) {
+            self.hold_guard.replace(Some(application.hold()));
+        }
+
+        reminders.start();
+    }
+
     pub fn init(&self) {
         let this = self.clone();
         self.application.connect_activate(move |app| {
             Self::setup_context(&this, app);
         });
-        self.application.connect_startup(|app| {
+        let this = self.clone();
+        self.application.connect_startup(move |app| {
             Self::setup_resources(app);
+            Self::setup_accelerators(app);
+            this.setup_actions(app);
+            this.setup_settings();
+            this.setup_reminders(app);
+        });
+        self.application.connect_shutdown(|_| {
+            SaveQueue::wait();
         });
         self.application.run();
     }
 
-}
\ No newline at end of file
+}
@@ -1,6 +1,8 @@
 use crate::context::Context;
+use crate::data::{Data, DataError, Merge};
 use crate::utils::AlertButton;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NavigationAction {
@@ -8,6 +10,12 @@ pub enum NavigationAction {
     NavigateToOverview,
     NavigateToWallet { wallet: Option<usize> },
     NavigateToTransaction { wallet: usize, transaction: Option<usize> },
+    NavigateToUpcoming { days: u32 },
+    NavigateToPayees,
+    NavigateToPayee { payee: Option<usize> },
+    NavigateToRules { wallet: Option<usize> },
+    NavigateToRule { rule: Option<usize> },
+    NavigateToSearch,
     NavigateToPrevious,
     NavigateToCurrent,
 }
@@ -51,25 +59,106 @@ impl NavigationAction {
             transaction: Some(transaction)
         }
     }
+
+    #[inline]
+    pub const fn navigate_to_upcoming(days: u32) -> Self {
+        NavigationAction::NavigateToUpcoming {
+            days
+        }
+    }
+
+    pub fn navigate_to_new_payee() -> Self {
+        NavigationAction::NavigateToPayee {
+            payee: None
+        }
+    }
+
+    #[inline]
+    pub const fn navigate_to_payee(payee: usize) -> Self {
+        NavigationAction::NavigateToPayee {
+            payee: Some(payee)
+        }
+    }
+
+    #[inline]
+    pub const fn navigate_to_rules(wallet: Option<usize>) -> Self {
+        NavigationAction::NavigateToRules {
+            wallet
+        }
+    }
+
+    pub fn navigate_to_new_rule() -> Self {
+        NavigationAction::NavigateToRule {
+            rule: None
+        }
+    }
+
+    #[inline]
+    pub const fn navigate_to_rule(rule: usize) -> Self {
+        NavigationAction::NavigateToRule {
+            rule: Some(rule)
+        }
+    }
+
+    /// Whether the wallet, transaction, payee or rule the action shows is in `data`.
+    pub fn is_available(&self, data: &Data) -> bool {
+        match *self {
+            NavigationAction::NavigateToWallet { wallet: Some(wallet) } => data.find_wallet_by_id(wallet).is_some(),
+            NavigationAction::NavigateToTransaction { wallet, transaction } => data
+                .find_wallet_by_id(wallet)
+                .is_some_and(|w| transaction.is_none_or(|id| w.find_transaction_by_id(id).is_some())),
+            NavigationAction::NavigateToPayee { payee: Some(payee) } => data.find_payee_by_id(payee).is_some(),
+            NavigationAction::NavigateToRule { rule: Some(rule) } => data.find_rule_by_id(rule).is_some(),
+            _ => true,
+        }
+    }
 }
 
 pub enum UiAction {
-    PushNotification { message: String },
+    PushNotification { message: String, error: Option<Rc<DataError>> },
     OpenAlertDialog {
         title: String,
         message: String,
         buttons: Vec<AlertButton>,
         callback: Box<dyn FnOnce(Option<&AlertButton>) + 'static>
     },
+    OpenEntryDialog {
+        title: String,
+        text: String,
+        button: AlertButton,
+        callback: Box<dyn FnOnce(Option<String>) + 'static>
+    },
     OpenFileChooserDialog { callback: Box<dyn FnOnce(Option<PathBuf>, &Context) + 'static> },
+    SaveFileChooserDialog {
+        initial_name: String,
+        callback: Box<dyn FnOnce(Option<PathBuf>, &Context) + 'static>
+    },
     OpenAboutDialog,
+    OpenPreferencesDialog,
+    OpenShortcutsWindow,
+    OpenCommandPalette { is_data_open: bool },
+    OpenPassphraseDialog,
+    OpenMergeDialog {
+        merge: Merge,
+        callback: Box<dyn FnOnce(Data, &Context) + 'static>
+    },
+    OpenErrorDialog { message: String, error: Rc<DataError> },
 }
 
 impl UiAction {
 
     pub fn push_notification(message: impl Into<String>) -> Self {
         UiAction::PushNotification { 
-            message: message.into() 
+            message: message.into(),
+            error: None
+        }
+    }
+
+    /// A notification for a failure, with a button to see what went wrong.
+    pub fn push_error(message: impl Into<String>, error: DataError) -> Self {
+        UiAction::PushNotification {
+            message: message.into(),
+            error: Some(Rc::new(error))
         }
     }
 
@@ -79,6 +168,19 @@ impl UiAction {
         }
     }
 
+    pub fn save_file_chooser(initial_name: impl Into<String>, callback: impl FnOnce(Option<PathBuf>, &Context) + 'static) -> Self {
+        UiAction::SaveFileChooserDialog {
+            initial_name: initial_name.into(),
+            callback: Box::new(callback)
+        }
+    }
+
+    pub fn open_merge_dialog(merge: Merge, callback: impl FnOnce(Data, &Context) + 'static) -> Self {
+        UiAction::OpenMergeDialog {
+            merge,
+            callback: Box::new(callback)
+        }
+    }
 
 }
 
@@ -0,0 +1,60 @@
+use crate::metadata;
+
+use gtk::{gio, glib};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+type BusyCallback = Rc<dyn Fn(bool)>;
+
+/// Runs slow work such as file access off the main thread for one window,
+/// and tells the window whether any of it is still running.
+#[derive(Clone, Default)]
+pub struct Background {
+    pending: Rc<Cell<usize>>,
+    callback: Rc<RefCell<Option<BusyCallback>>>,
+}
+
+impl Background {
+
+    pub fn is_busy(&self) -> bool {
+        self.pending.get() > 0
+    }
+
+    fn notify(&self) {
+        let callback = self.callback.borrow().clone();
+        if let Some(callback) = callback {
+            callback(self.is_busy());
+        }
+    }
+
+    /// Runs `task` on a worker thread, then `callback` with its result on the main loop.
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> T + Send + 'static,
+        callback: impl FnOnce(T) + 'static
+    ) {
+        self.pending.set(self.pending.get() + 1);
+        if self.pending.get() == 1 {
+            self.notify();
+        }
+
+        let this = self.clone();
+        glib::spawn_future_local(async move {
+            let result = gio::spawn_blocking(task).await;
+            this.pending.set(this.pending.get() - 1);
+            if !this.is_busy() {
+                this.notify();
+            }
+            match result {
+                Ok(result) => callback(result),
+                Err(_) => glib::g_warning!(metadata::APP_NAME, "Background task panicked"),
+            }
+        });
+    }
+
+    /// Calls `callback` when work starts while none was running, and when all of it is done.
+    pub fn connect_busy_changed(&self, callback: impl Fn(bool) + 'static) {
+        self.callback.replace(Some(Rc::new(callback)));
+    }
+
+}
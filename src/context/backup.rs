@@ -0,0 +1,126 @@
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use crate::i18n::gettext_noop;
+
+const BACKUP_DIRECTORY: &str = "backups";
+const BACKUP_EXTENSION: &str = "bak";
+
+static CURRENT: RwLock<Backup> = RwLock::new(Backup::DEFAULT);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BackupPolicy {
+    #[default]
+    Never,
+    OnSave,
+    Daily,
+}
+
+impl BackupPolicy {
+
+    const POLICIES: [&'static str; 3] = [
+        gettext_noop("Never"),
+        gettext_noop("Before Every Save"),
+        gettext_noop("Once a Day"),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::POLICIES[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 3] {
+        [
+            Self::Never,
+            Self::OnSave,
+            Self::Daily
+        ]
+    }
+
+}
+
+/// Copies a data file aside before it gets overwritten, keeping at most
+/// `count` copies in a `backups` directory next to the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backup {
+    pub policy: BackupPolicy,
+    pub count: u32,
+}
+
+impl Backup {
+
+    const DEFAULT: Self = Self {
+        policy: BackupPolicy::Never,
+        count: 5,
+    };
+
+    pub fn current() -> Self {
+        *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_current(backup: Self) {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = backup;
+    }
+
+    fn list_backups(directory: &Path, file_name: &str) -> io::Result<Vec<String>> {
+        let prefix = format!("{}.", file_name);
+        let mut backups: Vec<String> = fs::read_dir(directory)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with(&prefix) && name.ends_with(BACKUP_EXTENSION))
+            .collect();
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Backups of the file at `path`, oldest first.
+    pub fn list(path: &Path) -> Vec<PathBuf> {
+        let (Some(parent), Some(file_name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+            return Vec::new();
+        };
+        let directory = parent.join(BACKUP_DIRECTORY);
+        Self::list_backups(&directory, file_name)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|backup| directory.join(backup))
+            .collect()
+    }
+
+    /// Most recent backup of the file at `path`, the usual common ancestor when merging.
+    pub fn find_latest(path: &Path) -> Option<PathBuf> {
+        Self::list(path).pop()
+    }
+
+    pub fn run(&self, path: &Path) -> io::Result<()> {
+        if self.policy == BackupPolicy::Never || !path.exists() {
+            return Ok(());
+        }
+        let (Some(parent), Some(file_name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+            return Ok(());
+        };
+
+        let directory = parent.join(BACKUP_DIRECTORY);
+        fs::create_dir_all(&directory)?;
+
+        let now = Local::now();
+        let backups = Self::list_backups(&directory, file_name)?;
+        let today_prefix = format!("{}.{}", file_name, now.format("%Y%m%d"));
+        if self.policy == BackupPolicy::Daily && backups.iter().any(|b| b.starts_with(&today_prefix)) {
+            return Ok(());
+        }
+
+        let backup_name = format!("{}.{}.{}", file_name, now.format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION);
+        fs::copy(path, directory.join(backup_name))?;
+
+        let backups = Self::list_backups(&directory, file_name)?;
+        let excess = backups.len().saturating_sub(self.count.max(1) as usize);
+        for backup in backups.iter().take(excess) {
+            fs::remove_file(directory.join(backup))?;
+        }
+        Ok(())
+    }
+
+}
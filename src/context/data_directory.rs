@@ -1,11 +1,15 @@
+use crate::data::Data;
 use crate::metadata;
 
 use super::data_file::DataFile;
+use super::storage::StorageFormat;
 use std::path::PathBuf;
 use std::io;
 use std::fs;
 use std::time::SystemTime;
 
+const DEFAULT_NAME: &str = "document";
+
 #[derive(Debug, Clone)]
 pub struct DataDirectory {
     pub path: PathBuf,
@@ -43,6 +47,24 @@ impl DataDirectory {
         Ok(valid_files)
     }
 
+    /// The documents of the directory with their data, reading each file once.
+    /// Encrypted documents, and databases that can't be read, come without data.
+    pub fn load_valid(&self) -> io::Result<Vec<(DataFile, Option<Data>)>> {
+        let mut documents = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let data_file = DataFile::new(entry?.path());
+            if !data_file.path.is_file() {
+                continue;
+            }
+            match data_file.load() {
+                Ok(data) => documents.push((data_file, Some(data))),
+                Err(_) if data_file.is_valid() => documents.push((data_file, None)),
+                Err(_) => {},
+            }
+        }
+        Ok(documents)
+    }
+
     pub fn find_most_recent_data_file(&self) -> io::Result<Option<DataFile>> {
         let valid_files = self.list_valid()?;
         let mut latest: Option<(DataFile, SystemTime)> = None;
@@ -61,12 +83,13 @@ impl DataDirectory {
         Ok(latest.map(|(file, _)| file))
     }
 
-    pub fn generate_unique_file_path(&self) -> io::Result<PathBuf> {
+    /// First free path for a document called `name`, adding a number after the name when taken.
+    pub fn generate_unique_file_path(&self, name: &str) -> io::Result<PathBuf> {
         const MAX_ATTEMPTS: usize = 1000;
         let mut count = 0;
         
         while count < MAX_ATTEMPTS {
-            let file_path = self.build_data_path(count);
+            let file_path = self.build_data_path(name, count);
             if !file_path.exists() {
                 return Ok(file_path);
             }
@@ -79,22 +102,41 @@ impl DataDirectory {
         ))
     }
 
-    pub fn generate_unique_file_path_or_default(&self) -> PathBuf {
-        self.generate_unique_file_path()
-            .unwrap_or(self.path.join(format!("data.{}.json", metadata::APP_NAME)))
+    pub fn generate_unique_file_path_or_default(&self, name: &str) -> PathBuf {
+        self.generate_unique_file_path(name)
+            .unwrap_or(self.build_data_path(name, 0))
     }
 
-    pub fn create_new_data_file(&self) -> io::Result<DataFile> {
-        let file_path = self.generate_unique_file_path()?;
+    pub fn create_new_data_file(&self, name: &str) -> io::Result<DataFile> {
+        let file_path = self.generate_unique_file_path(name)?;
         let data_file = DataFile::new(file_path);
         return Ok(data_file);
     }
 
-    fn build_data_path(&self, count: usize) -> PathBuf {
+    /// File name for a document name in `format`, without the characters that can't appear in one.
+    pub fn file_name(name: &str, format: StorageFormat) -> String {
+        format!("{}.{}", Self::file_stem(name), format.extension())
+    }
+
+    fn file_stem(name: &str) -> String {
+        let stem: String = name
+            .trim()
+            .chars()
+            .map(|c| if std::path::is_separator(c) || c.is_control() { '-' } else { c })
+            .collect();
+        match stem.trim_start_matches('.') {
+            "" => DEFAULT_NAME.to_owned(),
+            stem => stem.to_owned(),
+        }
+    }
+
+    fn build_data_path(&self, name: &str, count: usize) -> PathBuf {
+        let stem = Self::file_stem(name);
+        let extension = StorageFormat::Json.extension();
         let filename = if count == 0 {
-            format!("data.{}.json", metadata::APP_NAME)
+            format!("{}.{}", stem, extension)
         } else {
-            format!("data.{}.{}.json", count, metadata::APP_NAME)
+            format!("{} {}.{}", stem, count + 1, extension)
         };
         self.path.join(filename)
     }
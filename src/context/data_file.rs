@@ -1,33 +1,147 @@
-use crate::data::Data;
-use crate::metadata;
+use crate::data::{Data, DataError, TransactionFilter};
+
+use super::backup::Backup;
+use super::save_queue::SaveQueue;
+use super::encryption::Encryption;
+use super::file_lock::FileLock;
+use super::json_storage::JsonStorage;
+use super::sqlite_storage::SqliteStorage;
+use super::storage::{Storage, StorageFormat};
 
 use std::path::PathBuf;
 use std::fs;
 use std::io;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 #[derive(Debug, Clone, Default)]
 pub struct DataFile {
     pub path: PathBuf,
+    encryption: Option<Arc<Encryption>>,
 }
 
 impl DataFile {
 
     #[inline]
     pub const fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self { path, encryption: None }
+    }
+
+    /// Same file settings for another path, such as a copy of this document encrypted with the same key.
+    pub fn with_path(mut self, path: PathBuf) -> Self {
+        self.path = path;
+        self
     }
 
-    pub fn load(&self) -> io::Result<Data> {
+    /// Opens an encrypted file with its passphrase.
+    /// Fails with an I/O error of kind `PermissionDenied` when the passphrase is wrong.
+    pub fn unlock(mut self, passphrase: &str) -> Result<Self, DataError> {
         let content = fs::read(&self.path)?;
-        serde_json::from_slice(&content)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        self.encryption = Some(Arc::new(Encryption::unlock(passphrase, &content)?));
+        Ok(self)
+    }
+
+    /// Forgets the key of an encrypted file, so it has to be unlocked again.
+    pub fn lock(mut self) -> Self {
+        self.encryption = None;
+        self
+    }
+
+    /// Encrypts the file with a new passphrase from the next save on, or stores it as plain JSON when `None`.
+    /// Only JSON files can be encrypted.
+    pub fn with_passphrase(mut self, passphrase: Option<&str>) -> Result<Self, DataError> {
+        if passphrase.is_some() && self.format() != StorageFormat::Json {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "only JSON documents can be encrypted").into());
+        }
+        self.encryption = passphrase
+            .map(Encryption::new)
+            .transpose()?
+            .map(Arc::new);
+        Ok(self)
+    }
+
+    pub const fn has_passphrase(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Whether the file on disk is encrypted and has not been unlocked.
+    pub fn is_locked(&self) -> bool {
+        self.encryption.is_none() && self.is_encrypted()
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        let mut header = [0; 8];
+        fs::File::open(&self.path)
+            .and_then(|mut file| io::Read::read_exact(&mut file, &mut header))
+            .is_ok_and(|_| Encryption::is_encrypted(&header))
+    }
+
+    pub fn format(&self) -> StorageFormat {
+        StorageFormat::detect(&self.path)
     }
 
-    pub fn save(&self, data: &Data) -> io::Result<()> {
-        let content = serde_json::to_string(data)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        fs::write(&self.path, content)
+    fn storage(&self) -> Box<dyn Storage + '_> {
+        match self.format() {
+            StorageFormat::Json => Box::new(JsonStorage::new(&self.path, self.encryption.as_deref())),
+            StorageFormat::Sqlite => Box::new(SqliteStorage::new(&self.path)),
+        }
+    }
+
+    pub fn load(&self) -> Result<Data, DataError> {
+        self.storage().load()
+    }
+
+    pub fn save(&self, data: &Data) -> Result<(), DataError> {
+        if FileLock::is_held_by_other(self) {
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "data file is locked by another instance").into());
+        }
+        Backup::current().run(&self.path)?;
+        self.storage().save(data)?;
+        self.encrypt_backups()
+    }
+
+    /// Encrypts the backups of an encrypted document that were made while it was still stored in plain text.
+    fn encrypt_backups(&self) -> Result<(), DataError> {
+        let Some(encryption) = &self.encryption else { return Ok(()) };
+        for path in Backup::list(&self.path) {
+            let backup = self.clone().with_path(path);
+            if !backup.is_encrypted() {
+                let content = fs::read(&backup.path)?;
+                fs::write(&backup.path, encryption.encrypt(&content)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Ids of the wallets and transactions matching `filter` when the storage has an index for it
+    /// and holds every edit, so a save still waiting or one that failed leaves the search to `Data`.
+    pub fn search(&self, filter: &TransactionFilter) -> Option<Vec<(usize, usize)>> {
+        if !SaveQueue::is_written(&self.path) {
+            return None;
+        }
+        self.storage().search(filter)
+    }
+
+    /// A new file of another format next to this one, leaving this file as it is.
+    /// The open document, with its unsaved edits, is then saved into it.
+    /// Encrypted documents stay in JSON, the only format that can hold them.
+    pub fn convert(&self, format: StorageFormat) -> Result<Self, DataError> {
+        if self.has_passphrase() && format != StorageFormat::Json {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "only JSON documents can be encrypted").into());
+        }
+        let stem = self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = (1..1000)
+            .map(|count| match count {
+                1 => format!("{}.{}", stem, format.extension()),
+                count => format!("{} {}.{}", stem, count, format.extension()),
+            })
+            .map(|file_name| self.path.with_file_name(file_name))
+            .find(|path| !path.exists())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::AlreadyExists, "could not find a free name"))?;
+        Ok(self.clone().with_path(path))
     }
 
     pub fn remove(&self) -> io::Result<()> {
@@ -38,29 +152,16 @@ impl DataFile {
         self.path.exists()
     }
 
+    /// Recognizes documents by their content, whatever their file name:
+    /// encrypted files and databases by their header, the others by parsing as data.
     pub fn is_valid(&self) -> bool {
-        self.has_valid_extension() &&
-        self.has_valid_filename() &&
-        self.has_valid_content()
+        self.path.is_file() && (self.is_encrypted() || SqliteStorage::is_database(&self.path) || self.has_valid_content())
     }
 
     fn has_valid_content(&self) -> bool {
-        fs::read_to_string(&self.path)
+        fs::read(&self.path)
             .ok()
-            .and_then(|content| serde_json::from_str::<Data>(&content).ok())
-            .is_some()
-    }
-
-    fn has_valid_extension(&self) -> bool {
-        self.path.extension()
-            .and_then(|ext| ext.to_str())
-            .map_or(false, |ext| ext == "json")
-    }
-
-    fn has_valid_filename(&self) -> bool {
-        self.path.file_name()
-            .and_then(|name| name.to_str())
-            .map_or(false, |name| name.contains(metadata::APP_NAME))
+            .is_some_and(|content| serde_json::from_slice::<Data>(&content).is_ok())
     }
 
     pub fn modified_time(&self) -> io::Result<SystemTime> {
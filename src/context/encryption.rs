@@ -0,0 +1,202 @@
+use argon2::{Argon2, Params};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use std::fmt;
+use std::io;
+
+const MAGIC: &[u8; 8] = b"FMCRYPT1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PARAMS_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + PARAMS_LEN + SALT_LEN;
+
+/// Key derived from a passphrase, used to encrypt a data file with
+/// ChaCha20-Poly1305. An encrypted file is laid out as the magic bytes,
+/// the Argon2 parameters and salt, the nonce and the ciphertext; the
+/// header is authenticated along with the ciphertext.
+pub struct Encryption {
+    params: Params,
+    salt: [u8; SALT_LEN],
+    key: Key,
+}
+
+impl Encryption {
+
+    /// Derives a key for a new passphrase, with a fresh salt.
+    pub fn new(passphrase: &str) -> io::Result<Self> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, Params::default(), salt)
+    }
+
+    /// Derives the key of an encrypted file from its passphrase and checks it opens the file.
+    pub fn unlock(passphrase: &str, content: &[u8]) -> io::Result<Self> {
+        let (params, salt) = Self::read_header(content)?;
+        let encryption = Self::derive(passphrase, params, salt)?;
+        encryption.decrypt(content)?;
+        Ok(encryption)
+    }
+
+    pub fn is_encrypted(content: &[u8]) -> bool {
+        content.starts_with(MAGIC)
+    }
+
+    fn derive(passphrase: &str, params: Params, salt: [u8; SALT_LEN]) -> io::Result<Self> {
+        let mut key = Key::default();
+        Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params.clone())
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        Ok(Self { params, salt, key })
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        let mut value = [0; 4];
+        value.copy_from_slice(&bytes[offset..offset + 4]);
+        u32::from_le_bytes(value)
+    }
+
+    fn read_header(content: &[u8]) -> io::Result<(Params, [u8; SALT_LEN])> {
+        if !Self::is_encrypted(content) || content.len() < HEADER_LEN + NONCE_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "data file is not encrypted"));
+        }
+        let offset = MAGIC.len();
+        let (m_cost, t_cost, p_cost) = (
+            Self::read_u32(content, offset),
+            Self::read_u32(content, offset + 4),
+            Self::read_u32(content, offset + 8),
+        );
+        // The header is only authenticated once the key is derived, so costs
+        // above the ones this writer uses are refused rather than computed.
+        if m_cost > Params::DEFAULT_M_COST || t_cost > Params::DEFAULT_T_COST || p_cost > Params::DEFAULT_P_COST {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported encryption parameters"));
+        }
+        let params = Params::new(m_cost, t_cost, p_cost, None)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        let mut salt = [0; SALT_LEN];
+        salt.copy_from_slice(&content[offset + PARAMS_LEN..HEADER_LEN]);
+        Ok((params, salt))
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&self.params.m_cost().to_le_bytes());
+        header.extend_from_slice(&self.params.t_cost().to_le_bytes());
+        header.extend_from_slice(&self.params.p_cost().to_le_bytes());
+        header.extend_from_slice(&self.salt);
+        header
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        let header = self.header();
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, Payload { msg: plaintext, aad: &header })
+            .map_err(|_| io::Error::other("failed to encrypt data file"))?;
+
+        let mut content = header;
+        content.extend_from_slice(&nonce);
+        content.extend_from_slice(&ciphertext);
+        Ok(content)
+    }
+
+    /// Fails with `PermissionDenied` when the passphrase does not match the file.
+    pub fn decrypt(&self, content: &[u8]) -> io::Result<Vec<u8>> {
+        let (params, salt) = Self::read_header(content)?;
+        if params != self.params || salt != self.salt {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "data file was encrypted with another passphrase"));
+        }
+        let (header, rest) = content.split_at(HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        ChaCha20Poly1305::new(&self.key)
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map_err(|_| io::Error::new(io::ErrorKind::PermissionDenied, "wrong passphrase"))
+    }
+
+}
+
+impl fmt::Debug for Encryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Encryption").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Low costs keep the tests fast; files are still read with the costs in their header.
+    fn encryption(passphrase: &str) -> Encryption {
+        Encryption::derive(passphrase, Params::new(Params::MIN_M_COST, 1, 1, None).unwrap(), [7; SALT_LEN]).unwrap()
+    }
+
+    #[test]
+    fn encrypted_content_decrypts_back() {
+        let encryption = encryption("correct horse");
+        let content = encryption.encrypt(b"{\"wallets\":[]}").unwrap();
+
+        assert!(Encryption::is_encrypted(&content));
+        assert_eq!(encryption.decrypt(&content).unwrap(), b"{\"wallets\":[]}");
+        let unlocked = Encryption::unlock("correct horse", &content).unwrap();
+        assert_eq!(unlocked.decrypt(&content).unwrap(), b"{\"wallets\":[]}");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_denied() {
+        let content = encryption("correct horse").encrypt(b"secret").unwrap();
+
+        let error = Encryption::unlock("battery staple", &content).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        let error = encryption("battery staple").decrypt(&content).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn tampered_content_is_denied() {
+        let encryption = encryption("correct horse");
+        let content = encryption.encrypt(b"secret").unwrap();
+
+        let mut tampered_salt = content.clone();
+        tampered_salt[HEADER_LEN - 1] ^= 1;
+        let error = Encryption::unlock("correct horse", &tampered_salt).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(encryption.decrypt(&tampered_salt).unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+
+        // Same key with the tampered salt, so only the authenticated header tells them apart.
+        let forged = Encryption { params: encryption.params.clone(), salt: [8; SALT_LEN], key: encryption.key };
+        let mut tampered_header = content.clone();
+        tampered_header[HEADER_LEN - SALT_LEN..HEADER_LEN].copy_from_slice(&forged.salt);
+        assert_eq!(forged.decrypt(&tampered_header).unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+
+        let mut tampered_ciphertext = content;
+        *tampered_ciphertext.last_mut().unwrap() ^= 1;
+        assert_eq!(encryption.decrypt(&tampered_ciphertext).unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn oversized_costs_are_refused() {
+        let content = encryption("correct horse").encrypt(b"secret").unwrap();
+        let offset = MAGIC.len();
+
+        for (position, cost) in [
+            (offset, Params::DEFAULT_M_COST + 1),
+            (offset + 4, Params::DEFAULT_T_COST + 1),
+            (offset + 8, Params::DEFAULT_P_COST + 1),
+        ] {
+            let mut tampered = content.clone();
+            tampered[position..position + 4].copy_from_slice(&cost.to_le_bytes());
+            let error = Encryption::unlock("correct horse", &tampered).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn plain_and_truncated_content_is_not_encrypted() {
+        assert!(!Encryption::is_encrypted(b"{\"wallets\":[]}"));
+        assert_eq!(Encryption::unlock("correct horse", b"{}").unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(Encryption::unlock("correct horse", MAGIC).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
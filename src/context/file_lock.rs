@@ -0,0 +1,108 @@
+use super::data_file::DataFile;
+use crate::metadata;
+
+use gtk::{gio, glib};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+const REFRESH_SECONDS: u32 = 60;
+const STALE_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// Advisory lock kept next to an open data file as `.<file name>.lock`.
+/// It records the process and host holding the document, so a second
+/// instance refuses to write to it. The holder rewrites it every minute;
+/// locks of processes that are gone, or that were not rewritten for a few
+/// minutes, such as those left by a crashed instance on another host, are ignored.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+    refresh: Option<glib::SourceId>,
+}
+
+impl FileLock {
+
+    fn lock_path(file: &DataFile) -> PathBuf {
+        let file_name = file.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        file.path.with_file_name(format!(".{}.lock", file_name))
+    }
+
+    fn owner() -> String {
+        format!("{}\n{}", process::id(), gtk::glib::host_name())
+    }
+
+    fn is_owned(path: &Path) -> bool {
+        fs::read_to_string(path).is_ok_and(|content| content == Self::owner())
+    }
+
+    /// Whether the process is gone, when that can be told: only for processes of this host, through `/proc`.
+    fn is_process_gone(pid: &str, host: &str) -> bool {
+        let proc = Path::new("/proc");
+        host == gtk::glib::host_name() && proc.join("self").exists() && !proc.join(pid).exists()
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > STALE_AGE)
+    }
+
+    /// Whether another running instance holds the lock of `file`.
+    pub fn is_held_by_other(file: &DataFile) -> bool {
+        let path = Self::lock_path(file);
+        let Ok(content) = fs::read_to_string(&path) else { return false };
+        if content == Self::owner() {
+            return false;
+        }
+
+        let mut lines = content.lines();
+        let (Some(pid), Some(host)) = (lines.next(), lines.next()) else { return false };
+        !Self::is_process_gone(pid, host) && !Self::is_stale(&path)
+    }
+
+    pub fn acquire(file: &DataFile) -> io::Result<Self> {
+        if Self::is_held_by_other(file) {
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "data file is locked by another instance"));
+        }
+        let path = Self::lock_path(file);
+        fs::write(&path, Self::owner())?;
+
+        let refreshed_path = path.clone();
+        let refresh = glib::timeout_add_seconds_local(REFRESH_SECONDS, move || {
+            let path = refreshed_path.clone();
+            drop(gio::spawn_blocking(move || Self::refresh(&path)));
+            glib::ControlFlow::Continue
+        });
+        Ok(Self { path, refresh: Some(refresh) })
+    }
+
+    /// Rewrites the lock so it doesn't look abandoned, unless another instance has taken it over.
+    fn refresh(path: &Path) {
+        if Self::is_owned(path) {
+            if let Err(e) = fs::write(path, Self::owner()) {
+                glib::g_warning!(metadata::APP_NAME, "Failed to refresh lock file: {}", e);
+            }
+        }
+    }
+
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Some(refresh) = self.refresh.take() {
+            refresh.remove();
+        }
+        if Self::is_owned(&self.path) {
+            if let Err(e) = fs::remove_file(&self.path) {
+                glib::g_warning!(metadata::APP_NAME, "Failed to remove lock file: {}", e);
+            }
+        }
+    }
+}
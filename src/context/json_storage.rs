@@ -0,0 +1,45 @@
+use crate::data::{Data, DataError};
+
+use super::encryption::Encryption;
+use super::storage::Storage;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The whole document in one JSON file, encrypted when it has a key.
+pub struct JsonStorage<'a> {
+    path: &'a Path,
+    encryption: Option<&'a Encryption>,
+}
+
+impl<'a> JsonStorage<'a> {
+
+    pub const fn new(path: &'a Path, encryption: Option<&'a Encryption>) -> Self {
+        Self { path, encryption }
+    }
+
+}
+
+impl Storage for JsonStorage<'_> {
+
+    fn load(&self) -> Result<Data, DataError> {
+        let mut content = fs::read(self.path)?;
+        if Encryption::is_encrypted(&content) {
+            let Some(encryption) = self.encryption else {
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, "data file is encrypted").into());
+            };
+            content = encryption.decrypt(&content)?;
+        }
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    fn save(&self, data: &Data) -> Result<(), DataError> {
+        let mut content = serde_json::to_vec(data)?;
+        if let Some(encryption) = self.encryption {
+            content = encryption.encrypt(&content)?;
+        }
+        Ok(fs::write(self.path, content)?)
+    }
+
+}
@@ -1,14 +1,33 @@
 mod action;
 mod data_file;
 mod data_directory;
+mod backup;
+mod shortcut;
+mod file_lock;
+mod encryption;
+mod recent_documents;
+mod storage;
+mod json_storage;
+mod sqlite_storage;
+mod background;
+mod save_queue;
 
 pub use action::{NavigationAction, UiAction, Action};
 pub use data_file::DataFile;
 pub use data_directory::DataDirectory;
+pub use backup::{Backup, BackupPolicy};
+pub use file_lock::FileLock;
+pub use recent_documents::{RecentDocument, RecentDocuments};
+pub use storage::StorageFormat;
+pub use background::Background;
+pub use save_queue::SaveQueue;
+pub use shortcut::{Shortcut, WINDOW_ACTIONS, PAGE_ACTIONS, build_action_group};
 
 use crate::prelude::*;
 use crate::data::*;
+use crate::metadata;
 
+use gtk::glib;
 use std::rc::Rc;
 
 pub struct Context {
@@ -16,6 +35,7 @@ pub struct Context {
     file: DataFile,
     data: Rc<Data>,
     changed_data: bool,
+    background: Background,
 
     ui_propagator: Option<Rc<dyn Propagator<UiAction>>>,
     navigation_propagator: Option<Rc<dyn Propagator<NavigationAction>>>,
@@ -30,6 +50,7 @@ impl Default for Context {
             file: DataFile::default(),
             data: Rc::new(Data::default()),
             changed_data: false,
+            background: Background::default(),
             ui_propagator: None,
             navigation_propagator: None,
             navigation_action: None,
@@ -41,6 +62,7 @@ impl Default for Context {
 impl Context {
     pub fn new(
         directory: DataDirectory,
+        background: Background,
         ui_propagator: impl Propagator<UiAction> + 'static,
         navigation_propagator: impl Propagator<NavigationAction> + 'static
     ) -> Self {
@@ -49,6 +71,7 @@ impl Context {
             file: DataFile::default(),
             data: Rc::new(Data::default()),
             changed_data: false,
+            background,
             ui_propagator: Some(Rc::new(ui_propagator)),
             navigation_propagator: Some(Rc::new(navigation_propagator)),
             navigation_action: None,
@@ -68,6 +91,14 @@ impl Context {
         &self.file
     }
 
+    pub const fn background(&self) -> &Background {
+        &self.background
+    }
+
+    pub const fn has_changed_data(&self) -> bool {
+        self.changed_data
+    }
+
     pub fn with_file(mut self, file: DataFile) -> Self {
         self.file = file;
         self
@@ -79,6 +110,12 @@ impl Context {
         self
     }
 
+    /// Replaces the data with a version just read from disk, which needs no saving.
+    pub fn with_loaded_data(mut self, data: Data) -> Self {
+        self.data = Rc::new(data);
+        self
+    }
+
     pub fn with_navigation_action(mut self, action: NavigationAction) -> Self {
         self.navigation_action = Some(action);
         self
@@ -103,47 +140,108 @@ impl Context {
         }
     }
 
+    /// Forgets the key of an encrypted document and goes back to the status page to ask for it again.
+    pub fn lock(self) {
+        let file = self.file.clone().lock();
+        self
+            .with_file(file)
+            .with_loaded_data(Data::default())
+            .with_ui_action(UiAction::push_notification(gettext("Document locked")))
+            .with_navigation_action(NavigationAction::NavigateToStatus)
+            .propagate();
+    }
+
+    /// Merges `theirs` into the open document using `base` as the common ancestor,
+    /// when it is known. Fields changed on both sides are left to the user in the merge dialog.
+    pub fn merge(self, base: Option<&Data>, theirs: &Data) {
+        let merge = match base {
+            Some(base) => Merge::new(base, &self.data, theirs),
+            None => Merge::without_base(&self.data, theirs),
+        };
+        if !merge.has_conflicts() {
+            return Self::apply_merge(merge.data, &self);
+        }
+        self
+            .with_ui_action(UiAction::open_merge_dialog(merge, Self::apply_merge))
+            .propagate();
+    }
+
+    fn apply_merge(data: Data, context: &Context) {
+        context
+            .clone()
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Documents merged")))
+            .with_navigation_action(NavigationAction::NavigateToCurrent)
+            .propagate();
+    }
+
     fn handle_save_error(
-        &self,
-        err: std::io::Error,
+        err: DataError,
         ui_propagator: &Option<Rc<dyn Propagator<UiAction>>>,
         default_context: &Context,
     ) {
         if let Some(propagator) = ui_propagator {
+            let message = if err.io_kind() == Some(std::io::ErrorKind::WouldBlock) {
+                gettext("Document is locked by another instance")
+            } else {
+                gettext("Failed to save data")
+            };
             propagator.propagate(
-                UiAction::push_notification("Failed to save data"),
+                UiAction::push_error(message, err),
                 default_context,
             );
         } else {
-            eprintln!("Failed to save data: {}", err);
+            glib::g_warning!(metadata::APP_NAME, "Failed to save data: {}", err);
         }
     }
 
+    /// Writes the data on a worker thread, reporting a failure once it is known.
+    fn save(&self, ui_propagator: Option<Rc<dyn Propagator<UiAction>>>) {
+        let file = self.file.clone();
+        let data = Data::clone(&self.data);
+        let number = SaveQueue::enqueue(&file.path);
+        let default_context = self.clone();
+        self.background.spawn(
+            move || SaveQueue::write(&file.path, number, || file.save(&data)),
+            move |result| {
+                if let Err(e) = result {
+                    Self::handle_save_error(e, &ui_propagator, &default_context);
+                }
+            }
+        );
+    }
+
+    /// Reads the file back on a worker thread after invalid data was not saved,
+    /// and removes it there if it can't be read either.
+    fn check_file(&self, ui_propagator: Option<Rc<dyn Propagator<UiAction>>>) {
+        let file = self.file.clone();
+        let default_context = self.clone();
+        self.background.spawn(
+            move || file.load().err().map(|err| (err, file.remove())),
+            move |result| {
+                if let Some((err, remove_result)) = result {
+                    Self::handle_load_error(err, remove_result, &ui_propagator, &default_context);
+                }
+            }
+        );
+    }
+
     fn handle_load_error(
-        &self,
-        _: std::io::Error,
+        err: DataError,
+        remove_result: std::io::Result<()>,
         ui_propagator: &Option<Rc<dyn Propagator<UiAction>>>,
         default_context: &Context,
     ) {
-        let remove_result = self.file.remove();
         if let Some(propagator) = ui_propagator {
-            if remove_result.is_err() {
-                propagator.propagate(
-                    UiAction::push_notification("Failed to remove invalid file"),
-                    default_context,
-                );
-            } else {
-                propagator.propagate(
-                    UiAction::push_notification("Removed invalid file"),
-                    default_context,
-                );
-            }
+            let action = match remove_result {
+                Ok(()) => UiAction::push_error(gettext("Removed invalid file"), err),
+                Err(e) => UiAction::push_error(gettext("Failed to remove invalid file"), e.into()),
+            };
+            propagator.propagate(action, default_context);
+        } else if let Err(e) = remove_result {
+            glib::g_warning!(metadata::APP_NAME, "Failed to remove invalid file: {}", e);
         } else {
-            if let Err(e) = remove_result {
-                eprintln!("Failed to remove invalid file: {}", e);
-            } else {
-                eprintln!("Removed invalid file");
-            }
+            glib::g_warning!(metadata::APP_NAME, "Removed invalid file: {}", err);
         }
     }
 
@@ -155,6 +253,7 @@ impl Clone for Context {
             directory: self.directory.clone(),
             file: self.file.clone(),
             data: self.data.clone(),
+            background: self.background.clone(),
             ui_propagator: self.ui_propagator.clone(),
             navigation_propagator: self.navigation_propagator.clone(),
 
@@ -172,14 +271,12 @@ impl Drop for Context {
         }
 
         let ui_propagator = self.ui_propagator.take();
-        if self.data.is_valid() {
-            if let Err(e) = self.file.save(&self.data) {
-                self.handle_save_error(e, &ui_propagator, &self);
-            }
-        } else {
-            if let Err(e) = self.file.load() {
-                self.handle_load_error(e, &ui_propagator, &self);
-            }
+        match self.data.validate() {
+            Ok(()) => self.save(ui_propagator),
+            Err(e) => {
+                glib::g_warning!(metadata::APP_NAME, "Not saving invalid data: {}", e);
+                self.check_file(ui_propagator);
+            },
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,265 @@
+use crate::data::{Currency, Data, DataError, DocumentIcon};
+use crate::metadata;
+use crate::i18n::gettext_f;
+
+use super::data_directory::DataDirectory;
+use super::data_file::DataFile;
+use super::storage::StorageFormat;
+
+use gtk::gio;
+use gtk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const RECENT_FILE: &str = "recent-documents.json";
+const RECENT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecentEntry {
+    path: PathBuf,
+    #[serde(default)]
+    is_pinned: bool,
+}
+
+/// A document shown in the recent list, with what can be read without opening it.
+#[derive(Debug, Clone)]
+pub struct RecentDocument {
+    pub file: DataFile,
+    /// Name stored in the document, or its file name when it has none or can't be read.
+    pub name: String,
+    pub icon: DocumentIcon,
+    pub modified: Option<SystemTime>,
+    pub is_pinned: bool,
+    pub is_in_directory: bool,
+    /// Wallet count, net worth and whether it is fully converted, unknown for
+    /// encrypted or unreadable documents.
+    pub summary: Option<(usize, f64, Currency, bool)>,
+}
+
+impl RecentDocument {
+
+    fn new(file: DataFile, data: Option<Data>, is_pinned: bool, directory: &DataDirectory) -> Self {
+        let summary = data
+            .as_ref()
+            .map(|data: &Data| (data.wallets.len(), data.net_worth(), data.currency, !data.has_unconverted_amounts()));
+        Self {
+            name: RecentDocuments::name_or_file_name(data.as_ref(), &file.path),
+            icon: data.map(|data| data.icon).unwrap_or_default(),
+            modified: file.modified_time().ok(),
+            is_in_directory: file.path.parent() == Some(directory.path.as_path()),
+            is_pinned,
+            summary,
+            file,
+        }
+    }
+
+}
+
+/// Documents opened lately, kept in the configuration directory and
+/// published to the desktop through `gtk::RecentManager`. Documents in the
+/// data directory are always listed, the others once they were opened.
+pub struct RecentDocuments {
+    entries: Vec<RecentEntry>,
+}
+
+impl RecentDocuments {
+
+    fn storage_path() -> PathBuf {
+        gtk::glib::user_config_dir()
+            .join(metadata::APP_ID)
+            .join(RECENT_FILE)
+    }
+
+    pub fn load() -> Self {
+        let entries = fs::read(Self::storage_path())
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        Self { entries }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = Self::storage_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_vec(&self.entries)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+
+    fn uri(path: &Path) -> String {
+        gio::File::for_path(path).uri().to_string()
+    }
+
+    /// File name without the extensions added by the application.
+    pub fn file_display_name(path: &Path) -> String {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let suffix = format!(".{}.json", metadata::APP_NAME);
+        let extension = format!(".{}", StorageFormat::detect(path).extension());
+        file_name
+            .strip_suffix(&suffix)
+            .or_else(|| file_name.strip_suffix(&extension))
+            .map(str::to_owned)
+            .unwrap_or(file_name)
+    }
+
+    fn name_or_file_name(data: Option<&Data>, path: &Path) -> String {
+        data.map(|data| data.name.trim())
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .unwrap_or_else(|| Self::file_display_name(path))
+    }
+
+    /// Name of the document at `path`, as stored in it or else from its file name.
+    /// Reads the document, so it belongs on a worker thread.
+    fn display_name(path: &Path) -> String {
+        let data = DataFile::new(path.to_path_buf()).load().ok();
+        Self::name_or_file_name(data.as_ref(), path)
+    }
+
+    /// Writes `name` into the document, when it isn't encrypted.
+    fn store_name(path: &Path, name: &str) -> Result<(), DataError> {
+        let file = DataFile::new(path.to_path_buf());
+        let mut data = file.load()?;
+        data.name = name.to_owned();
+        file.save(&data)
+    }
+
+    fn is_pinned(&self, path: &Path) -> bool {
+        self.entries.iter().any(|entry| entry.path == path && entry.is_pinned)
+    }
+
+    /// Records an opened document, moving it to the top of the list.
+    pub fn add(path: &Path) -> io::Result<()> {
+        Self::publish(path);
+        Self::record(path)
+    }
+
+    /// Moves a document to the top of the list without telling the desktop,
+    /// so it can run on a worker thread.
+    fn record(path: &Path) -> io::Result<()> {
+        let mut this = Self::load();
+        let is_pinned = this.is_pinned(path);
+        this.entries.retain(|entry| entry.path != path);
+        this.entries.insert(0, RecentEntry { path: path.to_path_buf(), is_pinned });
+        this.entries.truncate(RECENT_LIMIT);
+        this.save()
+    }
+
+    /// Tells the desktop about a document, on the main thread.
+    pub fn publish(path: &Path) {
+        gtk::RecentManager::default().add_item(&Self::uri(path));
+    }
+
+    /// Tells the desktop a document was renamed, on the main thread.
+    pub fn publish_move(path: &Path, new_path: &Path) {
+        if path != new_path {
+            let _ = gtk::RecentManager::default().move_item(&Self::uri(path), Some(&Self::uri(new_path)));
+        }
+    }
+
+    pub fn set_pinned(path: &Path, is_pinned: bool) -> io::Result<()> {
+        let mut this = Self::load();
+        match this.entries.iter_mut().find(|entry| entry.path == path) {
+            Some(entry) => entry.is_pinned = is_pinned,
+            None => this.entries.push(RecentEntry { path: path.to_path_buf(), is_pinned }),
+        }
+        this.save()
+    }
+
+    /// Drops a document from the list, leaving the file where it is.
+    pub fn forget(path: &Path) -> io::Result<()> {
+        let mut this = Self::load();
+        this.entries.retain(|entry| entry.path != path);
+        let _ = gtk::RecentManager::default().remove_item(&Self::uri(path));
+        this.save()
+    }
+
+    /// Drops a document from the list and moves its file to the trash.
+    pub fn remove(path: &Path) -> io::Result<()> {
+        gio::File::for_path(path)
+            .trash(gio::Cancellable::NONE)
+            .map_err(io::Error::other)?;
+        Self::forget(path)
+    }
+
+    /// Gives a document a new name and a matching file name in the same directory.
+    /// Encrypted documents keep the name stored in them and only change file name.
+    /// Runs on a worker thread; `publish_move` then updates the desktop list.
+    pub fn rename(path: &Path, name: &str) -> io::Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid document name"));
+        }
+        let new_path = path.with_file_name(DataDirectory::file_name(name, StorageFormat::detect(path)));
+        if new_path != path {
+            if new_path.exists() {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, "a document with this name already exists"));
+            }
+            fs::rename(path, &new_path)?;
+
+            let mut this = Self::load();
+            for entry in this.entries.iter_mut().filter(|entry| entry.path == path) {
+                entry.path = new_path.clone();
+            }
+            this.save()?;
+        }
+        let _ = Self::store_name(&new_path, name);
+        Ok(new_path)
+    }
+
+    /// Copies a document next to the original under the first free "copy" name.
+    /// Runs on a worker thread; `publish` then adds the copy to the desktop list.
+    pub fn duplicate(path: &Path) -> io::Result<PathBuf> {
+        let name = Self::display_name(path);
+        let format = StorageFormat::detect(path);
+        let (new_name, new_path) = (1..1000)
+            .map(|count| match count {
+                1 => gettext_f("{name} copy", &[("name", &name)]),
+                count => gettext_f("{name} copy {count}", &[("name", &name), ("count", &count.to_string())]),
+            })
+            .map(|new_name| {
+                let new_path = path.with_file_name(DataDirectory::file_name(&new_name, format));
+                (new_name, new_path)
+            })
+            .find(|(_, new_path)| !new_path.exists())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::AlreadyExists, "could not find a free name"))?;
+        fs::copy(path, &new_path)?;
+        let _ = Self::store_name(&new_path, &new_name);
+        Self::record(&new_path)?;
+        Ok(new_path)
+    }
+
+    /// Documents of the data directory and the recorded ones that still exist,
+    /// pinned first and then by modification time.
+    /// Each document is read once.
+    pub fn list(directory: &DataDirectory) -> Vec<RecentDocument> {
+        let this = Self::load();
+        let mut files = directory.load_valid().unwrap_or_default();
+        for entry in &this.entries {
+            if !files.iter().any(|(file, _)| file.path == entry.path) && entry.path.exists() {
+                let file = DataFile::new(entry.path.clone());
+                let data = file.load().ok();
+                files.push((file, data));
+            }
+        }
+
+        let mut documents: Vec<RecentDocument> = files
+            .into_iter()
+            .map(|(file, data)| {
+                let is_pinned = this.is_pinned(&file.path);
+                RecentDocument::new(file, data, is_pinned, directory)
+            })
+            .collect();
+        documents.sort_by(|a, b| b.is_pinned.cmp(&a.is_pinned).then(b.modified.cmp(&a.modified)));
+        documents
+    }
+
+}
@@ -0,0 +1,72 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+static QUEUED: Mutex<(u64, BTreeMap<PathBuf, u64>)> = Mutex::new((0, BTreeMap::new()));
+static WRITING: Mutex<()> = Mutex::new(());
+static WRITTEN: Condvar = Condvar::new();
+static FAILED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Orders the saves that run on worker threads. Each save gets a number when
+/// it is queued, and only the latest one queued for a file gets written, so an
+/// older version never overwrites a newer one that was saved first.
+pub struct SaveQueue;
+
+impl SaveQueue {
+
+    /// Queues a save of `path`, returning the number to pass to `write`.
+    pub fn enqueue(path: &Path) -> u64 {
+        let mut queued = QUEUED.lock().unwrap_or_else(|e| e.into_inner());
+        queued.0 += 1;
+        let number = queued.0;
+        queued.1.insert(path.to_path_buf(), number);
+        number
+    }
+
+    /// Runs `write` on the calling worker thread, unless a newer save of `path` was queued since.
+    pub fn write<E>(path: &Path, number: u64, write: impl FnOnce() -> Result<(), E>) -> Result<(), E> {
+        let _writing = WRITING.lock().unwrap_or_else(|e| e.into_inner());
+        let is_latest = |queued: &(u64, BTreeMap<PathBuf, u64>)| queued.1.get(path) == Some(&number);
+        if !is_latest(&QUEUED.lock().unwrap_or_else(|e| e.into_inner())) {
+            return Ok(());
+        }
+
+        let result = write();
+
+        let mut queued = QUEUED.lock().unwrap_or_else(|e| e.into_inner());
+        if is_latest(&queued) {
+            queued.1.remove(path);
+            let mut failed = FAILED.lock().unwrap_or_else(|e| e.into_inner());
+            if result.is_ok() {
+                failed.remove(path);
+            } else {
+                failed.insert(path.to_path_buf());
+            }
+        }
+        WRITTEN.notify_all();
+        result
+    }
+
+    /// Whether a save of `path` is still waiting to be written.
+    pub fn is_pending(path: &Path) -> bool {
+        QUEUED.lock().unwrap_or_else(|e| e.into_inner()).1.contains_key(path)
+    }
+
+    /// Whether `path` holds the latest data saved to it: no save is waiting
+    /// and the last one did not fail.
+    pub fn is_written(path: &Path) -> bool {
+        !Self::is_pending(path) && !FAILED.lock().unwrap_or_else(|e| e.into_inner()).contains(path)
+    }
+
+    /// Blocks until every queued save is written, or for a few seconds at most, before the application quits.
+    pub fn wait() {
+        let queued = QUEUED.lock().unwrap_or_else(|e| e.into_inner());
+        let _queued = WRITTEN
+            .wait_timeout_while(queued, WAIT_TIMEOUT, |queued| !queued.1.is_empty())
+            .unwrap_or_else(|e| e.into_inner());
+    }
+
+}
@@ -0,0 +1,107 @@
+use crate::i18n::gettext_noop;
+use crate::prelude::*;
+
+use gtk::gio;
+
+pub const APP_ACTIONS: &str = "app";
+pub const WINDOW_ACTIONS: &str = "window";
+pub const PAGE_ACTIONS: &str = "page";
+
+/// Collects the actions a page or window handles into a group for `insert_action_group`.
+pub fn build_action_group(actions: &[&gio::SimpleAction]) -> gio::SimpleActionGroup {
+    let action_group = gio::SimpleActionGroup::new();
+    for action in actions {
+        action_group.add_action(*action);
+    }
+    action_group
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shortcut {
+    NewWindow,
+    Open,
+    Search,
+    Undo,
+    ShowShortcuts,
+    CommandPalette,
+    Lock,
+    NewWallet,
+    NewTransaction,
+    Save,
+    Delete,
+    SwitchPeriod,
+}
+
+impl Shortcut {
+
+    /// Action group, action name, accelerator, title and group title of every shortcut.
+    /// Application actions are handled by the app, window actions by the pages container
+    /// and page actions by the visible page.
+    const SHORTCUTS: [(&'static str, &'static str, &'static str, &'static str, &'static str); 12] = [
+        (APP_ACTIONS, "new-window", "<Control><Alt>n", gettext_noop("New Window"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "open", "<Control>o", gettext_noop("Open Document"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "search", "<Control>f", gettext_noop("Search Transactions"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "undo", "<Control>z", gettext_noop("Undo"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "show-shortcuts", "<Control>question", gettext_noop("Keyboard Shortcuts"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "command-palette", "<Control>k", gettext_noop("Command Palette"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "lock", "<Control>l", gettext_noop("Lock Document"), gettext_noop("General")),
+        (WINDOW_ACTIONS, "new-wallet", "<Control><Shift>n", gettext_noop("New Wallet"), gettext_noop("Editing")),
+        (PAGE_ACTIONS, "new-transaction", "<Control>n", gettext_noop("New Transaction"), gettext_noop("Editing")),
+        (PAGE_ACTIONS, "save", "<Control>s", gettext_noop("Save"), gettext_noop("Editing")),
+        (PAGE_ACTIONS, "delete", "<Control><Shift>Delete", gettext_noop("Delete"), gettext_noop("Editing")),
+        (PAGE_ACTIONS, "switch-period", "<Control>p", gettext_noop("Switch Period"), gettext_noop("Overview")),
+    ];
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 12] {
+        [
+            Self::NewWindow,
+            Self::Open,
+            Self::Search,
+            Self::Undo,
+            Self::ShowShortcuts,
+            Self::CommandPalette,
+            Self::Lock,
+            Self::NewWallet,
+            Self::NewTransaction,
+            Self::Save,
+            Self::Delete,
+            Self::SwitchPeriod
+        ]
+    }
+
+    #[inline]
+    pub const fn group_name(&self) -> &'static str {
+        Self::SHORTCUTS[*self as usize].0
+    }
+
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        Self::SHORTCUTS[*self as usize].1
+    }
+
+    #[inline]
+    pub const fn accelerator(&self) -> &'static str {
+        Self::SHORTCUTS[*self as usize].2
+    }
+
+    #[inline]
+    pub const fn title(&self) -> &'static str {
+        Self::SHORTCUTS[*self as usize].3
+    }
+
+    #[inline]
+    pub const fn section(&self) -> &'static str {
+        Self::SHORTCUTS[*self as usize].4
+    }
+
+    /// Name used by accelerators and actionable widgets, e.g. `page.save`.
+    pub fn detailed_name(&self) -> String {
+        format!("{}.{}", self.group_name(), self.name())
+    }
+
+    pub fn build_action(&self) -> gio::SimpleAction {
+        gio::SimpleAction::new(self.name(), None)
+    }
+
+}
@@ -0,0 +1,538 @@
+use crate::data::{Cycle, Data, DataError, Transaction, TransactionFilter, Wallet};
+
+use super::storage::Storage;
+
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+const HEADER: &[u8; 16] = b"SQLite format 3\0";
+const APPLICATION_ID_OFFSET: usize = 68;
+/// "FMDB", written to the database header so documents are told apart from other databases.
+const APPLICATION_ID: u32 = 0x464D_4442;
+const SCHEMA_VERSION: u32 = 1;
+const SETTINGS_KEY: &str = "settings";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS document (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS wallets (
+        id INTEGER PRIMARY KEY,
+        position INTEGER NOT NULL,
+        search_name TEXT NOT NULL,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS transactions (
+        wallet_id INTEGER NOT NULL,
+        id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        search_text TEXT NOT NULL,
+        amount REAL NOT NULL,
+        cycle TEXT NOT NULL,
+        start_date TEXT NOT NULL,
+        last_date TEXT,
+        value TEXT NOT NULL,
+        PRIMARY KEY (wallet_id, id)
+    ) WITHOUT ROWID;
+    CREATE INDEX IF NOT EXISTS transactions_start_date ON transactions (start_date);
+    CREATE INDEX IF NOT EXISTS transactions_last_date ON transactions (last_date);
+    CREATE INDEX IF NOT EXISTS transactions_amount ON transactions (amount);
+";
+
+/// Row of the `transactions` table with the columns searches are indexed on,
+/// next to the transaction itself as JSON.
+#[derive(PartialEq)]
+struct TransactionRow {
+    position: i64,
+    search_text: String,
+    amount: f64,
+    cycle: String,
+    start_date: String,
+    last_date: Option<String>,
+    value: String,
+}
+
+impl TransactionRow {
+
+    fn new(position: usize, transaction: &Transaction) -> Result<Self, DataError> {
+        let last_date = match transaction.cycle {
+            Cycle::OneTime => Some(transaction.start_date),
+            _ => transaction.end_date,
+        };
+        Ok(Self {
+            position: position as i64,
+            search_text: format!(
+                "{}\n{}",
+                transaction.name.to_lowercase(),
+                transaction.description.as_deref().unwrap_or_default().to_lowercase()
+            ),
+            amount: transaction.amount,
+            cycle: SqliteStorage::cycle_value(transaction.cycle),
+            start_date: transaction.start_date.format(DATE_FORMAT).to_string(),
+            last_date: last_date.map(|date| date.format(DATE_FORMAT).to_string()),
+            value: SqliteStorage::to_json(transaction)?,
+        })
+    }
+
+}
+
+/// The document in a SQLite database, one row per wallet and per transaction.
+/// Saving reads every row back, compares it with the data and rewrites only
+/// the ones that changed, all in one database transaction.
+pub struct SqliteStorage<'a> {
+    path: &'a Path,
+}
+
+impl<'a> SqliteStorage<'a> {
+
+    pub const fn new(path: &'a Path) -> Self {
+        Self { path }
+    }
+
+    /// Whether the file at `path` is a database written by this application.
+    pub fn is_database(path: &Path) -> bool {
+        let mut header = [0; APPLICATION_ID_OFFSET + 4];
+        let is_read = fs::File::open(path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .is_ok();
+        let mut application_id = [0; 4];
+        application_id.copy_from_slice(&header[APPLICATION_ID_OFFSET..]);
+        is_read && header.starts_with(HEADER) && u32::from_be_bytes(application_id) == APPLICATION_ID
+    }
+
+    fn to_json(value: &impl serde::Serialize) -> Result<String, DataError> {
+        Ok(serde_json::to_string(value)?)
+    }
+
+    fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, DataError> {
+        Ok(serde_json::from_str(value)?)
+    }
+
+    fn cycle_value(cycle: Cycle) -> String {
+        serde_json::to_value(cycle)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_owned))
+            .unwrap_or_default()
+    }
+
+    /// Opens the database, creating the tables in a new one.
+    fn open(&self) -> Result<Connection, DataError> {
+        let connection = Connection::open(self.path)?;
+        let version: u32 = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            ?;
+        match version {
+            SCHEMA_VERSION => {},
+            0 => {
+                connection.execute_batch(SCHEMA)?;
+                connection.pragma_update(None, "application_id", APPLICATION_ID)?;
+                connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            },
+            _ => return Err(io::Error::new(io::ErrorKind::Unsupported, "database was written by a newer version").into()),
+        }
+        Ok(connection)
+    }
+
+    /// The data without its wallets, kept as a single row.
+    fn settings(data: &Data) -> Data {
+        Data {
+            name: data.name.clone(),
+            icon: data.icon,
+            wallets: Vec::new(),
+            currency: data.currency,
+            period: data.period,
+            reminder_days: data.reminder_days,
+            payees: data.payees.clone(),
+            rules: data.rules.clone(),
+        }
+    }
+
+    /// The wallet without its transactions, which have rows of their own.
+    fn wallet_header(wallet: &Wallet) -> Wallet {
+        Wallet {
+            id: wallet.id,
+            name: wallet.name.clone(),
+            description: wallet.description.clone(),
+            currency: wallet.currency,
+            transactions: Vec::new(),
+            opening_balance: wallet.opening_balance,
+            opening_date: wallet.opening_date,
+            assertions: wallet.assertions.clone(),
+        }
+    }
+
+    fn load_wallets(connection: &Connection) -> Result<Vec<Wallet>, DataError> {
+        let mut statement = connection
+            .prepare("SELECT value FROM wallets ORDER BY position")
+            ?;
+        let values = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            ?;
+        values
+            .map(|value| Self::from_json(&value?))
+            .collect()
+    }
+
+    fn load_transactions(connection: &Connection, wallets: &mut [Wallet]) -> Result<(), DataError> {
+        let positions: HashMap<usize, usize> = wallets
+            .iter()
+            .enumerate()
+            .map(|(position, wallet)| (wallet.id, position))
+            .collect();
+        let mut statement = connection
+            .prepare("SELECT wallet_id, value FROM transactions ORDER BY wallet_id, position")
+            ?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            ?;
+        for row in rows {
+            let (wallet_id, value) = row?;
+            let Some(&position) = positions.get(&(wallet_id as usize)) else { continue };
+            wallets[position].transactions.push(Self::from_json(&value)?);
+        }
+        Ok(())
+    }
+
+    fn save_settings(transaction: &rusqlite::Transaction, data: &Data) -> Result<(), DataError> {
+        let settings = Self::to_json(&Self::settings(data))?;
+        transaction
+            .execute(
+                "INSERT INTO document (key, value) VALUES (?1, ?2)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value WHERE value <> excluded.value",
+                params![SETTINGS_KEY, settings],
+            )
+            ?;
+        Ok(())
+    }
+
+    fn save_wallets(transaction: &rusqlite::Transaction, data: &Data) -> Result<(), DataError> {
+        let mut saved: HashMap<i64, (i64, String)> = HashMap::new();
+        {
+            let mut statement = transaction
+                .prepare("SELECT id, position, value FROM wallets")
+                ?;
+            let rows = statement
+                .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+                ?;
+            for row in rows {
+                let (id, row) = row?;
+                saved.insert(id, row);
+            }
+        }
+
+        let mut upsert = transaction
+            .prepare_cached(
+                "INSERT OR REPLACE INTO wallets (id, position, search_name, value) VALUES (?1, ?2, ?3, ?4)"
+            )
+            ?;
+        for (position, wallet) in data.wallets.iter().enumerate() {
+            let id = wallet.id as i64;
+            let row = (position as i64, Self::to_json(&Self::wallet_header(wallet))?);
+            if saved.remove(&id).as_ref() != Some(&row) {
+                upsert
+                    .execute(params![id, row.0, wallet.name.to_lowercase(), row.1])
+                    ?;
+            }
+        }
+        for id in saved.keys() {
+            transaction
+                .execute("DELETE FROM wallets WHERE id = ?1", [id])
+                ?;
+        }
+        Ok(())
+    }
+
+    fn save_transactions(transaction: &rusqlite::Transaction, data: &Data) -> Result<(), DataError> {
+        let mut saved: HashMap<(i64, i64), TransactionRow> = HashMap::new();
+        {
+            let mut statement = transaction
+                .prepare(
+                    "SELECT wallet_id, id, position, search_text, amount, cycle, start_date, last_date, value
+                     FROM transactions"
+                )
+                ?;
+            let rows = statement
+                .query_map([], |row| Ok((
+                    (row.get(0)?, row.get(1)?),
+                    TransactionRow {
+                        position: row.get(2)?,
+                        search_text: row.get(3)?,
+                        amount: row.get(4)?,
+                        cycle: row.get(5)?,
+                        start_date: row.get(6)?,
+                        last_date: row.get(7)?,
+                        value: row.get(8)?,
+                    }
+                )))
+                ?;
+            for row in rows {
+                let (key, row) = row?;
+                saved.insert(key, row);
+            }
+        }
+
+        let mut upsert = transaction
+            .prepare_cached(
+                "INSERT OR REPLACE INTO transactions
+                 (wallet_id, id, position, search_text, amount, cycle, start_date, last_date, value)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+            )
+            ?;
+        let mut written = HashSet::new();
+        for wallet in &data.wallets {
+            for (position, item) in wallet.transactions.iter().enumerate() {
+                let key = (wallet.id as i64, item.id as i64);
+                if !written.insert(key) {
+                    return Err(DataError::validation(format!("wallets[{}].transactions[{}]", wallet.id, item.id)));
+                }
+                let row = TransactionRow::new(position, item)?;
+                if saved.remove(&key).as_ref() == Some(&row) {
+                    continue;
+                }
+                upsert
+                    .execute(params![
+                        key.0, key.1, row.position, row.search_text, row.amount,
+                        row.cycle, row.start_date, row.last_date, row.value
+                    ])
+                    ?;
+            }
+        }
+        for (wallet_id, id) in saved.keys() {
+            transaction
+                .execute("DELETE FROM transactions WHERE wallet_id = ?1 AND id = ?2", [wallet_id, id])
+                ?;
+        }
+        Ok(())
+    }
+
+    /// Builds the conditions of `filter` over the indexed columns, with their parameters.
+    /// Dates only narrow the results to transactions running during the range,
+    /// so `TransactionFilter::matches` still has to check they occur in it.
+    fn build_search_query(filter: &TransactionFilter) -> (String, Vec<Value>) {
+        let mut conditions = vec!["1".to_owned()];
+        let mut values = Vec::new();
+        for term in &filter.terms {
+            conditions.push("instr(t.search_text, ?) > 0".to_owned());
+            values.push(Value::Text(term.clone()));
+        }
+        if let Some(min) = filter.min_amount {
+            conditions.push("abs(t.amount) >= ?".to_owned());
+            values.push(Value::Real(min));
+        }
+        if let Some(max) = filter.max_amount {
+            conditions.push("abs(t.amount) <= ?".to_owned());
+            values.push(Value::Real(max));
+        }
+        if let Some(end) = filter.end_date {
+            conditions.push("t.start_date <= ?".to_owned());
+            values.push(Value::Text(end.format(DATE_FORMAT).to_string()));
+        }
+        if let Some(start) = filter.start_date {
+            conditions.push("(t.last_date IS NULL OR t.last_date >= ?)".to_owned());
+            values.push(Value::Text(start.format(DATE_FORMAT).to_string()));
+        }
+        if let Some(cycle) = filter.cycle {
+            conditions.push("t.cycle = ?".to_owned());
+            values.push(Value::Text(Self::cycle_value(cycle)));
+        }
+        if let Some(wallet) = &filter.wallet {
+            conditions.push("instr(w.search_name, ?) > 0".to_owned());
+            values.push(Value::Text(wallet.clone()));
+        }
+        let query = format!(
+            "SELECT t.wallet_id, t.id FROM transactions t JOIN wallets w ON w.id = t.wallet_id WHERE {}",
+            conditions.join(" AND ")
+        );
+        (query, values)
+    }
+
+    fn query(&self, filter: &TransactionFilter) -> Result<Vec<(usize, usize)>, DataError> {
+        if !self.path.exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "database does not exist").into());
+        }
+        let connection = self.open()?;
+        let (query, values) = Self::build_search_query(filter);
+        let mut statement = connection.prepare(&query)?;
+        let rows = statement
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize))
+            })
+            ?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+}
+
+impl Storage for SqliteStorage<'_> {
+
+    fn load(&self) -> Result<Data, DataError> {
+        if !self.path.exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "database does not exist").into());
+        }
+        let connection = self.open()?;
+        let settings: Option<String> = connection
+            .query_row("SELECT value FROM document WHERE key = ?1", [SETTINGS_KEY], |row| row.get(0))
+            .optional()
+            ?;
+        let Some(settings) = settings else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "database holds no document").into());
+        };
+        let mut data: Data = Self::from_json(&settings)?;
+        data.wallets = Self::load_wallets(&connection)?;
+        Self::load_transactions(&connection, &mut data.wallets)?;
+        Ok(data)
+    }
+
+    fn save(&self, data: &Data) -> Result<(), DataError> {
+        let mut connection = self.open()?;
+        let transaction = connection.transaction()?;
+        Self::save_settings(&transaction, data)?;
+        Self::save_wallets(&transaction, data)?;
+        Self::save_transactions(&transaction, data)?;
+        Ok(transaction.commit()?)
+    }
+
+    fn search(&self, filter: &TransactionFilter) -> Option<Vec<(usize, usize)>> {
+        self.query(filter).ok()
+    }
+
+}
+
+impl From<rusqlite::Error> for DataError {
+    fn from(e: rusqlite::Error) -> Self {
+        DataError::Database(Box::new(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Split;
+    use chrono::NaiveDate;
+    use std::path::PathBuf;
+
+    /// A database path of its own for each test, removed when dropped.
+    struct TestPath(PathBuf);
+
+    impl TestPath {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("sqlite-storage-{}-{}.sqlite", name, std::process::id()));
+            let _ = fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TestPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, DATE_FORMAT).unwrap()
+    }
+
+    fn transaction(id: usize, name: &str, amount: f64, cycle: Cycle) -> Transaction {
+        Transaction {
+            id,
+            name: name.to_owned(),
+            amount,
+            cycle,
+            start_date: date("2024-01-01"),
+            ..Transaction::default()
+        }
+    }
+
+    fn data() -> Data {
+        let mut groceries = transaction(3, "Market", -80.0, Cycle::Weekly);
+        groceries.description = Some("Weekly groceries".to_owned());
+        groceries.end_date = Some(date("2024-06-30"));
+        groceries.splits = vec![
+            Split { amount: -60.0, category: "Food".to_owned(), memo: None },
+            Split { amount: -20.0, category: "Home".to_owned(), memo: Some("Soap".to_owned()) },
+        ];
+        Data {
+            name: "Household".to_owned(),
+            wallets: vec![
+                Wallet {
+                    id: 2,
+                    name: "Checking".to_owned(),
+                    opening_balance: 250.0,
+                    transactions: vec![
+                        transaction(1, "Salary", 1500.0, Cycle::Monthly),
+                        groceries,
+                    ],
+                    ..Wallet::default()
+                },
+                Wallet {
+                    id: 1,
+                    name: "Cash".to_owned(),
+                    transactions: vec![transaction(1, "Coffee", -3.0, Cycle::OneTime)],
+                    ..Wallet::default()
+                },
+            ],
+            ..Data::default()
+        }
+    }
+
+    #[test]
+    fn saved_document_loads_back_unchanged() {
+        let path = TestPath::new("round-trip");
+        let storage = SqliteStorage::new(&path.0);
+        let data = data();
+
+        storage.save(&data).unwrap();
+
+        assert!(SqliteStorage::is_database(&path.0));
+        assert_eq!(storage.load().unwrap(), data);
+    }
+
+    #[test]
+    fn saving_again_updates_moves_and_removes_rows() {
+        let path = TestPath::new("update");
+        let storage = SqliteStorage::new(&path.0);
+        let mut data = data();
+        storage.save(&data).unwrap();
+
+        data.name = "Home".to_owned();
+        data.wallets.swap(0, 1);
+        data.wallets[1].transactions.swap(0, 1);
+        data.wallets[1].transactions[0].amount = -90.0;
+        data.wallets[0].transactions.clear();
+        storage.save(&data).unwrap();
+
+        assert_eq!(storage.load().unwrap(), data);
+    }
+
+    #[test]
+    fn search_uses_the_saved_rows() {
+        let path = TestPath::new("search");
+        let storage = SqliteStorage::new(&path.0);
+        storage.save(&data()).unwrap();
+        let search = |query: &str| {
+            let mut ids = storage.search(&query.parse().unwrap()).unwrap();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(search("groceries"), vec![(2, 3)]);
+        assert_eq!(search("amount:>50"), vec![(2, 1), (2, 3)]);
+        assert_eq!(search("wallet:cash"), vec![(1, 1)]);
+        assert_eq!(search("from:2024-07-01 cycle:weekly"), Vec::<(usize, usize)>::new());
+    }
+
+    #[test]
+    fn missing_database_is_not_loaded() {
+        let path = TestPath::new("missing");
+        let storage = SqliteStorage::new(&path.0);
+
+        assert!(storage.load().is_err());
+        assert_eq!(storage.search(&TransactionFilter::default()), None);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::data::{Data, DataError, TransactionFilter};
+use crate::i18n::gettext_noop;
+
+use super::sqlite_storage::SqliteStorage;
+
+use std::path::Path;
+
+/// Where the data of a document is read from and written to.
+pub trait Storage {
+    fn load(&self) -> Result<Data, DataError>;
+
+    fn save(&self, data: &Data) -> Result<(), DataError>;
+
+    /// Ids of the wallets and transactions that may match `filter`, for storages
+    /// that can answer from an index. `None` leaves the search to `Data`.
+    fn search(&self, _filter: &TransactionFilter) -> Option<Vec<(usize, usize)>> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StorageFormat {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+impl StorageFormat {
+
+    const FORMATS: [&'static str; 2] = [
+        gettext_noop("JSON File"),
+        gettext_noop("SQLite Database"),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::FORMATS[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 2] {
+        [
+            Self::Json,
+            Self::Sqlite
+        ]
+    }
+
+    #[inline]
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Sqlite => "sqlite",
+        }
+    }
+
+    /// Format of the file at `path`, told by its header; new files go by their extension.
+    pub fn detect(path: &Path) -> Self {
+        let is_sqlite = if path.exists() {
+            SqliteStorage::is_database(path)
+        } else {
+            path.extension().is_some_and(|ext| ext == Self::Sqlite.extension())
+        };
+        if is_sqlite { Self::Sqlite } else { Self::Json }
+    }
+
+}
@@ -9,35 +9,12 @@ pub struct Balance {
 impl Balance {
 
     pub fn format_value(value: f64, prefix_sign: bool) -> String {
-        let prefix = match (value >= 0.0, prefix_sign) {
-            (true, true) => "+",
-            (false, true) => "-",
-            _ => ""
-        };
-        let abs_value = value.abs();
-        
-        let string = format!("{:.2}", abs_value);
-        let parts: Vec<&str> = string.split('.').collect();
-        let int_part = parts[0];
-        let dec_part = parts[1];
-        
-        let with_separators: String = int_part
-            .chars()
-            .rev()
-            .enumerate()
-            .flat_map(|(i, c)| {
-                if i > 0 && i % 3 == 0 {
-                    vec![' ', c]
-                } else {
-                    vec![c]
-                }
-            })
-            .collect::<String>()
-            .chars()
-            .rev()
-            .collect();
-        
-        format!("{}{}.{}", prefix, with_separators, dec_part)
+        let locale = Preferences::current().locale;
+        match (value >= 0.0, prefix_sign) {
+            (true, true) => format!("+{}", locale.format_number(value, 2)),
+            (false, true) => locale.format_number(value, 2),
+            _ => locale.number_format.format(value, 2)
+        }
     }
 
     pub fn from_transactions(transactions: &[&Transaction]) -> Self {
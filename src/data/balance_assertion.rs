@@ -0,0 +1,28 @@
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BalanceAssertion {
+    pub date: NaiveDate,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Discrepancy {
+    pub assertion: BalanceAssertion,
+    pub computed_balance: f64,
+}
+
+impl Discrepancy {
+
+    #[inline]
+    pub fn difference(&self) -> f64 {
+        self.assertion.balance - self.computed_balance
+    }
+
+    #[inline]
+    pub fn is_balanced(&self) -> bool {
+        self.difference().abs() < 0.005
+    }
+
+}
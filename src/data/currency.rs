@@ -1,10 +1,71 @@
+use super::{DataError, Preferences};
 use serde::{Serialize, Deserialize};
 
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::Mutex;
 use serde_json::Value;
+use crate::i18n::{gettext_f, gettext_noop};
 
-static EXCHANGE_RATES: LazyLock<[f64; Currency::count()]> = LazyLock::new(|| fetch_exchange_rates().expect("Failed to fetch exchange rates"));
+type ExchangeRates = [f64; Currency::count()];
+
+static EXCHANGE_RATES: Mutex<Option<(RateProvider, ExchangeRates)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RateProvider {
+    #[default]
+    Frankfurter,
+    OpenExchangeRates,
+}
+
+impl RateProvider {
+
+    const PROVIDERS: [(&'static str, &'static str); 2] = [
+        ("Frankfurter", "https://api.frankfurter.app/latest"),
+        ("ExchangeRate-API", "https://open.er-api.com/v6/latest/EUR"),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::PROVIDERS[*self as usize].0
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 2] {
+        [
+            Self::Frankfurter,
+            Self::OpenExchangeRates
+        ]
+    }
+
+    #[inline]
+    const fn url(&self) -> &'static str {
+        Self::PROVIDERS[*self as usize].1
+    }
+
+    /// Whether the rates of this provider were fetched already.
+    pub fn has_rates(&self) -> bool {
+        EXCHANGE_RATES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|(provider, _)| provider == self)
+    }
+
+    /// Fetches the rates of this provider over the network, blocking until they are in.
+    /// Amounts in other currencies can't be converted before that.
+    pub fn load_rates(&self) -> Result<(), DataError> {
+        let rates = fetch_exchange_rates(*self).map_err(|e| DataError::RateUnavailable {
+            provider: *self,
+            message: e.to_string(),
+        })?;
+        EXCHANGE_RATES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace((*self, rates));
+        Ok(())
+    }
+
+}
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Currency {
@@ -18,11 +79,11 @@ pub enum Currency {
 impl Currency {
 
     const CURRENCIES: [(&'static str, &'static str, &'static str); 5] = [
-        ("$", "USD", "US Dollar"),
-        ("€", "EUR", "Euro"),
-        ("$", "CAD", "Canadian Dollar"),
-        ("£", "GBP", "Pound Sterling"),
-        ("¥", "JPY", "Japanese Yen")
+        ("$", "USD", gettext_noop("US Dollar")),
+        ("€", "EUR", gettext_noop("Euro")),
+        ("$", "CAD", gettext_noop("Canadian Dollar")),
+        ("£", "GBP", gettext_noop("Pound Sterling")),
+        ("¥", "JPY", gettext_noop("Japanese Yen"))
     ];
 
     #[inline]
@@ -65,38 +126,10 @@ impl Currency {
             return String::from("N/A");
         }
         
-        let prefix = if value < 0.0 { "-" } else { "" };
-        let abs_value = value.abs();
-        
-        let (int_part, dec_part) = if *self == Currency::JPY {
-            let rounded = abs_value.round();
-            (rounded.to_string(), None)
-        } else {
-            let formatted = format!("{:.2}", abs_value);
-            let parts: Vec<&str> = formatted.split('.').collect();
-            (parts[0].to_string(), Some(parts[1].to_string()))
-        };
-
-        let with_separators: String = int_part
-            .chars()
-            .rev()
-            .enumerate()
-            .flat_map(|(i, c)| {
-                if i > 0 && i % 3 == 0 {
-                    vec![' ', c]
-                } else {
-                    vec![c]
-                }
-            })
-            .collect::<String>()
-            .chars()
-            .rev()
-            .collect();
-
-        match dec_part {
-            Some(dec) => format!("{}{}{}.{}", prefix, self.as_symbol(), with_separators, dec),
-            None => format!("{}{}", self.as_symbol(), with_separators)
-        }
+        let decimals = if *self == Currency::JPY { 0 } else { 2 };
+        Preferences::current()
+            .locale
+            .format_amount(value, decimals, self.as_symbol())
     }
 
     pub fn normalize_amount(&self, amount: f64) -> f64 {
@@ -109,23 +142,35 @@ impl Currency {
         }
     }
 
-    fn get_exchange_rate(&self) -> f64 {
+    fn get_exchange_rate(&self) -> Option<f64> {
         if *self == Self::EUR {
-            1.0
-        } else {
-            EXCHANGE_RATES[*self as usize]
+            return Some(1.0);
+        }
+
+        let provider = Preferences::current().rate_provider;
+        match EXCHANGE_RATES.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            Some((cached_provider, rates)) if *cached_provider == provider => Some(rates[*self as usize]).filter(|rate| *rate > 0.0),
+            _ => None,
         }
     }
 
-    pub fn convert_amount(&self, amount: f64, to: Currency) -> f64 {
+    /// Converts `amount` with the fetched rates, or gives `None` while they are not loaded.
+    pub fn convert_amount(&self, amount: f64, to: Currency) -> Option<f64> {
         if *self == to || !amount.is_finite() {
-            return amount;
+            return Some(amount);
+        }
+
+        let (origin_rate, target_rate) = (self.get_exchange_rate()?, to.get_exchange_rate()?);
+        Some(self.normalize_amount(amount * (target_rate / origin_rate)))
+    }
+
+    /// Formats a total, marking it when some of its amounts could not be converted.
+    pub fn format_total(&self, value: f64, is_converted: bool) -> String {
+        if is_converted {
+            self.format_amount(value)
+        } else {
+            gettext_f("{amount} (not converted)", &[("amount", &self.format_amount(value))])
         }
-        
-        let origin_rate = self.get_exchange_rate();
-        let target_rate = to.get_exchange_rate();
-        
-        self.normalize_amount(amount * (target_rate / origin_rate))
     }
 }
 
@@ -156,8 +201,8 @@ impl std::str::FromStr for Currency {
     }
 }
 
-fn fetch_exchange_rates() -> Result<[f64; Currency::count()], Box<dyn std::error::Error>> {
-    let response = ureq::get("https://api.frankfurter.app/latest")
+fn fetch_exchange_rates(provider: RateProvider) -> Result<ExchangeRates, Box<dyn std::error::Error>> {
+    let response = ureq::get(provider.url())
         .call()?
         .into_string()?;
     
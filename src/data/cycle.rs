@@ -1,5 +1,6 @@
 use chrono::{Duration, Months, NaiveDate};
 use serde::{Serialize, Deserialize};
+use crate::i18n::{gettext, gettext_noop};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -14,11 +15,11 @@ pub enum Cycle {
 impl Cycle {
 
     const CYCLES: [&'static str; 5] = [
-        "Daily",
-        "Weekly",
-        "Monthly",
-        "Yearly",
-        "One Time"
+        gettext_noop("Daily"),
+        gettext_noop("Weekly"),
+        gettext_noop("Monthly"),
+        gettext_noop("Yearly"),
+        gettext_noop("One Time")
     ];
 
     #[inline]
@@ -72,7 +73,6 @@ impl Default for Cycle {
 
 impl std::fmt::Display for Cycle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = self.as_str();
-        write!(f, "{}", s)
+        write!(f, "{}", gettext(self.as_str()))
     }
 }
\ No newline at end of file
@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+use crate::i18n::{gettext, gettext_noop};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentIcon {
+    #[default]
+    Document,
+    Personal,
+    Household,
+    Business,
+    Savings,
+    Travel,
+}
+
+impl DocumentIcon {
+
+    const ICONS: [&'static str; 6] = [
+        gettext_noop("Document"),
+        gettext_noop("Personal"),
+        gettext_noop("Household"),
+        gettext_noop("Business"),
+        gettext_noop("Savings"),
+        gettext_noop("Travel")
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::ICONS[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 6] {
+        [
+            Self::Document,
+            Self::Personal,
+            Self::Household,
+            Self::Business,
+            Self::Savings,
+            Self::Travel
+        ]
+    }
+
+    #[inline]
+    pub const fn icon_name(&self) -> &'static str {
+        match self {
+            Self::Document => "x-office-document-symbolic",
+            Self::Personal => "avatar-default-symbolic",
+            Self::Household => "user-home-symbolic",
+            Self::Business => "applications-office-symbolic",
+            Self::Savings => "starred-symbolic",
+            Self::Travel => "airplane-mode-symbolic",
+        }
+    }
+
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+}
+
+impl std::fmt::Display for DocumentIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", gettext(self.as_str()))
+    }
+}
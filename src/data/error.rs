@@ -0,0 +1,102 @@
+use super::RateProvider;
+use crate::i18n::gettext_f;
+
+use std::io;
+
+/// Why a document could not be read, written or shown.
+#[derive(Debug)]
+pub enum DataError {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// The database of a document could not be read or written.
+    Database(Box<dyn std::error::Error + Send + Sync>),
+    /// The content is not a document; `line` and `column` count from 1.
+    Parse { line: usize, column: usize, message: String },
+    /// The document holds a value that can't be right, in `field`.
+    Validation { field: String },
+    /// Exchange rates could not be fetched from `provider`.
+    RateUnavailable { provider: RateProvider, message: String },
+}
+
+impl DataError {
+
+    pub fn validation(field: impl Into<String>) -> Self {
+        DataError::Validation {
+            field: field.into()
+        }
+    }
+
+    /// Kind of the underlying I/O error, such as `PermissionDenied` for a wrong passphrase.
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            DataError::Io(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+
+    /// The error in the language of the user, to show it. `Display` stays in
+    /// English, so logs and copied reports can be read by anyone.
+    pub fn localized(&self) -> String {
+        match self {
+            DataError::Io(e) => gettext_f("Could not access the file: {error}", &[("error", &e.to_string())]),
+            DataError::Database(e) => gettext_f("Could not access the database: {error}", &[("error", &e.to_string())]),
+            DataError::Parse { line, column, message } => gettext_f(
+                "Invalid document at line {line}, column {column}: {error}",
+                &[("line", &line.to_string()), ("column", &column.to_string()), ("error", message)]
+            ),
+            DataError::Validation { field } => gettext_f("Invalid value in {field}", &[("field", field)]),
+            DataError::RateUnavailable { provider, message } => gettext_f(
+                "Exchange rates from {provider} are unavailable: {error}",
+                &[("provider", provider.as_str()), ("error", message)]
+            ),
+        }
+    }
+
+}
+
+impl std::fmt::Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataError::Io(e) => write!(f, "Could not access the file: {}", e),
+            DataError::Database(e) => write!(f, "Could not access the database: {}", e),
+            DataError::Parse { line, column, message } => write!(f, "Invalid document at line {}, column {}: {}", line, column, message),
+            DataError::Validation { field } => write!(f, "Invalid value in {}", field),
+            DataError::RateUnavailable { provider, message } => write!(f, "Exchange rates from {} are unavailable: {}", provider.as_str(), message),
+        }
+    }
+}
+
+impl std::error::Error for DataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DataError::Io(e) => Some(e),
+            DataError::Database(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DataError {
+    fn from(e: io::Error) -> Self {
+        DataError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for DataError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            return DataError::Io(e.into());
+        }
+        let message = e.to_string();
+        let message = message
+            .split(" at line ")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        DataError::Parse {
+            line: e.line(),
+            column: e.column(),
+            message,
+        }
+    }
+}
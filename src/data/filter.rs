@@ -0,0 +1,212 @@
+use super::*;
+use chrono::NaiveDate;
+
+/// A parsed search query such as `rent amount:>100 cycle:monthly wallet:cash from:2024-01-01`.
+/// Free words must all appear in the transaction name or description.
+/// Amounts are matched by size, so `amount:>100` finds both income and expenses above 100.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionFilter {
+    pub terms: Vec<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub cycle: Option<Cycle>,
+    pub wallet: Option<String>,
+}
+
+impl TransactionFilter {
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn parse_date(input: &str) -> Result<NaiveDate, ()> {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| ())
+    }
+
+    fn parse_number(input: &str) -> Result<f64, ()> {
+        input.parse::<f64>().ok().filter(|n| n.is_finite()).ok_or(())
+    }
+
+    fn parse_cycle(input: &str) -> Result<Cycle, ()> {
+        let normalize = |s: &str| s.to_lowercase().replace([' ', '-', '_'], "");
+        let input = normalize(input);
+        Cycle::as_slice()
+            .into_iter()
+            .find(|cycle| normalize(cycle.as_str()) == input || normalize(&cycle.to_string()) == input)
+            .ok_or(())
+    }
+
+    fn parse_amount(&mut self, input: &str) -> Result<(), ()> {
+        if let Some((min, max)) = input.split_once("..") {
+            self.min_amount = (!min.is_empty()).then(|| Self::parse_number(min)).transpose()?;
+            self.max_amount = (!max.is_empty()).then(|| Self::parse_number(max)).transpose()?;
+        } else if let Some(value) = input.strip_prefix(">=").or_else(|| input.strip_prefix('>')) {
+            self.min_amount = Some(Self::parse_number(value)?);
+        } else if let Some(value) = input.strip_prefix("<=").or_else(|| input.strip_prefix('<')) {
+            self.max_amount = Some(Self::parse_number(value)?);
+        } else {
+            let value = Self::parse_number(input.strip_prefix('=').unwrap_or(input))?;
+            self.min_amount = Some(value);
+            self.max_amount = Some(value);
+        }
+        Ok(())
+    }
+
+    fn matches_text(&self, transaction: &Transaction) -> bool {
+        let name = transaction.name.to_lowercase();
+        let description = transaction.description.as_deref().unwrap_or_default().to_lowercase();
+        self.terms
+            .iter()
+            .all(|term| name.contains(term) || description.contains(term))
+    }
+
+    fn matches_amount(&self, amount: f64) -> bool {
+        let amount = amount.abs();
+        self.min_amount.map_or(true, |min| amount >= min)
+            && self.max_amount.map_or(true, |max| amount <= max)
+    }
+
+    /// Whether the transaction occurs at least once between the dates, both included.
+    fn matches_dates(&self, transaction: &Transaction) -> bool {
+        let start = self.start_date.unwrap_or(transaction.start_date);
+        match self.end_date {
+            Some(end) => !transaction.occurrences_between(start, end).is_empty(),
+            None => transaction.next_occurrence(start).is_some(),
+        }
+    }
+
+    pub fn matches_wallet(&self, wallet: &Wallet) -> bool {
+        self.wallet
+            .as_ref()
+            .map_or(true, |name| wallet.name.to_lowercase().contains(name))
+    }
+
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        self.matches_text(transaction)
+            && self.matches_amount(transaction.amount)
+            && self.matches_dates(transaction)
+            && self.cycle.map_or(true, |cycle| transaction.cycle == cycle)
+    }
+
+}
+
+impl std::str::FromStr for TransactionFilter {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut filter = TransactionFilter::default();
+        for token in input.split_whitespace() {
+            let Some((key, value)) = token.split_once(':') else {
+                filter.terms.push(token.to_lowercase());
+                continue;
+            };
+            match key.to_lowercase().as_str() {
+                "amount" => filter.parse_amount(value)?,
+                "from" => filter.start_date = Some(Self::parse_date(value)?),
+                "to" => filter.end_date = Some(Self::parse_date(value)?),
+                "cycle" => filter.cycle = Some(Self::parse_cycle(value)?),
+                "wallet" if !value.is_empty() => filter.wallet = Some(value.to_lowercase()),
+                _ => return Err(()),
+            }
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d").unwrap()
+    }
+
+    fn transaction(name: &str, amount: f64, cycle: Cycle, start_date: &str) -> Transaction {
+        Transaction {
+            name: name.to_owned(),
+            amount,
+            cycle,
+            start_date: date(start_date),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn query_is_parsed_into_criteria() {
+        let filter: TransactionFilter = "Rent amount:100..500 from:2024-01-01 to:2024-12-31 cycle:monthly wallet:Cash"
+            .parse()
+            .unwrap();
+
+        assert_eq!(filter, TransactionFilter {
+            terms: vec!["rent".to_owned()],
+            min_amount: Some(100.0),
+            max_amount: Some(500.0),
+            start_date: Some(date("2024-01-01")),
+            end_date: Some(date("2024-12-31")),
+            cycle: Some(Cycle::Monthly),
+            wallet: Some("cash".to_owned()),
+        });
+    }
+
+    #[test]
+    fn amount_bounds_are_parsed() {
+        let parse = |input: &str| input.parse::<TransactionFilter>().map(|f| (f.min_amount, f.max_amount));
+
+        assert_eq!(parse("amount:>100"), Ok((Some(100.0), None)));
+        assert_eq!(parse("amount:>=100"), Ok((Some(100.0), None)));
+        assert_eq!(parse("amount:<=50.5"), Ok((None, Some(50.5))));
+        assert_eq!(parse("amount:=20"), Ok((Some(20.0), Some(20.0))));
+        assert_eq!(parse("amount:20"), Ok((Some(20.0), Some(20.0))));
+        assert_eq!(parse("amount:..20"), Ok((None, Some(20.0))));
+        assert_eq!(parse("amount:10.."), Ok((Some(10.0), None)));
+    }
+
+    #[test]
+    fn invalid_criteria_are_rejected() {
+        for input in ["amount:abc", "amount:>inf", "from:2024-13-01", "to:yesterday", "cycle:hourly", "color:red"] {
+            assert_eq!(input.parse::<TransactionFilter>(), Err(()), "{}", input);
+        }
+        assert_eq!("one-time".parse::<TransactionFilter>().map(|f| f.terms), Ok(vec!["one-time".to_owned()]));
+        assert_eq!("cycle:one-time".parse::<TransactionFilter>().map(|f| f.cycle), Ok(Some(Cycle::OneTime)));
+    }
+
+    #[test]
+    fn amounts_are_matched_by_size() {
+        let filter: TransactionFilter = "amount:>100".parse().unwrap();
+        assert!(filter.matches(&transaction("Rent", -150.0, Cycle::OneTime, "2024-01-01")));
+        assert!(filter.matches(&transaction("Salary", 150.0, Cycle::OneTime, "2024-01-01")));
+        assert!(!filter.matches(&transaction("Coffee", -3.0, Cycle::OneTime, "2024-01-01")));
+
+        let filter: TransactionFilter = "amount:50".parse().unwrap();
+        assert!(filter.matches(&transaction("Groceries", -50.0, Cycle::OneTime, "2024-01-01")));
+    }
+
+    #[test]
+    fn dates_match_occurrences() {
+        let rent = transaction("Rent", -500.0, Cycle::Monthly, "2024-01-01");
+
+        let filter: TransactionFilter = "from:2024-03-05 to:2024-03-20".parse().unwrap();
+        assert!(!filter.matches(&rent));
+
+        let filter: TransactionFilter = "from:2024-03-01 to:2024-03-01".parse().unwrap();
+        assert!(filter.matches(&rent));
+
+        let filter: TransactionFilter = "from:2030-06-02".parse().unwrap();
+        assert!(filter.matches(&rent));
+        assert!(!filter.matches(&Transaction { end_date: Some(date("2024-12-31")), ..rent.clone() }));
+
+        let filter: TransactionFilter = "to:2023-12-31".parse().unwrap();
+        assert!(!filter.matches(&rent));
+    }
+
+    #[test]
+    fn one_time_transactions_match_only_their_date() {
+        let refund = transaction("Refund", 20.0, Cycle::OneTime, "2024-05-10");
+
+        assert!("from:2024-05-10 to:2024-05-10".parse::<TransactionFilter>().unwrap().matches(&refund));
+        assert!(!"from:2024-05-11".parse::<TransactionFilter>().unwrap().matches(&refund));
+        assert!(!"to:2024-05-09".parse::<TransactionFilter>().unwrap().matches(&refund));
+    }
+}
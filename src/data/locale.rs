@@ -0,0 +1,474 @@
+use chrono::{Datelike, NaiveDate};
+use crate::i18n::{gettext, gettext_f, gettext_noop};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NumberFormat {
+    #[default]
+    SpaceDot,
+    CommaDot,
+    DotComma,
+    SpaceComma,
+}
+
+impl NumberFormat {
+
+    const FORMATS: [(&'static str, char, char); 4] = [
+        ("1 234.56", ' ', '.'),
+        ("1,234.56", ',', '.'),
+        ("1.234,56", '.', ','),
+        ("1 234,56", ' ', ','),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::FORMATS[*self as usize].0
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 4] {
+        [
+            Self::SpaceDot,
+            Self::CommaDot,
+            Self::DotComma,
+            Self::SpaceComma
+        ]
+    }
+
+    #[inline]
+    const fn group_separator(&self) -> char {
+        Self::FORMATS[*self as usize].1
+    }
+
+    #[inline]
+    const fn decimal_separator(&self) -> char {
+        Self::FORMATS[*self as usize].2
+    }
+
+    /// Formats the absolute value with grouped thousands and `decimals` digits.
+    pub fn format(&self, value: f64, decimals: usize) -> String {
+        let string = format!("{:.*}", decimals, value.abs());
+        let (int_part, dec_part) = match string.split_once('.') {
+            Some((int_part, dec_part)) => (int_part, Some(dec_part)),
+            None => (string.as_str(), None),
+        };
+
+        let with_separators: String = int_part
+            .chars()
+            .rev()
+            .enumerate()
+            .flat_map(|(i, c)| {
+                if i > 0 && i % 3 == 0 {
+                    vec![self.group_separator(), c]
+                } else {
+                    vec![c]
+                }
+            })
+            .collect::<String>()
+            .chars()
+            .rev()
+            .collect();
+
+        match dec_part {
+            Some(dec) => format!("{}{}{}", with_separators, self.decimal_separator(), dec),
+            None => with_separators
+        }
+    }
+
+    /// The format of a locale with these separators, such as those of `localeconv`.
+    pub fn from_separators(group: &str, decimal: &str) -> Option<Self> {
+        match (decimal, group) {
+            (",", ".") => Some(Self::DotComma),
+            (",", _) => Some(Self::SpaceComma),
+            (".", ",") => Some(Self::CommaDot),
+            (".", _) => Some(Self::SpaceDot),
+            _ => None,
+        }
+    }
+
+    /// The digits of `integer` when its group separators, if any, split it into groups of three.
+    fn ungroup(integer: &str, group: char) -> Option<String> {
+        if !integer.contains(group) {
+            return Some(integer.to_owned());
+        }
+        let groups: Vec<&str> = integer.split(group).collect();
+        let is_grouped = (1..=3).contains(&groups[0].len())
+            && groups[1..].iter().all(|g| g.len() == 3);
+        is_grouped.then(|| groups.concat())
+    }
+
+    /// Parses a number written with this format. Group separators may be
+    /// omitted, and a value wrapped in parentheses is read as negative.
+    /// A lone separator that cannot group thousands, as in `12.5` with
+    /// `1.234,56`, is read as the decimal point.
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let (text, is_negative) = match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            Some(inner) => (inner, true),
+            None => (text, false),
+        };
+
+        let (group, decimal) = (self.group_separator(), self.decimal_separator());
+        let normalized: String = text
+            .chars()
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+') || *c == group || *c == decimal)
+            .collect();
+        let normalized = normalized.trim();
+        let (sign, number) = match normalized.strip_prefix('-') {
+            Some(number) => ("-", number.trim_start()),
+            None => ("", normalized.strip_prefix('+').unwrap_or(normalized).trim_start()),
+        };
+
+        let (integer, fraction) = match number.split_once(decimal) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+        let number = match (Self::ungroup(integer, group), fraction) {
+            (Some(integer), Some(fraction)) => format!("{}.{}", integer, fraction),
+            (Some(integer), None) => integer,
+            (None, None) if group != ' ' && integer.matches(group).count() == 1 => integer.replace(group, "."),
+            (None, _) => return None,
+        };
+
+        let value = format!("{}{}", sign, number).parse::<f64>().ok()?;
+        Some(if is_negative { -value.abs() } else { value })
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SymbolPosition {
+    #[default]
+    Before,
+    After,
+}
+
+impl SymbolPosition {
+
+    const POSITIONS: [&'static str; 2] = [
+        gettext_noop("Before Amount"),
+        gettext_noop("After Amount"),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::POSITIONS[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 2] {
+        [
+            Self::Before,
+            Self::After
+        ]
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NegativeStyle {
+    #[default]
+    Minus,
+    Parentheses,
+}
+
+impl NegativeStyle {
+
+    const STYLES: [&'static str; 2] = [
+        "-1.00",
+        "(1.00)",
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::STYLES[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 2] {
+        [
+            Self::Minus,
+            Self::Parentheses
+        ]
+    }
+
+    fn wrap(&self, text: String, is_negative: bool) -> String {
+        match (self, is_negative) {
+            (_, false) => text,
+            (Self::Minus, true) => format!("-{}", text),
+            (Self::Parentheses, true) => format!("({})", text),
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+    #[default]
+    Iso,
+    DayMonthYearDot,
+    DayMonthYearSlash,
+    DayMonthYearDash,
+    MonthDayYearSlash,
+}
+
+impl DateFormat {
+
+    const FORMATS: [(&'static str, &'static str); 5] = [
+        ("2025-12-31", "%Y-%m-%d"),
+        ("31.12.2025", "%d.%m.%Y"),
+        ("31/12/2025", "%d/%m/%Y"),
+        ("31-12-2025", "%d-%m-%Y"),
+        ("12/31/2025", "%m/%d/%Y"),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::FORMATS[*self as usize].0
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 5] {
+        [
+            Self::Iso,
+            Self::DayMonthYearDot,
+            Self::DayMonthYearSlash,
+            Self::DayMonthYearDash,
+            Self::MonthDayYearSlash
+        ]
+    }
+
+    #[inline]
+    const fn pattern(&self) -> &'static str {
+        Self::FORMATS[*self as usize].1
+    }
+
+    /// The format closest to a `strftime` date pattern such as the `D_FMT` of a locale.
+    pub fn from_pattern(pattern: &str) -> Option<Self> {
+        let mut order = String::new();
+        let mut separator = None;
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                separator = separator.or((!c.is_whitespace()).then_some(c));
+                continue;
+            }
+            let field = chars.by_ref().find(|c| !matches!(c, '-' | '_' | '0' | '^' | '#' | 'E' | 'O'));
+            match field {
+                Some('F') => order.push_str("ymd"),
+                Some('D') => order.push_str("mdy"),
+                Some('d' | 'e') => order.push('d'),
+                Some('m') => order.push('m'),
+                Some('Y' | 'y') => order.push('y'),
+                _ => {},
+            }
+        }
+
+        match (order.as_str(), separator) {
+            ("ymd", _) => Some(Self::Iso),
+            ("dmy", Some('.')) => Some(Self::DayMonthYearDot),
+            ("dmy", Some('/')) => Some(Self::DayMonthYearSlash),
+            ("dmy", Some('-')) => Some(Self::DayMonthYearDash),
+            ("mdy", _) => Some(Self::MonthDayYearSlash),
+            _ => None,
+        }
+    }
+
+}
+
+const MONTHS: [&str; 12] = [
+    gettext_noop("January"),
+    gettext_noop("February"),
+    gettext_noop("March"),
+    gettext_noop("April"),
+    gettext_noop("May"),
+    gettext_noop("June"),
+    gettext_noop("July"),
+    gettext_noop("August"),
+    gettext_noop("September"),
+    gettext_noop("October"),
+    gettext_noop("November"),
+    gettext_noop("December"),
+];
+
+/// How amounts and dates are written for the user. It is resolved from the
+/// system locale by the settings, with each part optionally overridden.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Locale {
+    pub number_format: NumberFormat,
+    pub symbol_position: SymbolPosition,
+    pub negative_style: NegativeStyle,
+    pub date_format: DateFormat,
+}
+
+impl Locale {
+
+    pub const DEFAULT: Self = Self {
+        number_format: NumberFormat::SpaceDot,
+        symbol_position: SymbolPosition::Before,
+        negative_style: NegativeStyle::Minus,
+        date_format: DateFormat::Iso,
+    };
+
+    /// The conventions of a locale from its separators, whether its currency
+    /// symbol comes first and its date pattern. Those it does not define are
+    /// left to the defaults.
+    pub fn from_conventions(group: &str, decimal: &str, is_symbol_before: Option<bool>, date_pattern: &str) -> Self {
+        let symbol_position = match is_symbol_before {
+            Some(true) => SymbolPosition::Before,
+            Some(false) => SymbolPosition::After,
+            None => Self::DEFAULT.symbol_position,
+        };
+        Self {
+            number_format: NumberFormat::from_separators(group, decimal).unwrap_or(Self::DEFAULT.number_format),
+            symbol_position,
+            negative_style: Self::DEFAULT.negative_style,
+            date_format: DateFormat::from_pattern(date_pattern).unwrap_or(Self::DEFAULT.date_format),
+        }
+    }
+
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        self.negative_style.wrap(self.number_format.format(value, decimals), value < 0.0)
+    }
+
+    pub fn format_amount(&self, value: f64, decimals: usize, symbol: &str) -> String {
+        let number = self.number_format.format(value, decimals);
+        let amount = match self.symbol_position {
+            SymbolPosition::Before => format!("{}{}", symbol, number),
+            SymbolPosition::After => format!("{} {}", number, symbol),
+        };
+        self.negative_style.wrap(amount, value < 0.0)
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.date_format.pattern()).to_string()
+    }
+
+    #[inline]
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        self.number_format.parse(text)
+    }
+
+    pub fn month_name(date: NaiveDate) -> String {
+        gettext(MONTHS[date.month0() as usize])
+    }
+
+    /// Abbreviates the translated month name to its first three letters.
+    pub fn short_month_name(date: NaiveDate) -> String {
+        Self::month_name(date).chars().take(3).collect()
+    }
+
+    pub fn month_and_year(date: NaiveDate) -> String {
+        // Translators: a month name followed by a year, e.g. "March 2025"
+        gettext_f("{month} {year}", &[("month", &Self::month_name(date)), ("year", &date.year().to_string())])
+    }
+
+    pub fn short_month_and_year(date: NaiveDate) -> String {
+        // Translators: an abbreviated month name followed by a year, e.g. "Mar 2025"
+        gettext_f("{month} {year}", &[("month", &Self::short_month_name(date)), ("year", &date.year().to_string())])
+    }
+
+    pub fn day_and_short_month(date: NaiveDate) -> String {
+        // Translators: a day of the month followed by an abbreviated month name, e.g. "31 Mar"
+        gettext_f("{day} {month}", &[("day", &date.day().to_string()), ("month", &Self::short_month_name(date))])
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_formatted_with_groups() {
+        assert_eq!(NumberFormat::SpaceDot.format(1234567.891, 2), "1 234 567.89");
+        assert_eq!(NumberFormat::CommaDot.format(-1234.5, 2), "1,234.50");
+        assert_eq!(NumberFormat::DotComma.format(1234.5, 2), "1.234,50");
+        assert_eq!(NumberFormat::SpaceComma.format(999.0, 0), "999");
+    }
+
+    #[test]
+    fn amounts_follow_the_locale() {
+        let locale = Locale {
+            number_format: NumberFormat::DotComma,
+            symbol_position: SymbolPosition::After,
+            negative_style: NegativeStyle::Parentheses,
+            date_format: DateFormat::DayMonthYearDot,
+        };
+
+        assert_eq!(locale.format_amount(-1234.5, 2, "€"), "(1.234,50 €)");
+        assert_eq!(Locale::DEFAULT.format_amount(-1234.5, 2, "$"), "-$1 234.50");
+        assert_eq!(locale.format_number(12.0, 1), "12,0");
+        assert_eq!(locale.format_date(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap()), "09.03.2025");
+    }
+
+    #[test]
+    fn formatted_numbers_parse_back() {
+        for format in NumberFormat::as_slice() {
+            for value in [0.0, 12.5, 999.99, 1234.5, 1234567.25] {
+                assert_eq!(format.parse(&format.format(value, 2)), Some(value), "{:?}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn group_separators_only_count_between_thousands() {
+        assert_eq!(NumberFormat::DotComma.parse("12.5"), Some(12.5));
+        assert_eq!(NumberFormat::DotComma.parse("1.234"), Some(1234.0));
+        assert_eq!(NumberFormat::DotComma.parse("1.234.567,8"), Some(1234567.8));
+        assert_eq!(NumberFormat::DotComma.parse("1234,56"), Some(1234.56));
+        assert_eq!(NumberFormat::CommaDot.parse("1,5"), Some(1.5));
+        assert_eq!(NumberFormat::CommaDot.parse("-123,456.7"), Some(-123456.7));
+        assert_eq!(NumberFormat::DotComma.parse("1.23.4"), None);
+        assert_eq!(NumberFormat::DotComma.parse("12.5,3"), None);
+        assert_eq!(NumberFormat::SpaceComma.parse("12 5"), None);
+    }
+
+    #[test]
+    fn signs_symbols_and_parentheses_are_read() {
+        assert_eq!(NumberFormat::SpaceComma.parse("1\u{202f}234,50 €"), Some(1234.5));
+        assert_eq!(NumberFormat::CommaDot.parse("$ -12.50"), Some(-12.5));
+        assert_eq!(NumberFormat::CommaDot.parse("+7"), Some(7.0));
+        assert_eq!(NumberFormat::DotComma.parse("(1.234,5)"), Some(-1234.5));
+        assert_eq!(NumberFormat::SpaceDot.parse("abc"), None);
+    }
+
+    #[test]
+    fn conventions_of_the_system_locale_are_mapped() {
+        assert_eq!(Locale::from_conventions(",", ".", Some(true), "%m/%d/%Y"), Locale {
+            number_format: NumberFormat::CommaDot,
+            symbol_position: SymbolPosition::Before,
+            negative_style: NegativeStyle::Minus,
+            date_format: DateFormat::MonthDayYearSlash,
+        });
+        assert_eq!(Locale::from_conventions(".", ",", Some(false), "%d.%m.%Y"), Locale {
+            number_format: NumberFormat::DotComma,
+            symbol_position: SymbolPosition::After,
+            negative_style: NegativeStyle::Minus,
+            date_format: DateFormat::DayMonthYearDot,
+        });
+        assert_eq!(Locale::from_conventions("", "", None, "%m/%d/%y"), Locale {
+            date_format: DateFormat::MonthDayYearSlash,
+            ..Locale::DEFAULT
+        });
+    }
+
+    #[test]
+    fn date_patterns_are_mapped() {
+        assert_eq!(DateFormat::from_pattern("%Y-%m-%d"), Some(DateFormat::Iso));
+        assert_eq!(DateFormat::from_pattern("%F"), Some(DateFormat::Iso));
+        assert_eq!(DateFormat::from_pattern("%Y年%m月%d日"), Some(DateFormat::Iso));
+        assert_eq!(DateFormat::from_pattern("%d/%m/%y"), Some(DateFormat::DayMonthYearSlash));
+        assert_eq!(DateFormat::from_pattern("%d-%m-%Y"), Some(DateFormat::DayMonthYearDash));
+        assert_eq!(DateFormat::from_pattern("%e.%m.%Y"), Some(DateFormat::DayMonthYearDot));
+        assert_eq!(DateFormat::from_pattern("%D"), Some(DateFormat::MonthDayYearSlash));
+        assert_eq!(DateFormat::from_pattern("%A %d %B %Y"), None);
+    }
+
+    #[test]
+    fn separators_are_mapped() {
+        assert_eq!(NumberFormat::from_separators("\u{a0}", ","), Some(NumberFormat::SpaceComma));
+        assert_eq!(NumberFormat::from_separators("'", "."), Some(NumberFormat::SpaceDot));
+        assert_eq!(NumberFormat::from_separators("", ""), None);
+    }
+}
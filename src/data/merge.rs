@@ -0,0 +1,664 @@
+use super::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use crate::i18n::{gettext, gettext_f, gettext_noop};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeField {
+    WalletName,
+    WalletDescription,
+    WalletCurrency,
+    OpeningBalance,
+    OpeningDate,
+    Assertions,
+    Name,
+    Description,
+    Amount,
+    Cycle,
+    StartDate,
+    EndDate,
+    Payee,
+    Category,
+    Splits,
+    Statuses,
+    PayeeName,
+    Aliases,
+    DefaultCategory,
+    DefaultAmount,
+    DefaultCycle,
+    RuleName,
+    Pattern,
+    MinimumAmount,
+    MaximumAmount,
+    SetCategory,
+    SetPayee,
+    SetDescription,
+    Enabled,
+}
+
+impl MergeField {
+
+    const FIELDS: [&'static str; 29] = [
+        gettext_noop("Name"),
+        gettext_noop("Description"),
+        gettext_noop("Currency"),
+        gettext_noop("Opening Balance"),
+        gettext_noop("Opening Date"),
+        gettext_noop("Balance Assertions"),
+        gettext_noop("Name"),
+        gettext_noop("Description"),
+        gettext_noop("Amount"),
+        gettext_noop("Cycle"),
+        gettext_noop("Start Date"),
+        gettext_noop("End Date"),
+        gettext_noop("Payee"),
+        gettext_noop("Category"),
+        gettext_noop("Splits"),
+        gettext_noop("Statuses"),
+        gettext_noop("Name"),
+        gettext_noop("Aliases"),
+        gettext_noop("Default Category"),
+        gettext_noop("Default Amount"),
+        gettext_noop("Default Cycle"),
+        gettext_noop("Name"),
+        gettext_noop("Pattern"),
+        gettext_noop("Minimum Amount"),
+        gettext_noop("Maximum Amount"),
+        gettext_noop("Set Category"),
+        gettext_noop("Set Payee"),
+        gettext_noop("Set Description"),
+        gettext_noop("Enabled"),
+    ];
+
+    const WALLET_FIELDS: [Self; 6] = [
+        Self::WalletName,
+        Self::WalletDescription,
+        Self::WalletCurrency,
+        Self::OpeningBalance,
+        Self::OpeningDate,
+        Self::Assertions,
+    ];
+
+    const TRANSACTION_FIELDS: [Self; 10] = [
+        Self::Name,
+        Self::Description,
+        Self::Amount,
+        Self::Cycle,
+        Self::StartDate,
+        Self::EndDate,
+        Self::Payee,
+        Self::Category,
+        Self::Splits,
+        Self::Statuses,
+    ];
+
+    const PAYEE_FIELDS: [Self; 5] = [
+        Self::PayeeName,
+        Self::Aliases,
+        Self::DefaultCategory,
+        Self::DefaultAmount,
+        Self::DefaultCycle,
+    ];
+
+    const RULE_FIELDS: [Self; 8] = [
+        Self::RuleName,
+        Self::Pattern,
+        Self::MinimumAmount,
+        Self::MaximumAmount,
+        Self::SetCategory,
+        Self::SetPayee,
+        Self::SetDescription,
+        Self::Enabled,
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::FIELDS[*self as usize]
+    }
+
+    fn wallet_value(&self, wallet: &Wallet) -> String {
+        match self {
+            Self::WalletName => wallet.name.clone(),
+            Self::WalletDescription => wallet.description.clone().unwrap_or_default(),
+            Self::WalletCurrency => wallet.currency.to_string(),
+            Self::OpeningBalance => wallet.opening_balance.to_string(),
+            Self::OpeningDate => wallet.opening_date.map(|d| d.to_string()).unwrap_or_default(),
+            Self::Assertions => wallet.assertions
+                .iter()
+                .map(|a| format!("{} {}", a.date, a.balance))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => unreachable!(),
+        }
+    }
+
+    fn copy_wallet_value(&self, from: &Wallet, to: &mut Wallet) {
+        match self {
+            Self::WalletName => to.name = from.name.clone(),
+            Self::WalletDescription => to.description = from.description.clone(),
+            Self::WalletCurrency => to.currency = from.currency,
+            Self::OpeningBalance => to.opening_balance = from.opening_balance,
+            Self::OpeningDate => to.opening_date = from.opening_date,
+            Self::Assertions => to.assertions = from.assertions.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn transaction_value(&self, transaction: &Transaction) -> String {
+        match self {
+            Self::Name => transaction.name.clone(),
+            Self::Description => transaction.description.clone().unwrap_or_default(),
+            Self::Amount => transaction.amount.to_string(),
+            Self::Cycle => transaction.cycle.to_string(),
+            Self::StartDate => transaction.start_date.to_string(),
+            Self::EndDate => transaction.end_date.map(|d| d.to_string()).unwrap_or_default(),
+            Self::Payee => transaction.payee.clone().unwrap_or_default(),
+            Self::Category => transaction.category.clone().unwrap_or_default(),
+            Self::Splits => transaction.splits
+                .iter()
+                .map(|s| format!("{} {}", s.category, s.amount))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Statuses => transaction.statuses
+                .iter()
+                .map(|(date, status)| format!("{} {}", date, status))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => unreachable!(),
+        }
+    }
+
+    fn copy_transaction_value(&self, from: &Transaction, to: &mut Transaction) {
+        match self {
+            Self::Name => to.name = from.name.clone(),
+            Self::Description => to.description = from.description.clone(),
+            Self::Amount => to.amount = from.amount,
+            Self::Cycle => to.cycle = from.cycle,
+            Self::StartDate => to.start_date = from.start_date,
+            Self::EndDate => to.end_date = from.end_date,
+            Self::Payee => to.payee = from.payee.clone(),
+            Self::Category => to.category = from.category.clone(),
+            Self::Splits => to.splits = from.splits.clone(),
+            Self::Statuses => to.statuses = from.statuses.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn payee_value(&self, payee: &Payee) -> String {
+        match self {
+            Self::PayeeName => payee.name.clone(),
+            Self::Aliases => payee.aliases.join(", "),
+            Self::DefaultCategory => payee.category.clone().unwrap_or_default(),
+            Self::DefaultAmount => payee.amount.map(|a| a.to_string()).unwrap_or_default(),
+            Self::DefaultCycle => payee.cycle.map(|c| c.to_string()).unwrap_or_default(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn copy_payee_value(&self, from: &Payee, to: &mut Payee) {
+        match self {
+            Self::PayeeName => to.name = from.name.clone(),
+            Self::Aliases => to.aliases = from.aliases.clone(),
+            Self::DefaultCategory => to.category = from.category.clone(),
+            Self::DefaultAmount => to.amount = from.amount,
+            Self::DefaultCycle => to.cycle = from.cycle,
+            _ => unreachable!(),
+        }
+    }
+
+    fn rule_value(&self, rule: &Rule) -> String {
+        match self {
+            Self::RuleName => rule.name.clone(),
+            Self::Pattern => rule.pattern.clone(),
+            Self::MinimumAmount => rule.min_amount.map(|a| a.to_string()).unwrap_or_default(),
+            Self::MaximumAmount => rule.max_amount.map(|a| a.to_string()).unwrap_or_default(),
+            Self::SetCategory => rule.category.clone().unwrap_or_default(),
+            Self::SetPayee => rule.payee.clone().unwrap_or_default(),
+            Self::SetDescription => rule.description.clone().unwrap_or_default(),
+            Self::Enabled => rule.enabled.to_string(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn copy_rule_value(&self, from: &Rule, to: &mut Rule) {
+        match self {
+            Self::RuleName => to.name = from.name.clone(),
+            Self::Pattern => to.pattern = from.pattern.clone(),
+            Self::MinimumAmount => to.min_amount = from.min_amount,
+            Self::MaximumAmount => to.max_amount = from.max_amount,
+            Self::SetCategory => to.category = from.category.clone(),
+            Self::SetPayee => to.payee = from.payee.clone(),
+            Self::SetDescription => to.description = from.description.clone(),
+            Self::Enabled => to.enabled = from.enabled,
+            _ => unreachable!(),
+        }
+    }
+
+}
+
+/// The record a conflict belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeItem {
+    Wallet(usize),
+    Transaction { wallet: usize, transaction: usize },
+    Payee(usize),
+    Rule(usize),
+}
+
+/// A field both documents changed in different ways since the common ancestor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub item: MergeItem,
+    pub item_name: String,
+    pub field: MergeField,
+    pub ours: String,
+    pub theirs: String,
+}
+
+impl MergeConflict {
+
+    pub fn title(&self) -> String {
+        match self.item {
+            MergeItem::Wallet(_) => gettext_f("Wallet {name}", &[("name", &self.item_name)]),
+            MergeItem::Transaction { .. } => gettext_f("Transaction {name}", &[("name", &self.item_name)]),
+            MergeItem::Payee(_) => gettext_f("Payee {name}", &[("name", &self.item_name)]),
+            MergeItem::Rule(_) => gettext_f("Rule {name}", &[("name", &self.item_name)]),
+        }
+    }
+
+}
+
+/// Result of a three-way merge. `data` keeps our value of every conflicting
+/// field until `resolve` picks theirs where asked.
+#[derive(Debug, Clone)]
+pub struct Merge {
+    pub data: Data,
+    pub conflicts: Vec<MergeConflict>,
+    theirs: Data,
+}
+
+impl Merge {
+
+    /// Merges wallets, transactions, payees and rules by id. Changes made on one
+    /// side only are taken as they are, items removed on one side and untouched
+    /// on the other are removed, and document settings keep our value unless
+    /// only theirs changed.
+    pub fn new(base: &Data, ours: &Data, theirs: &Data) -> Self {
+        Self::merge_documents(Some(base), ours, theirs)
+    }
+
+    /// Merges two copies whose common ancestor is unknown. Removals can't be
+    /// told from additions, so items of either side are kept, every field that
+    /// differs is a conflict and document settings keep our value.
+    pub fn without_base(ours: &Data, theirs: &Data) -> Self {
+        Self::merge_documents(None, ours, theirs)
+    }
+
+    fn merge_documents(base: Option<&Data>, ours: &Data, theirs: &Data) -> Self {
+        let mut conflicts = Vec::new();
+        let wallets = Self::merge_records(base.map_or(&[][..], |b| &b.wallets), &ours.wallets, &theirs.wallets, |w| w.id, |base, ours, theirs| {
+            Self::merge_wallet(base, ours, theirs, &mut conflicts)
+        });
+        let payees = Self::merge_records(base.map_or(&[][..], |b| &b.payees), &ours.payees, &theirs.payees, |p| p.id, |base, ours, theirs| {
+            let (payee, payee_conflicts) = Self::merge_fields(
+                &MergeField::PAYEE_FIELDS,
+                base,
+                ours,
+                theirs,
+                MergeField::payee_value,
+                MergeField::copy_payee_value,
+            );
+            conflicts.extend(Self::conflicts(MergeItem::Payee(ours.id), &ours.name, payee_conflicts));
+            payee
+        });
+        let rules = Self::merge_records(base.map_or(&[][..], |b| &b.rules), &ours.rules, &theirs.rules, |r| r.id, |base, ours, theirs| {
+            let (rule, rule_conflicts) = Self::merge_fields(
+                &MergeField::RULE_FIELDS,
+                base,
+                ours,
+                theirs,
+                MergeField::rule_value,
+                MergeField::copy_rule_value,
+            );
+            conflicts.extend(Self::conflicts(MergeItem::Rule(ours.id), &ours.name, rule_conflicts));
+            rule
+        });
+
+        let data = Data {
+            name: Self::merge_setting(base.map(|b| &b.name), &ours.name, &theirs.name),
+            icon: Self::merge_setting(base.map(|b| &b.icon), &ours.icon, &theirs.icon),
+            wallets,
+            currency: Self::merge_setting(base.map(|b| &b.currency), &ours.currency, &theirs.currency),
+            period: Self::merge_setting(base.map(|b| &b.period), &ours.period, &theirs.period),
+            reminder_days: Self::merge_setting(base.map(|b| &b.reminder_days), &ours.reminder_days, &theirs.reminder_days),
+            payees,
+            rules,
+        };
+
+        Self {
+            data,
+            conflicts,
+            theirs: theirs.clone(),
+        }
+    }
+
+    fn merge_setting<T: Serialize + Clone>(base: Option<&T>, ours: &T, theirs: &T) -> T {
+        let value = |v: &T| serde_json::to_value(v).ok();
+        if base.is_some_and(|base| value(ours) == value(base)) {
+            theirs.clone()
+        } else {
+            ours.clone()
+        }
+    }
+
+    /// Pairs the records of both sides by id, merging those present in both
+    /// with `merge` and keeping the ones added or changed on a single side.
+    fn merge_records<T: Clone + PartialEq>(
+        base: &[T],
+        ours: &[T],
+        theirs: &[T],
+        id: impl Fn(&T) -> usize,
+        mut merge: impl FnMut(Option<&T>, &T, &T) -> T,
+    ) -> Vec<T> {
+        let base_records: HashMap<usize, &T> = base.iter().map(|r| (id(r), r)).collect();
+        let their_records: HashMap<usize, &T> = theirs.iter().map(|r| (id(r), r)).collect();
+        let our_ids: Vec<usize> = ours.iter().map(&id).collect();
+
+        let mut records = Vec::new();
+        for our_record in ours {
+            let base_record = base_records.get(&id(our_record)).copied();
+            match their_records.get(&id(our_record)) {
+                Some(their_record) => records.push(merge(base_record, our_record, their_record)),
+                None if base_record == Some(our_record) => {},
+                None => records.push(our_record.clone()),
+            }
+        }
+        for their_record in theirs.iter().filter(|r| !our_ids.contains(&id(r))) {
+            if base_records.get(&id(their_record)) != Some(&their_record) {
+                records.push(their_record.clone());
+            }
+        }
+        records
+    }
+
+    /// Merges the fields of one item, returning the conflicting fields with both values.
+    fn merge_fields<T: Clone>(
+        fields: &[MergeField],
+        base: Option<&T>,
+        ours: &T,
+        theirs: &T,
+        value: impl Fn(&MergeField, &T) -> String,
+        copy: impl Fn(&MergeField, &T, &mut T),
+    ) -> (T, Vec<(MergeField, String, String)>) {
+        let mut merged = ours.clone();
+        let mut conflicts = Vec::new();
+        for field in fields {
+            let (our_value, their_value) = (value(field, ours), value(field, theirs));
+            if our_value == their_value {
+                continue;
+            }
+            let base_value = base.map(|b| value(field, b));
+            if base_value.as_ref() == Some(&our_value) {
+                copy(field, theirs, &mut merged);
+            } else if base_value.as_ref() != Some(&their_value) {
+                conflicts.push((*field, our_value, their_value));
+            }
+        }
+        (merged, conflicts)
+    }
+
+    fn conflicts(item: MergeItem, item_name: &str, fields: Vec<(MergeField, String, String)>) -> impl Iterator<Item = MergeConflict> + '_ {
+        fields.into_iter().map(move |(field, ours, theirs)| MergeConflict {
+            item,
+            item_name: item_name.to_owned(),
+            field,
+            ours,
+            theirs,
+        })
+    }
+
+    fn merge_wallet(base: Option<&Wallet>, ours: &Wallet, theirs: &Wallet, conflicts: &mut Vec<MergeConflict>) -> Wallet {
+        let (mut wallet, wallet_conflicts) = Self::merge_fields(
+            &MergeField::WALLET_FIELDS,
+            base,
+            ours,
+            theirs,
+            MergeField::wallet_value,
+            MergeField::copy_wallet_value,
+        );
+        conflicts.extend(Self::conflicts(MergeItem::Wallet(ours.id), &ours.name, wallet_conflicts));
+
+        let base_transactions = base.map_or(&[][..], |b| &b.transactions);
+        wallet.transactions = Self::merge_records(base_transactions, &ours.transactions, &theirs.transactions, |t| t.id, |base, ours_transaction, theirs_transaction| {
+            let (transaction, transaction_conflicts) = Self::merge_fields(
+                &MergeField::TRANSACTION_FIELDS,
+                base,
+                ours_transaction,
+                theirs_transaction,
+                MergeField::transaction_value,
+                MergeField::copy_transaction_value,
+            );
+            let item = MergeItem::Transaction { wallet: ours.id, transaction: ours_transaction.id };
+            conflicts.extend(Self::conflicts(item, &ours_transaction.name, transaction_conflicts));
+            transaction
+        });
+        wallet
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Applies their value of every conflict whose flag in `use_theirs` is set.
+    pub fn resolve(mut self, use_theirs: &[bool]) -> Data {
+        for (conflict, _) in self.conflicts.iter().zip(use_theirs).filter(|(_, theirs)| **theirs) {
+            match conflict.item {
+                MergeItem::Wallet(wallet_id) => {
+                    let Some(their_wallet) = self.theirs.find_wallet_by_id(wallet_id) else { continue };
+                    let Some(wallet) = self.data.wallets.iter_mut().find(|w| w.id == wallet_id) else { continue };
+                    conflict.field.copy_wallet_value(their_wallet, wallet);
+                },
+                MergeItem::Transaction { wallet: wallet_id, transaction: transaction_id } => {
+                    let Some(their_transaction) = self.theirs
+                        .find_wallet_by_id(wallet_id)
+                        .and_then(|w| w.find_transaction_by_id(transaction_id)) else { continue };
+                    let Some(transaction) = self.data.wallets
+                        .iter_mut()
+                        .find(|w| w.id == wallet_id)
+                        .and_then(|w| w.transactions.iter_mut().find(|t| t.id == transaction_id)) else { continue };
+                    conflict.field.copy_transaction_value(their_transaction, transaction);
+                },
+                MergeItem::Payee(payee_id) => {
+                    let Some(their_payee) = self.theirs.find_payee_by_id(payee_id) else { continue };
+                    let Some(payee) = self.data.payees.iter_mut().find(|p| p.id == payee_id) else { continue };
+                    conflict.field.copy_payee_value(their_payee, payee);
+                },
+                MergeItem::Rule(rule_id) => {
+                    let Some(their_rule) = self.theirs.find_rule_by_id(rule_id) else { continue };
+                    let Some(rule) = self.data.rules.iter_mut().find(|r| r.id == rule_id) else { continue };
+                    conflict.field.copy_rule_value(their_rule, rule);
+                },
+            }
+        }
+        self.data
+    }
+
+}
+
+impl std::fmt::Display for MergeField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", gettext(self.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(id: usize, name: &str, amount: f64) -> Transaction {
+        Transaction {
+            id,
+            name: name.to_owned(),
+            amount,
+            ..Transaction::default()
+        }
+    }
+
+    fn data(transactions: Vec<Transaction>) -> Data {
+        Data {
+            wallets: vec![Wallet {
+                id: 1,
+                name: "Checking".to_owned(),
+                transactions,
+                ..Wallet::default()
+            }],
+            ..Data::default()
+        }
+    }
+
+    fn names(data: &Data) -> Vec<&str> {
+        data.wallets[0].transactions.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn additions_on_both_sides_are_kept() {
+        let base = data(vec![transaction(1, "Rent", -900.0)]);
+        let ours = data(vec![transaction(1, "Rent", -900.0), transaction(2, "Salary", 2000.0)]);
+        let theirs = data(vec![transaction(1, "Rent", -900.0), transaction(3, "Coffee", -3.0)]);
+
+        let merge = Merge::new(&base, &ours, &theirs);
+
+        assert!(!merge.has_conflicts());
+        assert_eq!(names(&merge.data), ["Rent", "Salary", "Coffee"]);
+    }
+
+    #[test]
+    fn removals_of_untouched_items_are_kept() {
+        let base = data(vec![transaction(1, "Rent", -900.0), transaction(2, "Salary", 2000.0)]);
+        let ours = data(vec![transaction(1, "Rent", -900.0)]);
+        let theirs = data(vec![transaction(2, "Salary", 2000.0)]);
+
+        let merge = Merge::new(&base, &ours, &theirs);
+
+        assert!(!merge.has_conflicts());
+        assert!(names(&merge.data).is_empty());
+    }
+
+    #[test]
+    fn a_removed_item_changed_on_the_other_side_is_kept() {
+        let base = data(vec![transaction(1, "Rent", -900.0)]);
+        let ours = data(Vec::new());
+        let theirs = data(vec![transaction(1, "Rent", -950.0)]);
+
+        let merge = Merge::new(&base, &ours, &theirs);
+
+        assert_eq!(merge.data.wallets[0].transactions, [transaction(1, "Rent", -950.0)]);
+    }
+
+    #[test]
+    fn without_base_items_of_both_sides_are_kept_and_differences_conflict() {
+        let mut ours = data(vec![transaction(1, "Rent", -900.0), transaction(2, "Salary", 2000.0)]);
+        ours.name = "Home".to_owned();
+        let mut theirs = data(vec![transaction(1, "Rent", -950.0), transaction(3, "Coffee", -3.0)]);
+        theirs.name = "Household".to_owned();
+
+        let merge = Merge::without_base(&ours, &theirs);
+
+        assert_eq!(names(&merge.data), ["Rent", "Salary", "Coffee"]);
+        assert_eq!(merge.data.name, "Home");
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].field, MergeField::Amount);
+        assert_eq!(merge.conflicts[0].item, MergeItem::Transaction { wallet: 1, transaction: 1 });
+    }
+
+    #[test]
+    fn without_base_identical_items_do_not_conflict() {
+        let ours = data(vec![transaction(1, "Rent", -900.0)]);
+
+        let merge = Merge::without_base(&ours, &ours.clone());
+
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.data.wallets, ours.wallets);
+    }
+
+    #[test]
+    fn changes_to_different_fields_are_combined() {
+        let base = data(vec![transaction(1, "Rent", -900.0)]);
+        let ours = data(vec![transaction(1, "Rent", -950.0)]);
+        let theirs = data(vec![transaction(1, "Apartment", -900.0)]);
+
+        let merge = Merge::new(&base, &ours, &theirs);
+
+        assert!(!merge.has_conflicts());
+        assert_eq!(merge.data.wallets[0].transactions, [transaction(1, "Apartment", -950.0)]);
+    }
+
+    #[test]
+    fn conflicting_changes_are_resolved_field_by_field() {
+        let base = data(vec![transaction(1, "Rent", -900.0)]);
+        let ours = data(vec![transaction(1, "Housing", -950.0)]);
+        let theirs = data(vec![transaction(1, "Apartment", -1000.0)]);
+
+        let merge = Merge::new(&base, &ours, &theirs);
+        let fields: Vec<MergeField> = merge.conflicts.iter().map(|c| c.field).collect();
+
+        assert_eq!(fields, [MergeField::Name, MergeField::Amount]);
+        assert_eq!(merge.conflicts[0].item, MergeItem::Transaction { wallet: 1, transaction: 1 });
+        assert_eq!(merge.data.wallets[0].transactions, [transaction(1, "Housing", -950.0)]);
+        assert_eq!(merge.resolve(&[false, true]).wallets[0].transactions, [transaction(1, "Housing", -1000.0)]);
+    }
+
+    #[test]
+    fn payee_and_rule_conflicts_are_listed() {
+        let payee = |category: &str| Payee {
+            id: 7,
+            name: "Grocer".to_owned(),
+            category: Some(category.to_owned()),
+            ..Payee::default()
+        };
+        let rule = |pattern: &str| Rule {
+            id: 8,
+            name: "Groceries".to_owned(),
+            pattern: pattern.to_owned(),
+            ..Rule::default()
+        };
+        let base = Data { payees: vec![payee("Food")], rules: vec![rule("market")], ..data(Vec::new()) };
+        let ours = Data { payees: vec![payee("Groceries")], rules: vec![rule("supermarket")], ..data(Vec::new()) };
+        let theirs = Data { payees: vec![payee("Household")], rules: vec![rule("grocer")], ..data(Vec::new()) };
+
+        let merge = Merge::new(&base, &ours, &theirs);
+        let items: Vec<(MergeItem, MergeField)> = merge.conflicts.iter().map(|c| (c.item, c.field)).collect();
+
+        assert_eq!(items, [(MergeItem::Payee(7), MergeField::DefaultCategory), (MergeItem::Rule(8), MergeField::Pattern)]);
+        let resolved = merge.resolve(&[true, false]);
+        assert_eq!(resolved.payees, [payee("Household")]);
+        assert_eq!(resolved.rules, [rule("supermarket")]);
+    }
+
+    #[test]
+    fn legacy_records_get_the_same_ids_in_every_copy() {
+        let legacy = r#"{
+            "wallets": [{
+                "name": "Checking",
+                "description": null,
+                "currency": "USD",
+                "transactions": [
+                    { "name": "Rent", "description": null, "amount": -900.0, "cycle": "monthly", "start_date": "2024-01-01", "end_date": null },
+                    { "name": "Rent", "description": null, "amount": -900.0, "cycle": "monthly", "start_date": "2024-01-01", "end_date": null }
+                ]
+            }],
+            "currency": "USD",
+            "period": "month"
+        }"#;
+
+        let first: Data = serde_json::from_str(legacy).unwrap();
+        let second: Data = serde_json::from_str(legacy).unwrap();
+        let transactions = &first.wallets[0].transactions;
+
+        assert_eq!(first, second);
+        assert!(first.wallets[0].is_created());
+        assert_ne!(transactions[0].id, transactions[1].id);
+        assert!(!Merge::new(&first, &first, &second).has_conflicts());
+    }
+
+}
@@ -4,38 +4,100 @@ mod currency;
 mod balance;
 mod period;
 mod cycle;
+mod occurrence;
+mod occurrence_status;
+mod balance_assertion;
+mod reconciliation;
+mod split;
+mod payee;
+mod rule;
+mod filter;
+mod view_options;
+mod preferences;
+mod locale;
+mod merge;
+mod document_icon;
+mod error;
 
 pub use wallet::Wallet;
 pub use transaction::Transaction;
-pub use currency::Currency;
+pub use currency::{Currency, RateProvider};
 pub use balance::Balance;
 pub use period::Period;
 pub use cycle::Cycle;
+pub use occurrence::Occurrence;
+pub use occurrence_status::OccurrenceStatus;
+pub use balance_assertion::{BalanceAssertion, Discrepancy};
+pub use reconciliation::Reconciliation;
+pub use split::Split;
+pub use payee::Payee;
+pub use rule::{Rule, RuleChange, RuleMode, RuleSet};
+pub use filter::TransactionFilter;
+pub use view_options::{GroupBy, SortKey, ViewOptions, ViewSettings};
+pub use preferences::Preferences;
+pub use locale::{DateFormat, Locale, NegativeStyle, NumberFormat, SymbolPosition};
+pub use merge::{Merge, MergeConflict};
+pub use document_icon::DocumentIcon;
+pub use error::DataError;
 
 use serde::{Serialize, Deserialize};
+use chrono::{Duration, Local, NaiveDate};
+use std::collections::{BTreeMap, HashSet};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Data {
+    /// Name shown for the document, independent of its file name.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "DocumentIcon::is_default")]
+    pub icon: DocumentIcon,
+    #[serde(deserialize_with = "deserialize_unique")]
     pub wallets: Vec<Wallet>,
     pub currency: Currency,
     pub period: Period,
+    #[serde(default = "Data::default_reminder_days")]
+    pub reminder_days: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "deserialize_unique")]
+    pub payees: Vec<Payee>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "deserialize_unique")]
+    pub rules: Vec<Rule>,
 }
 
 impl Default for Data {
     fn default() -> Self {
         Self {
+            name: String::new(),
+            icon: DocumentIcon::default(),
             wallets: Vec::new(),
             currency: Currency::USD,
             period: Period::Month,
+            reminder_days: Self::default_reminder_days(),
+            payees: Vec::new(),
+            rules: Vec::new(),
         }
     }
 }
 
 impl Data {
 
-    pub fn is_valid(&self) -> bool {
-        self.wallets.len() > 0 && 
-        self.wallets.iter().all(|w| w.is_valid())
+    const fn default_reminder_days() -> u32 {
+        1
+    }
+
+    /// Checks that there is a wallet and that every wallet and transaction is valid,
+    /// telling which field is not by its position, such as `wallets[2].transactions[14].amount`.
+    pub fn validate(&self) -> Result<(), DataError> {
+        if self.is_empty() {
+            return Err(DataError::validation("wallets"));
+        }
+        for (index, wallet) in self.wallets.iter().enumerate() {
+            if let Some(field) = wallet.invalid_field() {
+                return Err(DataError::validation(format!("wallets[{}].{}", index, field)));
+            }
+        }
+        Ok(())
     }
 
     pub fn is_empty(&self) -> bool {    
@@ -60,6 +122,182 @@ impl Data {
         }
     }
 
+    pub fn find_payee_by_id(&self, id: usize) -> Option<&Payee> {
+        self.payees
+            .iter()
+            .find(|p| p.id == id)
+    }
+
+    pub fn find_payee_by_name(&self, name: &str) -> Option<&Payee> {
+        self.payees
+            .iter()
+            .find(|p| p.matches(name))
+    }
+
+    pub fn remove_payee_by_id(&mut self, id: usize) {
+        self.payees.retain(|p| p.id != id);
+    }
+
+    pub fn add_or_update_payee(&mut self, payee: Payee) {
+        if let Some(index) = self.payees.iter().position(|p| p.id == payee.id) {
+            let previous_name = std::mem::replace(&mut self.payees[index], payee.clone()).name;
+            if previous_name != payee.name {
+                self.wallets
+                    .iter_mut()
+                    .flat_map(|w| w.transactions.iter_mut())
+                    .filter(|t| t.payee.as_ref() == Some(&previous_name))
+                    .for_each(|t| t.payee = Some(payee.name.clone()));
+            }
+        } else {
+            self.payees.push(payee);
+        }
+        self.payees.sort_by_key(|p| p.name.to_lowercase());
+    }
+
+    pub fn payee_suggestions(&self, query: &str, limit: usize) -> Vec<&Payee> {
+        if query.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let mut suggestions: Vec<(usize, &Payee)> = self.payees
+            .iter()
+            .filter_map(|p| p.match_score(query).map(|score| (score, p)))
+            .collect();
+        suggestions.sort_by_key(|(score, p)| (*score, p.name.to_lowercase()));
+        suggestions
+            .into_iter()
+            .take(limit)
+            .map(|(_, p)| p)
+            .collect()
+    }
+
+    pub fn payee_for_transaction(&self, transaction: &Transaction) -> Option<&Payee> {
+        transaction.payee
+            .as_deref()
+            .and_then(|name| self.find_payee_by_name(name))
+            .or_else(|| self.find_payee_by_name(&transaction.name))
+    }
+
+    pub fn payee_transactions(&self, payee_id: usize) -> Vec<(&Wallet, &Transaction)> {
+        self.wallets
+            .iter()
+            .flat_map(|w| w.transactions.iter().map(move |t| (w, t)))
+            .filter(|(_, t)| self.payee_for_transaction(t).is_some_and(|p| p.id == payee_id))
+            .collect()
+    }
+
+    pub fn payee_totals(&self) -> Vec<(usize, Balance)> {
+        let mut totals: Vec<(usize, Balance)> = self.payees
+            .iter()
+            .map(|p| (p.id, Balance::default()))
+            .collect();
+
+        for wallet in self.wallets_for_period() {
+            let wallet = wallet.convert_to_currency(self.currency);
+            for transaction in wallet.transactions.iter() {
+                let Some(payee) = self.payee_for_transaction(transaction) else { continue };
+                let Some((_, balance)) = totals.iter_mut().find(|(id, _)| *id == payee.id) else { continue };
+                let transactions = [transaction];
+                *balance = balance.join(&Balance::from_transactions(&transactions));
+            }
+        }
+
+        totals.sort_by(|(_, a), (_, b)| b.expense.partial_cmp(&a.expense).unwrap_or(std::cmp::Ordering::Equal));
+        totals
+    }
+
+    pub fn find_rule_by_id(&self, id: usize) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|r| r.id == id)
+    }
+
+    pub fn remove_rule_by_id(&mut self, id: usize) {
+        self.rules.retain(|r| r.id != id);
+    }
+
+    pub fn add_or_update_rule(&mut self, rule: Rule) {
+        if let Some(index) = self.rules.iter().position(|r| r.id == rule.id) {
+            self.rules[index] = rule;
+        } else {
+            self.rules.push(rule);
+        }
+    }
+
+    pub fn apply_rules(&self, transaction: &Transaction) -> Transaction {
+        RuleSet::new(&self.rules).apply(transaction, RuleMode::FillEmpty)
+    }
+
+    pub fn preview_rules(&self, wallet_id: usize) -> Vec<RuleChange> {
+        let rule_set = RuleSet::new(&self.rules);
+        if rule_set.is_empty() {
+            return Vec::new();
+        }
+        let Some(wallet) = self.find_wallet_by_id(wallet_id) else { return Vec::new() };
+
+        wallet.transactions
+            .iter()
+            .map(|t| RuleChange {
+                wallet_id,
+                before: t.clone(),
+                after: rule_set.apply(t, RuleMode::Overwrite),
+            })
+            .filter(|change| !change.changed_fields().is_empty())
+            .collect()
+    }
+
+    pub fn run_rules(&mut self, wallet_id: usize) -> usize {
+        let changes = self.preview_rules(wallet_id);
+        if let Some(wallet) = self.wallets.iter_mut().find(|w| w.id == wallet_id) {
+            for change in changes.iter() {
+                wallet.add_or_update_transaction(change.after.clone());
+            }
+        }
+        changes.len()
+    }
+
+    pub fn count_rule_matches(&self, rule: &Rule) -> usize {
+        let rules = [Rule { enabled: true, ..rule.clone() }];
+        let rule_set = RuleSet::new(&rules);
+        self.wallets
+            .iter()
+            .flat_map(|w| w.transactions.iter())
+            .filter(|t| !rule_set.matching_rules(t).is_empty())
+            .count()
+    }
+
+    pub fn search(&self, filter: &TransactionFilter) -> Vec<(&Wallet, &Transaction)> {
+        self.wallets
+            .iter()
+            .filter(|w| filter.matches_wallet(w))
+            .flat_map(|w| w.transactions.iter().map(move |t| (w, t)))
+            .filter(|(_, t)| filter.matches(t))
+            .collect()
+    }
+
+    /// Wallets and transactions with the given ids, such as the results of an indexed search.
+    pub fn find_transactions(&self, ids: &[(usize, usize)]) -> Vec<(&Wallet, &Transaction)> {
+        let ids: HashSet<&(usize, usize)> = ids.iter().collect();
+        self.wallets
+            .iter()
+            .flat_map(|w| w.transactions.iter().map(move |t| (w, t)))
+            .filter(|(w, t)| ids.contains(&(w.id, t.id)))
+            .collect()
+    }
+
+    /// Whether some wallet is in another currency than the document, so its amounts need exchange rates.
+    pub fn needs_exchange_rates(&self) -> bool {
+        self.wallets
+            .iter()
+            .any(|w| w.currency != self.currency)
+    }
+
+    /// Whether totals hold amounts in their own currency because the exchange
+    /// rates are not loaded yet.
+    pub fn has_unconverted_amounts(&self) -> bool {
+        self.needs_exchange_rates() && !Preferences::current().rate_provider.has_rates()
+    }
+
     pub fn total_balance_for_period(&self) -> Balance {
         self.wallets_for_period()
             .iter()
@@ -68,6 +306,146 @@ impl Data {
             })
     }
 
+    pub fn net_worth_at(&self, date: NaiveDate) -> f64 {
+        self.wallets
+            .iter()
+            .map(|w| {
+                let balance = w.balance_at(date);
+                w.currency.convert_amount(balance, self.currency).unwrap_or(balance)
+            })
+            .sum()
+    }
+
+    pub fn net_worth(&self) -> f64 {
+        self.net_worth_at(Local::now().naive_local().date())
+    }
+
+    pub fn net_worth_series(&self) -> Vec<(NaiveDate, f64)> {
+        let (_, end) = self.period.bounds();
+        self.balance_series()
+            .into_iter()
+            .map(|(date, _)| {
+                let bucket_end = self.period
+                    .next_bucket(date)
+                    .and_then(|next| next.pred_opt())
+                    .map_or(end, |last_day| last_day.min(end));
+                (date, self.net_worth_at(bucket_end))
+            })
+            .collect()
+    }
+
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<Occurrence> {
+        let mut occurrences: Vec<Occurrence> = self.wallets
+            .iter()
+            .flat_map(|wallet| wallet.occurrences_between(start, end))
+            .collect();
+        occurrences.sort_by_key(|o| o.date);
+        occurrences
+    }
+
+    pub fn upcoming_occurrences(&self, days: u32) -> Vec<Occurrence> {
+        let today = Local::now().naive_local().date();
+        self.occurrences_between(
+            today,
+            today + Duration::days(days as i64)
+        )
+    }
+
+    pub fn balance_series(&self) -> Vec<(NaiveDate, Balance)> {
+        let (start, end) = self.period.bounds();
+        let occurrences = self.occurrences_between(start, end);
+        let first_date = match self.period {
+            Period::All => occurrences.first().map_or(end, |o| o.date),
+            _ => start,
+        };
+
+        let mut series = Vec::new();
+        let mut bucket = Some(self.period.bucket_start(first_date));
+        while let Some(date) = bucket.filter(|date| *date <= end) {
+            series.push((date, Balance::default()));
+            bucket = self.period.next_bucket(date);
+        }
+
+        for occurrence in occurrences {
+            let bucket = self.period.bucket_start(occurrence.date);
+            let Some((_, balance)) = series.iter_mut().rev().find(|(date, _)| *date <= bucket) else { continue };
+            let amount = occurrence.convert_amount(self.currency).unwrap_or(occurrence.amount);
+            if amount >= 0.0 {
+                balance.income += amount;
+            } else {
+                balance.expense += amount.abs();
+            }
+        }
+
+        series
+    }
+
+    pub fn cumulative_series(&self) -> Vec<(NaiveDate, f64)> {
+        let (start, _) = self.period.bounds();
+        let opening_balance = match start.pred_opt() {
+            Some(previous_day) if self.period != Period::All => self
+                .occurrences_between(NaiveDate::MIN, previous_day)
+                .iter()
+                .map(|o| o.convert_amount(self.currency).unwrap_or(o.amount))
+                .sum(),
+            _ => 0.0,
+        };
+
+        self.balance_series()
+            .into_iter()
+            .scan(opening_balance, |total, (date, balance)| {
+                *total += balance.net_balance();
+                Some((date, *total))
+            })
+            .collect()
+    }
+
+    pub fn wallet_shares(&self) -> Vec<(String, f64)> {
+        self.wallets_for_period()
+            .iter()
+            .map(|wallet| {
+                let balance = wallet.convert_to_currency(self.currency).balance();
+                (wallet.name.clone(), balance.income + balance.expense)
+            })
+            .filter(|(_, amount)| *amount > 0.0)
+            .collect()
+    }
+
+    pub fn category_totals(&self) -> Vec<(String, Balance)> {
+        let mut totals: BTreeMap<String, Balance> = BTreeMap::new();
+        for wallet in self.wallets_for_period() {
+            let wallet = wallet.convert_to_currency(self.currency);
+            for (category, amount) in wallet.transactions.iter().flat_map(|t| t.category_amounts()) {
+                let balance = totals.entry(category).or_default();
+                if amount >= 0.0 {
+                    balance.income += amount;
+                } else {
+                    balance.expense += amount.abs();
+                }
+            }
+        }
+
+        let mut totals: Vec<(String, Balance)> = totals.into_iter().collect();
+        totals.sort_by(|(_, a), (_, b)| b.expense.partial_cmp(&a.expense).unwrap_or(std::cmp::Ordering::Equal));
+        totals
+    }
+
+    pub fn expense_shares_by_category(&self) -> Vec<(String, f64)> {
+        self.category_totals()
+            .into_iter()
+            .map(|(category, balance)| (category, balance.expense))
+            .filter(|(_, amount)| *amount > 0.0)
+            .collect()
+    }
+
+    pub fn due_occurrences(&self) -> Vec<Occurrence> {
+        let today = Local::now().naive_local().date();
+        self.occurrences_between(today, today + Duration::days(self.reminder_days as i64))
+            .into_iter()
+            .filter(|o| !o.status.is_cleared())
+            .collect()
+    }
+
     pub fn sort_by_name(&mut self) {
         self.wallets.sort_by(|a, b| a.name.cmp(&b.name));
     }
@@ -79,4 +457,158 @@ impl Data {
             .collect()
     }
 
-}
\ No newline at end of file
+    /// Exports every transaction as comma separated values, one line per transaction.
+    /// Amounts, dates and cycles use the document format so spreadsheets can parse them.
+    pub fn transactions_csv(&self) -> String {
+        let mut csv = String::from("wallet,name,description,category,payee,amount,currency,start_date,end_date,cycle\n");
+        for wallet in &self.wallets {
+            for transaction in &wallet.transactions {
+                let cycle = serde_json::to_value(transaction.cycle)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_owned))
+                    .unwrap_or_default();
+                let fields = [
+                    wallet.name.clone(),
+                    transaction.name.clone(),
+                    transaction.description.clone().unwrap_or_default(),
+                    transaction.category.clone().unwrap_or_default(),
+                    transaction.payee.clone().unwrap_or_default(),
+                    transaction.amount.to_string(),
+                    wallet.currency.to_string(),
+                    transaction.start_date.to_string(),
+                    transaction.end_date.map(|d| d.to_string()).unwrap_or_default(),
+                    cycle,
+                ];
+                let line: Vec<String> = fields.iter().map(|f| escape_csv_field(f)).collect();
+                csv.push_str(&line.join(","));
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+
+}
+
+/// Wallet, transaction, payee and rule ids are stored in documents and must not collide
+/// between copies edited on different machines, so they are random.
+fn generate_id() -> usize {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(Local::now().timestamp_nanos_opt().unwrap_or_default() as u128);
+    (hasher.finish() as usize).max(1)
+}
+
+/// Id for a record saved before ids were stored, derived from its content so
+/// that every copy of a legacy document gives the record the same id and a
+/// merge can still pair them.
+fn legacy_id(content: &str) -> usize {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+    (hash as usize).max(1)
+}
+
+/// Records whose id is stored in the document.
+trait Identified {
+    fn id_mut(&mut self) -> &mut usize;
+}
+
+/// Deserializes a list of records, giving records that share an id, such as
+/// identical legacy ones, an id derived from their position instead.
+fn deserialize_unique<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Identified,
+{
+    let mut records = Vec::<T>::deserialize(deserializer)?;
+    let mut ids = HashSet::new();
+    for (index, record) in records.iter_mut().enumerate() {
+        let id = record.id_mut();
+        if !ids.insert(*id) {
+            *id = legacy_id(&format!("{}#{}", id, index));
+            ids.insert(*id);
+        }
+    }
+    Ok(records)
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(name: &str, amount: f64) -> Transaction {
+        Transaction {
+            id: generate_id(),
+            name: name.to_owned(),
+            amount,
+            ..Transaction::default()
+        }
+    }
+
+    fn data(transactions: Vec<Transaction>) -> Data {
+        Data {
+            wallets: vec![
+                Wallet { id: generate_id(), name: "Savings".to_owned(), ..Wallet::default() },
+                Wallet { id: generate_id(), name: "Checking".to_owned(), transactions, ..Wallet::default() },
+            ],
+            ..Data::default()
+        }
+    }
+
+    fn invalid_field(data: &Data) -> Option<String> {
+        match data.validate() {
+            Err(DataError::Validation { field }) => Some(field),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn valid_document_passes() {
+        assert_eq!(invalid_field(&data(vec![transaction("Rent", -900.0)])), None);
+    }
+
+    #[test]
+    fn validation_names_the_field_by_position() {
+        let mut data = data(vec![transaction("Rent", -900.0), transaction("Salary", 0.0)]);
+        assert_eq!(invalid_field(&data).as_deref(), Some("wallets[1].transactions[1].amount"));
+
+        data.wallets[1].transactions[1].amount = 2000.0;
+        data.wallets[1].transactions[0].end_date = Some(data.wallets[1].transactions[0].start_date.pred_opt().unwrap());
+        assert_eq!(invalid_field(&data).as_deref(), Some("wallets[1].transactions[0].end_date"));
+
+        data.wallets[0].opening_balance = f64::NAN;
+        assert_eq!(invalid_field(&data).as_deref(), Some("wallets[0].opening_balance"));
+    }
+
+    #[test]
+    fn validation_names_the_split_field() {
+        let mut rent = transaction("Rent", -900.0);
+        rent.splits = vec![
+            Split { amount: -600.0, category: "Housing".to_owned(), memo: None },
+            Split { amount: -300.0, category: " ".to_owned(), memo: None },
+        ];
+        assert_eq!(invalid_field(&data(vec![rent.clone()])).as_deref(), Some("wallets[1].transactions[0].splits[1].category"));
+
+        rent.splits[1].category = "Utilities".to_owned();
+        rent.splits[1].amount = -200.0;
+        assert_eq!(invalid_field(&data(vec![rent])).as_deref(), Some("wallets[1].transactions[0].splits"));
+    }
+
+    #[test]
+    fn empty_document_is_invalid() {
+        assert_eq!(invalid_field(&Data::default()).as_deref(), Some("wallets"));
+    }
+
+    #[test]
+    fn display_is_not_translated() {
+        let error = DataError::validation("wallets[0].name");
+        assert_eq!(error.to_string(), "Invalid value in wallets[0].name");
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub wallet_id: usize,
+    pub transaction_id: usize,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub currency: Currency,
+    pub status: OccurrenceStatus,
+}
+
+impl Occurrence {
+
+    pub fn new(wallet: &Wallet, transaction: &Transaction, date: NaiveDate) -> Self {
+        Self {
+            wallet_id: wallet.id,
+            transaction_id: transaction.id,
+            date,
+            amount: transaction.amount,
+            currency: wallet.currency,
+            status: transaction.status(date),
+        }
+    }
+
+    pub fn convert_amount(&self, to: Currency) -> Option<f64> {
+        self.currency.convert_amount(self.amount, to)
+    }
+
+}
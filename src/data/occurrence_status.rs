@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+use crate::i18n::{gettext, gettext_noop};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OccurrenceStatus {
+    #[default]
+    Scheduled,
+    Pending,
+    Cleared,
+    Reconciled,
+}
+
+impl OccurrenceStatus {
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            OccurrenceStatus::Scheduled => gettext_noop("Scheduled"),
+            OccurrenceStatus::Pending => gettext_noop("Pending"),
+            OccurrenceStatus::Cleared => gettext_noop("Cleared"),
+            OccurrenceStatus::Reconciled => gettext_noop("Reconciled"),
+        }
+    }
+
+    #[inline]
+    pub const fn is_cleared(&self) -> bool {
+        matches!(self, OccurrenceStatus::Cleared | OccurrenceStatus::Reconciled)
+    }
+
+    #[inline]
+    pub const fn is_locked(&self) -> bool {
+        matches!(self, OccurrenceStatus::Reconciled)
+    }
+
+}
+
+impl std::fmt::Display for OccurrenceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", gettext(self.as_str()))
+    }
+}
@@ -0,0 +1,128 @@
+use super::*;
+use serde::{Serialize, Deserialize, Deserializer};
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Payee {
+    pub id: usize,
+
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<Cycle>,
+}
+
+impl Payee {
+
+    pub fn assign_global_id(self) -> Self {
+        if self.is_created() {
+            self
+        } else {
+            Self {
+                id: generate_id(),
+                ..self
+            }
+        }
+    }
+
+    #[inline]
+    pub const fn is_created(&self) -> bool {
+        self.id != 0
+    }
+
+    fn normalize(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let name_valid = !self.name.trim().is_empty() && self.name.len() <= 100;
+        let aliases_valid = self.aliases.iter().all(|a| !a.trim().is_empty() && a.len() <= 100);
+        let category_valid = self.category.as_ref().map_or(true, |c| !c.trim().is_empty() && c.len() <= 100);
+        let amount_valid = self.amount.map_or(true, |a| a.is_finite() && a != 0.0);
+
+        name_valid && aliases_valid && category_valid && amount_valid
+    }
+
+    pub fn is_different(&self, other: &Payee) -> bool {
+        self.name != other.name
+            || self.aliases != other.aliases
+            || self.category != other.category
+            || self.amount != other.amount
+            || self.cycle != other.cycle
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.name).chain(self.aliases.iter())
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = Self::normalize(name);
+        self.names().any(|n| Self::normalize(n) == name)
+    }
+
+    pub fn match_score(&self, query: &str) -> Option<usize> {
+        let query = Self::normalize(query);
+        self.names()
+            .filter_map(|n| {
+                let n = Self::normalize(n);
+                if n.starts_with(&query) {
+                    Some(0)
+                } else if n.contains(&query) {
+                    Some(1)
+                } else {
+                    None
+                }
+            })
+            .min()
+    }
+
+}
+
+impl std::fmt::Display for Payee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Deserialize)]
+struct PayeeHelper {
+    #[serde(default)]
+    id: usize,
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    amount: Option<f64>,
+    #[serde(default)]
+    cycle: Option<Cycle>,
+}
+
+impl Identified for Payee {
+    fn id_mut(&mut self) -> &mut usize {
+        &mut self.id
+    }
+}
+
+impl<'de> Deserialize<'de> for Payee {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        let helper = PayeeHelper::deserialize(deserializer)?;
+
+        Ok(Payee {
+            id: if helper.id != 0 { helper.id } else { legacy_id(&helper.name) },
+            name: helper.name,
+            aliases: helper.aliases,
+            category: helper.category,
+            amount: helper.amount,
+            cycle: helper.cycle,
+        })
+    }
+}
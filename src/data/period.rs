@@ -1,5 +1,7 @@
+use super::Preferences;
 use serde::{Serialize, Deserialize};
 use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use crate::i18n::{gettext, gettext_noop};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -20,11 +22,11 @@ impl Default for Period {
 impl Period {
 
     const PERIODS: [&'static str; 5] = [
-        "Day",
-        "Week",
-        "Month",
-        "Year",
-        "All"
+        gettext_noop("Day"),
+        gettext_noop("Week"),
+        gettext_noop("Month"),
+        gettext_noop("Year"),
+        gettext_noop("All")
     ];
 
     #[inline]
@@ -50,7 +52,8 @@ impl Period {
                 (now, now)
             },
             Self::Week => {
-                let week_start = now - Duration::days(now.weekday().num_days_from_monday() as i64);
+                let first_weekday = Preferences::current().first_weekday;
+                let week_start = now - Duration::days(now.weekday().days_since(first_weekday) as i64);
                 let week_end = week_start + Duration::days(6);
                 (week_start, week_end.min(now))
             },
@@ -77,11 +80,24 @@ impl Period {
         (start, end)
     }
 
+    pub fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Year | Self::All => date.with_day(1).unwrap_or(date),
+            _ => date,
+        }
+    }
+
+    pub fn next_bucket(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Year | Self::All => date.checked_add_months(Months::new(1)),
+            _ => date.succ_opt(),
+        }
+    }
+
 }
 
 impl std::fmt::Display for Period {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = self.as_str();
-        write!(f, "{}", s)
+        write!(f, "{}", gettext(self.as_str()))
     }
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+use super::*;
+use chrono::Weekday;
+use std::sync::RwLock;
+
+static CURRENT: RwLock<Preferences> = RwLock::new(Preferences::DEFAULT);
+
+/// Application wide choices that change how data is presented or converted.
+/// They are owned by the settings and pushed here so the data layer does not
+/// depend on GIO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preferences {
+    pub locale: Locale,
+    pub first_weekday: Weekday,
+    pub default_currency: Currency,
+    pub rate_provider: RateProvider,
+    pub views: ViewSettings,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Preferences {
+
+    const DEFAULT: Self = Self {
+        locale: Locale::DEFAULT,
+        first_weekday: Weekday::Mon,
+        default_currency: Currency::USD,
+        rate_provider: RateProvider::Frankfurter,
+        views: ViewSettings::DEFAULT,
+    };
+
+    pub fn current() -> Self {
+        *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_current(preferences: Self) {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = preferences;
+    }
+
+}
@@ -0,0 +1,72 @@
+use super::*;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub wallet: Wallet,
+    pub statement_date: NaiveDate,
+    pub statement_balance: f64,
+}
+
+impl Reconciliation {
+
+    pub fn new(wallet: Wallet, statement_date: NaiveDate, statement_balance: f64) -> Self {
+        Self {
+            wallet,
+            statement_date,
+            statement_balance,
+        }
+    }
+
+    fn start_date(&self) -> NaiveDate {
+        self.wallet.opening_date
+            .and_then(|opening_date| opening_date.succ_opt())
+            .unwrap_or(NaiveDate::MIN)
+    }
+
+    pub fn occurrences(&self) -> Vec<Occurrence> {
+        self.wallet
+            .occurrences_between(self.start_date(), self.statement_date)
+            .into_iter()
+            .filter(|o| !o.status.is_locked())
+            .collect()
+    }
+
+    pub fn cleared_balance(&self) -> f64 {
+        self.wallet
+            .occurrences_between(self.start_date(), self.statement_date)
+            .iter()
+            .filter(|o| o.status.is_cleared())
+            .fold(self.wallet.opening_balance, |acc, o| acc + o.amount)
+    }
+
+    #[inline]
+    pub fn difference(&self) -> f64 {
+        self.statement_balance - self.cleared_balance()
+    }
+
+    #[inline]
+    pub fn is_balanced(&self) -> bool {
+        self.difference().abs() < 0.005
+    }
+
+    pub fn set_status(&mut self, occurrence: &Occurrence, status: OccurrenceStatus) {
+        if !occurrence.status.is_locked() && !status.is_locked() {
+            self.wallet.set_occurrence_status(occurrence.transaction_id, occurrence.date, status);
+        }
+    }
+
+    pub fn finish(mut self) -> Wallet {
+        for occurrence in self.occurrences() {
+            if occurrence.status.is_cleared() {
+                self.wallet.set_occurrence_status(occurrence.transaction_id, occurrence.date, OccurrenceStatus::Reconciled);
+            }
+        }
+        self.wallet.add_or_update_assertion(BalanceAssertion {
+            date: self.statement_date,
+            balance: self.statement_balance,
+        });
+        self.wallet
+    }
+
+}
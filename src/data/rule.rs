@@ -0,0 +1,358 @@
+use super::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Serialize, Deserialize, Deserializer};
+use crate::i18n::gettext_noop;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Rule {
+    pub id: usize,
+
+    pub name: String,
+    pub pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub enabled: bool,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: String::new(),
+            pattern: String::new(),
+            min_amount: None,
+            max_amount: None,
+            category: None,
+            payee: None,
+            description: None,
+            enabled: true,
+        }
+    }
+}
+
+impl Rule {
+
+    pub fn assign_global_id(self) -> Self {
+        if self.is_created() {
+            self
+        } else {
+            Self {
+                id: generate_id(),
+                ..self
+            }
+        }
+    }
+
+    #[inline]
+    pub const fn is_created(&self) -> bool {
+        self.id != 0
+    }
+
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(&self.pattern)
+            .case_insensitive(true)
+            .build()
+    }
+
+    pub fn has_actions(&self) -> bool {
+        self.category.is_some() || self.payee.is_some() || self.description.is_some()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let name_valid = !self.name.trim().is_empty() && self.name.len() <= 100;
+        let pattern_valid = !self.pattern.is_empty() && self.compile().is_ok();
+        let range_valid = match (self.min_amount, self.max_amount) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        };
+
+        name_valid && pattern_valid && range_valid && self.has_actions()
+    }
+
+    pub fn is_different(&self, other: &Rule) -> bool {
+        self.name != other.name
+            || self.pattern != other.pattern
+            || self.min_amount != other.min_amount
+            || self.max_amount != other.max_amount
+            || self.category != other.category
+            || self.payee != other.payee
+            || self.description != other.description
+            || self.enabled != other.enabled
+    }
+
+    fn matches_amount(&self, amount: f64) -> bool {
+        self.min_amount.map_or(true, |min| amount >= min)
+            && self.max_amount.map_or(true, |max| amount <= max)
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleMode {
+    FillEmpty,
+    Overwrite,
+}
+
+pub struct RuleSet<'a> {
+    rules: Vec<(&'a Rule, Regex)>,
+}
+
+impl<'a> RuleSet<'a> {
+
+    pub fn new(rules: &'a [Rule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled && rule.has_actions())
+            .filter_map(|rule| rule.compile().ok().map(|regex| (rule, regex)))
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn matching_rules(&self, transaction: &Transaction) -> Vec<&'a Rule> {
+        self.rules
+            .iter()
+            .filter(|(rule, regex)| regex.is_match(&transaction.name) && rule.matches_amount(transaction.amount))
+            .map(|(rule, _)| *rule)
+            .collect()
+    }
+
+    pub fn apply(&self, transaction: &Transaction, mode: RuleMode) -> Transaction {
+        let mut transaction = transaction.clone();
+        let overwrite = mode == RuleMode::Overwrite;
+
+        for rule in self.matching_rules(&transaction) {
+            if let Some(category) = &rule.category {
+                if overwrite || transaction.category.is_none() {
+                    transaction.category = Some(category.clone());
+                }
+            }
+            if let Some(payee) = &rule.payee {
+                if overwrite || transaction.payee.is_none() {
+                    transaction.payee = Some(payee.clone());
+                }
+            }
+            if let Some(description) = &rule.description {
+                if overwrite || transaction.description.is_none() {
+                    transaction.description = Some(description.clone());
+                }
+            }
+        }
+
+        transaction
+    }
+
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleChange {
+    pub wallet_id: usize,
+    pub before: Transaction,
+    pub after: Transaction,
+}
+
+impl RuleChange {
+
+    pub fn changed_fields(&self) -> Vec<(&'static str, Option<&str>, Option<&str>)> {
+        let fields = [
+            (gettext_noop("Category"), self.before.category.as_deref(), self.after.category.as_deref()),
+            (gettext_noop("Payee"), self.before.payee.as_deref(), self.after.payee.as_deref()),
+            (gettext_noop("Description"), self.before.description.as_deref(), self.after.description.as_deref()),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, before, after)| before != after)
+            .collect()
+    }
+
+}
+
+#[derive(Deserialize)]
+struct RuleHelper {
+    #[serde(default)]
+    id: usize,
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    min_amount: Option<f64>,
+    #[serde(default)]
+    max_amount: Option<f64>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    payee: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+const fn default_enabled() -> bool {
+    true
+}
+
+impl Identified for Rule {
+    fn id_mut(&mut self) -> &mut usize {
+        &mut self.id
+    }
+}
+
+impl<'de> Deserialize<'de> for Rule {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        let helper = RuleHelper::deserialize(deserializer)?;
+
+        Ok(Rule {
+            id: if helper.id != 0 { helper.id } else { legacy_id(&format!("{}\n{}", helper.name, helper.pattern)) },
+            name: helper.name,
+            pattern: helper.pattern,
+            min_amount: helper.min_amount,
+            max_amount: helper.max_amount,
+            category: helper.category,
+            payee: helper.payee,
+            description: helper.description,
+            enabled: helper.enabled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, category: &str) -> Rule {
+        Rule {
+            id: generate_id(),
+            name: pattern.to_owned(),
+            pattern: pattern.to_owned(),
+            category: Some(category.to_owned()),
+            ..Rule::default()
+        }
+    }
+
+    fn transaction(id: usize, name: &str, amount: f64) -> Transaction {
+        Transaction {
+            id,
+            name: name.to_owned(),
+            amount,
+            ..Transaction::default()
+        }
+    }
+
+    fn data(rules: Vec<Rule>, transactions: Vec<Transaction>) -> Data {
+        Data {
+            wallets: vec![Wallet {
+                id: 1,
+                name: "Checking".to_owned(),
+                transactions,
+                ..Wallet::default()
+            }],
+            rules,
+            ..Data::default()
+        }
+    }
+
+    #[test]
+    fn pattern_is_a_case_insensitive_regex() {
+        let rules = [rule("^coffee|espresso$", "Food")];
+        let rule_set = RuleSet::new(&rules);
+
+        assert_eq!(rule_set.matching_rules(&transaction(1, "COFFEE shop", -3.0)).len(), 1);
+        assert_eq!(rule_set.matching_rules(&transaction(2, "Double Espresso", -2.0)).len(), 1);
+        assert!(rule_set.matching_rules(&transaction(3, "Iced coffee", -4.0)).is_empty());
+    }
+
+    #[test]
+    fn invalid_disabled_and_empty_rules_are_skipped() {
+        let rules = [
+            rule("(unclosed", "Broken"),
+            Rule { enabled: false, ..rule("rent", "Housing") },
+            Rule { category: None, ..rule("rent", "Housing") },
+        ];
+
+        assert!(!rules[0].is_valid());
+        assert!(!rules[2].is_valid());
+        assert!(RuleSet::new(&rules).is_empty());
+    }
+
+    #[test]
+    fn amount_range_is_inclusive() {
+        let rules = [Rule { min_amount: Some(-50.0), max_amount: Some(-10.0), ..rule("market", "Groceries") }];
+        let rule_set = RuleSet::new(&rules);
+
+        assert_eq!(rule_set.matching_rules(&transaction(1, "Market", -50.0)).len(), 1);
+        assert_eq!(rule_set.matching_rules(&transaction(2, "Market", -10.0)).len(), 1);
+        assert!(rule_set.matching_rules(&transaction(3, "Market", -50.01)).is_empty());
+        assert!(rule_set.matching_rules(&transaction(4, "Market", 5.0)).is_empty());
+        assert!(!Rule { min_amount: Some(10.0), max_amount: Some(1.0), ..rule("market", "Groceries") }.is_valid());
+    }
+
+    #[test]
+    fn fill_empty_keeps_existing_values_and_overwrite_replaces_them() {
+        let rules = [
+            Rule { payee: Some("Corner Café".to_owned()), ..rule("coffee", "Food") },
+            rule("coffee", "Drinks"),
+        ];
+        let rule_set = RuleSet::new(&rules);
+        let transaction = Transaction {
+            category: Some("Other".to_owned()),
+            ..transaction(1, "Coffee", -3.0)
+        };
+
+        let filled = rule_set.apply(&transaction, RuleMode::FillEmpty);
+        assert_eq!(filled.category.as_deref(), Some("Other"));
+        assert_eq!(filled.payee.as_deref(), Some("Corner Café"));
+
+        let overwritten = rule_set.apply(&transaction, RuleMode::Overwrite);
+        assert_eq!(overwritten.category.as_deref(), Some("Drinks"));
+        assert_eq!(overwritten.payee.as_deref(), Some("Corner Café"));
+    }
+
+    #[test]
+    fn preview_is_a_dry_run_of_run_rules() {
+        let mut data = data(
+            vec![rule("salary", "Income")],
+            vec![
+                transaction(1, "Salary", 2000.0),
+                transaction(2, "Rent", -800.0),
+                Transaction { category: Some("Income".to_owned()), ..transaction(3, "Salary bonus", 300.0) },
+            ]
+        );
+        let before = data.clone();
+
+        let changes = data.preview_rules(1);
+        assert_eq!(data, before);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].before.id, 1);
+        assert_eq!(changes[0].changed_fields(), vec![("Category", None, Some("Income"))]);
+
+        assert_eq!(data.run_rules(1), 1);
+        let wallet = data.find_wallet_by_id(1).unwrap();
+        assert_eq!(wallet.find_transaction_by_id(1).unwrap().category.as_deref(), Some("Income"));
+        assert_eq!(wallet.find_transaction_by_id(2).unwrap().category, None);
+        assert!(data.preview_rules(1).is_empty());
+    }
+
+    #[test]
+    fn ids_are_kept_across_saves() {
+        let rule = rule("coffee", "Food");
+        let json = serde_json::to_string(&rule).unwrap();
+        let loaded: Rule = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.id, rule.id);
+        assert_eq!(loaded, rule);
+    }
+}
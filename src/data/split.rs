@@ -0,0 +1,27 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Split {
+    pub amount: f64,
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+impl Split {
+
+    /// Name of the first field holding a value that can't be right.
+    pub fn invalid_field(&self) -> Option<&'static str> {
+        if self.amount == 0.0 || !self.amount.is_finite() {
+            return Some("amount");
+        }
+        if self.category.trim().is_empty() || self.category.len() > 100 {
+            return Some("category");
+        }
+        if self.memo.as_ref().is_some_and(|memo| memo.len() > 500) {
+            return Some("memo");
+        }
+        None
+    }
+
+}
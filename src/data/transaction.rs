@@ -2,9 +2,10 @@
 use super::*;
 use serde::{Serialize, Deserialize, Deserializer};
 use chrono::{Local, NaiveDate};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::BTreeMap;
+use crate::i18n::{gettext, gettext_noop};
 
-static GLOBAL_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
+pub const UNCATEGORIZED: &str = gettext_noop("Uncategorized");
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Transaction {
@@ -15,7 +16,11 @@ pub struct Transaction {
     pub amount: f64,
     pub cycle: Cycle,
     pub start_date: NaiveDate,
-    pub end_date: Option<NaiveDate>
+    pub end_date: Option<NaiveDate>,
+    pub payee: Option<String>,
+    pub category: Option<String>,
+    pub splits: Vec<Split>,
+    pub statuses: BTreeMap<NaiveDate, OccurrenceStatus>,
 }
 
 impl Default for Transaction {
@@ -28,6 +33,10 @@ impl Default for Transaction {
             start_date: Local::now().naive_local().date(),
             end_date: None,
             cycle: Cycle::OneTime,
+            payee: None,
+            category: None,
+            splits: Vec::new(),
+            statuses: BTreeMap::new(),
         }
     }
 }
@@ -39,7 +48,7 @@ impl Transaction {
             self
         } else {
             Self {
-                id: GLOBAL_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+                id: generate_id(),
                 ..self
             }
         }
@@ -51,27 +60,41 @@ impl Transaction {
     }
 
     pub fn is_valid(&self) -> bool {
+        self.invalid_field().is_none()
+    }
+
+    /// Path of the first field holding a value that can't be right, such as `splits[1].category`.
+    pub fn invalid_field(&self) -> Option<String> {
         if self.name.trim().is_empty() || self.name.len() > 100 {
-            return false;
+            return Some("name".to_string());
         }
 
-        if let Some(desc) = &self.description {
-            if desc.len() > 500 {
-                return false;
-            }
+        if self.description.as_ref().is_some_and(|desc| desc.len() > 500) {
+            return Some("description".to_string());
         }
 
         if self.amount == 0.0 {
-            return false;
+            return Some("amount".to_string());
+        }
+
+        if self.end_date.is_some_and(|end_date| self.start_date > end_date) {
+            return Some("end_date".to_string());
+        }
+
+        if self.category.as_ref().is_some_and(|category| category.trim().is_empty() || category.len() > 100) {
+            return Some("category".to_string());
         }
 
-        if let Some(end_date) = self.end_date {
-            if self.start_date > end_date {
-                return false;
+        for (index, split) in self.splits.iter().enumerate() {
+            if let Some(field) = split.invalid_field() {
+                return Some(format!("splits[{}].{}", index, field));
             }
         }
+        if !self.splits.is_empty() && self.unallocated_amount().abs() >= 0.005 {
+            return Some("splits".to_string());
+        }
 
-        return true
+        None
     }
 
     pub fn is_different(&self, other: &Transaction) -> bool {
@@ -81,6 +104,59 @@ impl Transaction {
             || self.start_date != other.start_date
             || self.end_date != other.end_date
             || self.cycle != other.cycle
+            || self.payee != other.payee
+            || self.category != other.category
+            || self.splits != other.splits
+            || self.statuses != other.statuses
+    }
+
+    pub fn unallocated_amount(&self) -> f64 {
+        self.amount - self.splits.iter().map(|s| s.amount).sum::<f64>()
+    }
+
+    pub fn category_amounts(&self) -> Vec<(String, f64)> {
+        if self.splits.is_empty() {
+            let category = self.category.clone().unwrap_or_else(|| gettext(UNCATEGORIZED));
+            vec![(category, self.amount)]
+        } else {
+            self.splits
+                .iter()
+                .map(|s| (s.category.clone(), s.amount))
+                .collect()
+        }
+    }
+
+    pub fn map_amounts(&self, f: impl Fn(f64) -> f64) -> Transaction {
+        Transaction {
+            amount: f(self.amount),
+            splits: self.splits
+                .iter()
+                .map(|s| Split { amount: f(s.amount), ..s.clone() })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    pub fn status(&self, date: NaiveDate) -> OccurrenceStatus {
+        self.statuses.get(&date).copied().unwrap_or_default()
+    }
+
+    pub fn set_status(&mut self, date: NaiveDate, status: OccurrenceStatus) {
+        if status == OccurrenceStatus::Scheduled {
+            self.statuses.remove(&date);
+        } else {
+            self.statuses.insert(date, status);
+        }
+    }
+
+    pub fn mark_paid(&mut self, date: NaiveDate) {
+        if !self.status(date).is_cleared() {
+            self.set_status(date, OccurrenceStatus::Cleared);
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.statuses.values().any(|status| status.is_locked())
     }
 
     fn get_occurrences_in_period(&self, period: &Period) -> Option<Vec<NaiveDate>> {
@@ -112,28 +188,72 @@ impl Transaction {
         Some(occurrences)
     }
 
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let last_date = self.end_date.map_or(end, |end_date| end_date.min(end));
+        let mut occurrences = Vec::new();
+        let mut current_ocurrence = self.start_date;
+
+        while current_ocurrence <= last_date {
+            if current_ocurrence >= start {
+                occurrences.push(current_ocurrence);
+            }
+
+            if let Some(ocurrence) = self.cycle.next(current_ocurrence) {
+                current_ocurrence = ocurrence;
+            } else {
+                break;
+            }
+        }
+
+        occurrences
+    }
+
+    pub fn next_occurrence(&self, from: NaiveDate) -> Option<NaiveDate> {
+        let mut current_ocurrence = self.start_date;
+        while current_ocurrence < from {
+            current_ocurrence = self.cycle.next(current_ocurrence)?;
+        }
+        match self.end_date {
+            Some(end_date) if current_ocurrence > end_date => None,
+            _ => Some(current_ocurrence),
+        }
+    }
+
     pub fn count_occurrences_in_period(&self, period: &Period) -> Option<usize> {
         self.get_occurrences_in_period(period)
             .map(|occurrences| occurrences.len())
     }
 
     pub fn for_period(&self, period: &Period) -> Transaction {
-        let occurrences = self.count_occurrences_in_period(period);
-        Transaction {
-            amount: self.amount * (occurrences.unwrap_or(0) as f64),
-            ..self.clone()
-        }
+        let occurrences = self.count_occurrences_in_period(period).unwrap_or(0) as f64;
+        self.map_amounts(|amount| amount * occurrences)
     }
 }
 
 #[derive(Deserialize, Serialize)]
 struct TransactionHelper {
+    #[serde(default)]
+    id: usize,
     name: String,
     description: Option<String>,
     amount: f64,
     cycle: Cycle,
     start_date: String,
     end_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    splits: Vec<Split>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    statuses: BTreeMap<NaiveDate, OccurrenceStatus>,
+}
+
+impl Identified for Transaction {
+    fn id_mut(&mut self) -> &mut usize {
+        &mut self.id
+    }
 }
 
 impl<'de> Deserialize<'de> for Transaction {
@@ -158,13 +278,21 @@ impl<'de> Deserialize<'de> for Transaction {
         }
 
         Ok(Transaction {
-            id: GLOBAL_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+            id: if helper.id != 0 {
+                helper.id
+            } else {
+                legacy_id(&format!("{}\n{}\n{:?}\n{}", helper.name, helper.amount, helper.cycle, helper.start_date))
+            },
             name: helper.name,
             description: helper.description,
             amount: helper.amount,
             cycle: helper.cycle,
             start_date,
             end_date,
+            payee: helper.payee,
+            category: helper.category,
+            splits: helper.splits,
+            statuses: helper.statuses,
         })
     }
 }
@@ -175,12 +303,17 @@ impl Serialize for Transaction {
         S: serde::Serializer
     {
         let helper = TransactionHelper {
+            id: self.id,
             name: self.name.clone(),
             description: self.description.clone(),
             amount: self.amount,
             cycle: self.cycle.clone(),
             start_date: self.start_date.format("%Y-%m-%d").to_string(),
             end_date: self.end_date.map(|date| date.format("%Y-%m-%d").to_string()),
+            payee: self.payee.clone(),
+            category: self.category.clone(),
+            splits: self.splits.clone(),
+            statuses: self.statuses.clone(),
         };
         
         helper.serialize(serializer)
@@ -0,0 +1,208 @@
+use super::*;
+use serde::{Serialize, Deserialize};
+use chrono::{Datelike, Local, NaiveDate};
+use std::cmp::Ordering;
+use crate::i18n::{gettext, gettext_noop};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Amount,
+    Date,
+    Name,
+    Cycle,
+    NextOccurrence,
+}
+
+impl SortKey {
+
+    const SORT_KEYS: [&'static str; 5] = [
+        gettext_noop("Amount"),
+        gettext_noop("Date"),
+        gettext_noop("Name"),
+        gettext_noop("Cycle"),
+        gettext_noop("Next Occurrence")
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::SORT_KEYS[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 5] {
+        [
+            Self::Amount,
+            Self::Date,
+            Self::Name,
+            Self::Cycle,
+            Self::NextOccurrence
+        ]
+    }
+
+    /// Transactions without a value for the key (zero amount, no upcoming
+    /// occurrence) are kept at the end regardless of the direction.
+    fn is_missing(&self, transaction: &Transaction, today: NaiveDate) -> bool {
+        match self {
+            Self::Amount => transaction.amount == 0.0,
+            Self::NextOccurrence => transaction.next_occurrence(today).is_none(),
+            _ => false,
+        }
+    }
+
+    fn compare(&self, a: &Transaction, b: &Transaction, today: NaiveDate) -> Ordering {
+        match self {
+            Self::Amount => a.amount.partial_cmp(&b.amount).unwrap_or(Ordering::Equal),
+            Self::Date => a.start_date.cmp(&b.start_date),
+            Self::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Self::Cycle => (a.cycle as usize).cmp(&(b.cycle as usize)),
+            Self::NextOccurrence => a.next_occurrence(today).cmp(&b.next_occurrence(today)),
+        }
+    }
+
+}
+
+impl std::fmt::Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", gettext(self.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    None,
+    Month,
+    Cycle,
+    Direction,
+}
+
+impl GroupBy {
+
+    const GROUPS: [&'static str; 4] = [
+        gettext_noop("No Grouping"),
+        gettext_noop("Month"),
+        gettext_noop("Cycle"),
+        gettext_noop("Income / Expense")
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::GROUPS[*self as usize]
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 4] {
+        [
+            Self::None,
+            Self::Month,
+            Self::Cycle,
+            Self::Direction
+        ]
+    }
+
+    fn key(&self, transaction: &Transaction) -> (i64, String) {
+        match self {
+            Self::None => (0, String::new()),
+            Self::Month => {
+                let date = transaction.start_date;
+                (date.year() as i64 * 12 + date.month0() as i64, Locale::month_and_year(date))
+            },
+            Self::Cycle => (transaction.cycle as i64, transaction.cycle.to_string()),
+            Self::Direction if transaction.amount >= 0.0 => (0, gettext("Income")),
+            Self::Direction => (1, gettext("Expenses")),
+        }
+    }
+
+}
+
+impl std::fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", gettext(self.as_str()))
+    }
+}
+
+/// How a transaction list is ordered and split into sections.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ViewOptions {
+    #[serde(default)]
+    pub sort_key: SortKey,
+    #[serde(default = "ViewOptions::default_descending")]
+    pub descending: bool,
+    #[serde(default)]
+    pub group_by: GroupBy,
+}
+
+impl Default for ViewOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ViewOptions {
+
+    pub const DEFAULT: Self = Self {
+        sort_key: SortKey::Amount,
+        descending: Self::default_descending(),
+        group_by: GroupBy::None,
+    };
+
+    const fn default_descending() -> bool {
+        true
+    }
+
+    pub fn sort<'a>(&self, transactions: impl IntoIterator<Item = &'a Transaction>) -> Vec<&'a Transaction> {
+        let today = Local::now().naive_local().date();
+        let mut transactions: Vec<&Transaction> = transactions.into_iter().collect();
+        transactions.sort_by(|a, b| {
+            let missing = self.sort_key.is_missing(a, today).cmp(&self.sort_key.is_missing(b, today));
+            let ordering = self.sort_key.compare(a, b, today);
+            missing.then(if self.descending { ordering.reverse() } else { ordering })
+        });
+        transactions
+    }
+
+    /// Sorts the transactions and splits them into titled sections. Without
+    /// grouping a single untitled section is returned.
+    pub fn arrange<'a>(&self, transactions: impl IntoIterator<Item = &'a Transaction>) -> Vec<(Option<String>, Vec<&'a Transaction>)> {
+        let sorted = self.sort(transactions);
+        if self.group_by == GroupBy::None {
+            return vec![(None, sorted)];
+        }
+
+        let mut groups: Vec<((i64, String), Vec<&Transaction>)> = Vec::new();
+        for transaction in sorted {
+            let key = self.group_by.key(transaction);
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(transaction),
+                None => groups.push((key, vec![transaction])),
+            }
+        }
+        groups.sort_by_key(|((order, _), _)| *order);
+
+        groups
+            .into_iter()
+            .map(|((_, title), transactions)| (Some(title), transactions))
+            .collect()
+    }
+
+}
+
+/// The view options of each page, kept per user in the settings rather
+/// than in the shared document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewSettings {
+    pub wallet: ViewOptions,
+    pub overview: ViewOptions,
+}
+
+impl ViewSettings {
+
+    pub const DEFAULT: Self = Self {
+        wallet: ViewOptions::DEFAULT,
+        overview: ViewOptions::DEFAULT,
+    };
+
+}
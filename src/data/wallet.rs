@@ -1,18 +1,24 @@
 use super::*;
 use serde::{Serialize, Deserialize, Deserializer};
-use std::sync::atomic::{AtomicUsize, Ordering};
-
-static GLOBAL_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
-
+use chrono::NaiveDate;
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct Wallet {
-    #[serde(skip_serializing)]
     pub id: usize,
 
     pub name: String,
     pub description: Option<String>,
     pub currency: Currency,
     pub transactions: Vec<Transaction>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub opening_balance: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opening_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<BalanceAssertion>,
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
 }
 
 impl Default for Wallet {
@@ -23,6 +29,9 @@ impl Default for Wallet {
             description: None,
             currency: Currency::USD,
             transactions: Vec::new(),
+            opening_balance: 0.0,
+            opening_date: None,
+            assertions: Vec::new(),
         }
     }
 }
@@ -34,7 +43,7 @@ impl Wallet {
             self
         } else {
             Self {
-                id: GLOBAL_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+                id: generate_id(),
                 ..self
             }
         }
@@ -46,17 +55,37 @@ impl Wallet {
     }
 
     pub fn is_valid(&self) -> bool {
-        let name_valid = !self.name.trim().is_empty() && self.name.len() <= 100;
-        let description_valid = self.description.as_ref().map_or(true, |desc| desc.len() <= 500);
-        let transactions_valid = self.transactions.iter().all(|t| t.is_valid());
+        self.invalid_field().is_none()
+    }
 
-        name_valid && description_valid && transactions_valid
+    /// Path of the first field holding a value that can't be right, such as `transactions[3].amount`.
+    pub fn invalid_field(&self) -> Option<String> {
+        if self.name.trim().is_empty() || self.name.len() > 100 {
+            return Some("name".to_string());
+        }
+        if self.description.as_ref().is_some_and(|desc| desc.len() > 500) {
+            return Some("description".to_string());
+        }
+        if !self.opening_balance.is_finite() {
+            return Some("opening_balance".to_string());
+        }
+        self.transactions
+            .iter()
+            .enumerate()
+            .find_map(|(index, transaction)| {
+                transaction
+                    .invalid_field()
+                    .map(|field| format!("transactions[{}].{}", index, field))
+            })
     }
 
     pub fn is_different(&self, other: &Wallet) -> bool {
         self.name != other.name
             || self.description != other.description
             || self.currency != other.currency
+            || self.opening_balance != other.opening_balance
+            || self.opening_date != other.opening_date
+            || self.assertions != other.assertions
             || self.transactions
                 .iter()
                 .zip(other.transactions.iter())
@@ -81,11 +110,70 @@ impl Wallet {
         }
     }
 
+    /// Income and expenses of the transactions. The opening balance is no income,
+    /// so it only counts in `balance_at` and the net worth.
     pub fn balance(&self) -> Balance {
         let transactions: Vec<&Transaction> = self.transactions.iter().collect();
         Balance::from_transactions(&transactions)
     }
 
+    pub fn balance_at(&self, date: NaiveDate) -> f64 {
+        if self.opening_date.is_some_and(|opening_date| opening_date > date) {
+            return 0.0;
+        }
+
+        let start = self.opening_date
+            .and_then(|opening_date| opening_date.succ_opt())
+            .unwrap_or(NaiveDate::MIN);
+
+        self.transactions
+            .iter()
+            .map(|t| t.amount * t.occurrences_between(start, date).len() as f64)
+            .fold(self.opening_balance, |acc, amount| acc + amount)
+    }
+
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<Occurrence> {
+        let mut occurrences: Vec<Occurrence> = self.transactions
+            .iter()
+            .flat_map(|transaction| transaction
+                .occurrences_between(start, end)
+                .into_iter()
+                .map(move |date| Occurrence::new(self, transaction, date))
+            )
+            .collect();
+        occurrences.sort_by_key(|o| o.date);
+        occurrences
+    }
+
+    pub fn set_occurrence_status(&mut self, transaction_id: usize, date: NaiveDate, status: OccurrenceStatus) {
+        if let Some(transaction) = self.transactions.iter_mut().find(|t| t.id == transaction_id) {
+            transaction.set_status(date, status);
+        }
+    }
+
+    pub fn add_or_update_assertion(&mut self, assertion: BalanceAssertion) {
+        if let Some(index) = self.assertions.iter().position(|a| a.date == assertion.date) {
+            self.assertions[index] = assertion;
+        } else {
+            self.assertions.push(assertion);
+            self.assertions.sort_by_key(|a| a.date);
+        }
+    }
+
+    pub fn remove_assertion_by_date(&mut self, date: NaiveDate) {
+        self.assertions.retain(|a| a.date != date);
+    }
+
+    pub fn check_assertions(&self) -> Vec<Discrepancy> {
+        self.assertions
+            .iter()
+            .map(|assertion| Discrepancy {
+                assertion: *assertion,
+                computed_balance: self.balance_at(assertion.date),
+            })
+            .collect()
+    }
+
     pub fn for_period(&self, period: Period) -> Wallet {
         let filtered_transactions: Vec<Transaction> = self.transactions
             .iter()
@@ -98,35 +186,31 @@ impl Wallet {
         }
     }
 
+    /// Amounts that can't be converted while the rates are not loaded are kept
+    /// as they are, see `Data::has_unconverted_amounts`.
     pub fn convert_to_currency(&self, target_currency: Currency) -> Self {
+        let convert = |amount: f64| self.currency.convert_amount(amount, target_currency).unwrap_or(amount);
         let transactions = self.transactions
             .iter()
-            .map(|t| {
-                let amount = self.currency.convert_amount(t.amount, target_currency);
-                Transaction {
-                    amount,
-                    ..t.clone()
-                }
+            .map(|t| t.map_amounts(convert))
+            .collect();
+        let assertions = self.assertions
+            .iter()
+            .map(|a| BalanceAssertion {
+                balance: convert(a.balance),
+                ..*a
             })
             .collect();
     
         Self {
             currency: target_currency,
             transactions,
+            opening_balance: convert(self.opening_balance),
+            assertions,
             ..self.clone()
         }
     }
 
-    pub fn sort_transactions_by_amount(&mut self) {
-        self.transactions.sort_by(|a, b| {
-            match (a.amount == 0.0, b.amount == 0.0) {
-                (true, false) => std::cmp::Ordering::Greater,
-                (false, true) => std::cmp::Ordering::Less,
-                _ => b.amount.partial_cmp(&a.amount).unwrap_or(std::cmp::Ordering::Equal)
-            }
-        });
-    }
-
 }
 
 impl std::fmt::Display for Wallet {
@@ -137,10 +221,25 @@ impl std::fmt::Display for Wallet {
 
 #[derive(Deserialize)]
 struct WalletHelper {
+    #[serde(default)]
+    id: usize,
     name: String,
     description: Option<String>,
     currency: Currency,
+    #[serde(deserialize_with = "deserialize_unique")]
     transactions: Vec<Transaction>,
+    #[serde(default)]
+    opening_balance: f64,
+    #[serde(default)]
+    opening_date: Option<NaiveDate>,
+    #[serde(default)]
+    assertions: Vec<BalanceAssertion>,
+}
+
+impl Identified for Wallet {
+    fn id_mut(&mut self) -> &mut usize {
+        &mut self.id
+    }
 }
 
 impl<'de> Deserialize<'de> for Wallet {
@@ -151,11 +250,71 @@ impl<'de> Deserialize<'de> for Wallet {
         let helper = WalletHelper::deserialize(deserializer)?;
         
         Ok(Wallet {
-            id: GLOBAL_ID_COUNTER.fetch_add(1, Ordering::Relaxed),
+            id: if helper.id != 0 { helper.id } else { legacy_id(&format!("{}\n{}", helper.name, helper.currency.as_short_str())) },
             name: helper.name,
             description: helper.description,
             currency: helper.currency,
             transactions: helper.transactions,
+            opening_balance: helper.opening_balance,
+            opening_date: helper.opening_date,
+            assertions: helper.assertions,
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn wallet(opening_balance: f64) -> Wallet {
+        let today = Local::now().naive_local().date();
+        Wallet {
+            id: 1,
+            name: "Checking".to_owned(),
+            opening_balance,
+            opening_date: today.pred_opt(),
+            transactions: vec![Transaction {
+                id: 1,
+                name: "Groceries".to_owned(),
+                amount: -50.0,
+                start_date: today,
+                ..Transaction::default()
+            }],
+            ..Wallet::default()
+        }
+    }
+
+    #[test]
+    fn opening_balance_is_not_period_income() {
+        for period in [Period::Day, Period::Week, Period::Month, Period::Year] {
+            let balance = wallet(1000.0).for_period(period).balance();
+            assert_eq!(balance.income, 0.0);
+            assert_eq!(balance.expense, 50.0);
+        }
+    }
+
+    #[test]
+    fn opening_balance_counts_in_the_balance_and_net_worth() {
+        let today = Local::now().naive_local().date();
+        assert_eq!(wallet(1000.0).balance_at(today), 950.0);
+
+        let data = Data {
+            wallets: vec![wallet(1000.0)],
+            ..Data::default()
+        };
+        assert_eq!(data.net_worth(), 950.0);
+        assert_eq!(data.total_balance_for_period().net_balance(), -50.0);
+    }
+
+    #[test]
+    fn occurrence_status_changes_are_differences() {
+        let today = Local::now().naive_local().date();
+        let previous = wallet(0.0);
+        let mut cleared = previous.clone();
+        cleared.transactions[0].set_status(today, OccurrenceStatus::Cleared);
+
+        assert!(cleared.is_different(&previous));
+        cleared.transactions[0].set_status(today, OccurrenceStatus::Scheduled);
+        assert!(!cleared.is_different(&previous));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::metadata;
+
+use gtk::glib;
+use std::path::PathBuf;
+
+pub use gettextrs::{gettext, ngettext};
+
+/// Marks a string for extraction without translating it, for labels kept in
+/// `const` tables. The caller translates it with [`gettext`] when displayed.
+pub const fn gettext_noop(msgid: &'static str) -> &'static str {
+    msgid
+}
+
+fn replace_arguments(mut text: String, arguments: &[(&str, &str)]) -> String {
+    for (name, value) in arguments {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Translates `msgid` and replaces each `{name}` placeholder with its value.
+pub fn gettext_f(msgid: &str, arguments: &[(&str, &str)]) -> String {
+    replace_arguments(gettext(msgid), arguments)
+}
+
+/// Plural aware version of [`gettext_f`], picking the form for `n`.
+pub fn ngettext_f(msgid: &str, msgid_plural: &str, n: u32, arguments: &[(&str, &str)]) -> String {
+    replace_arguments(ngettext(msgid, msgid_plural, n), arguments)
+}
+
+fn locale_directory() -> PathBuf {
+    let installed = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent()?.parent().map(|prefix| prefix.join("share").join("locale")))
+        .filter(|directory| directory.exists());
+
+    installed.unwrap_or_else(|| PathBuf::from(metadata::APP_LOCALEDIR))
+}
+
+pub fn init() {
+    gettextrs::setlocale(gettextrs::LocaleCategory::LcAll, "");
+    if let Err(e) = gettextrs::bindtextdomain(metadata::APP_NAME, locale_directory()) {
+        glib::g_warning!(metadata::APP_NAME, "Failed to bind the text domain: {}", e);
+    }
+    if let Err(e) = gettextrs::bind_textdomain_codeset(metadata::APP_NAME, "UTF-8") {
+        glib::g_warning!(metadata::APP_NAME, "Failed to set the text domain encoding: {}", e);
+    }
+    if let Err(e) = gettextrs::textdomain(metadata::APP_NAME) {
+        glib::g_warning!(metadata::APP_NAME, "Failed to set the text domain: {}", e);
+    }
+}
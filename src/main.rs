@@ -10,8 +10,12 @@ mod metadata;
 mod prelude;
 mod window;
 mod context;
+mod reminders;
+mod settings;
+mod i18n;
 mod utils;
 
 fn main() {
+    i18n::init();
     app::App::new().init();
 }
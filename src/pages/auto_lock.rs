@@ -0,0 +1,86 @@
+use crate::prelude::*;
+
+use gtk::glib;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL_SECONDS: u32 = 15;
+
+static TIMEOUT_MINUTES: AtomicU32 = AtomicU32::new(5);
+
+/// Locks an encrypted document after the window has seen no key presses
+/// or pointer motion for the configured number of minutes.
+pub struct AutoLock {
+    last_activity: Rc<Cell<Instant>>,
+    source: Rc<RefCell<Option<glib::SourceId>>>,
+}
+
+impl AutoLock {
+
+    pub fn new() -> Self {
+        Self {
+            last_activity: Rc::new(Cell::new(Instant::now())),
+            source: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Minutes of inactivity before locking, where 0 never locks.
+    pub fn timeout_minutes() -> u32 {
+        TIMEOUT_MINUTES.load(Ordering::Relaxed)
+    }
+
+    pub fn set_timeout_minutes(minutes: u32) {
+        TIMEOUT_MINUTES.store(minutes, Ordering::Relaxed);
+    }
+
+    /// Counts input on `widget` and its descendants as activity.
+    pub fn track(&self, widget: &impl IsA<gtk::Widget>) {
+        let key_controller = gtk::EventControllerKey::new();
+        key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+        let last_activity = self.last_activity.clone();
+        key_controller.connect_key_pressed(move |_, _, _, _| {
+            last_activity.set(Instant::now());
+            glib::Propagation::Proceed
+        });
+        widget.add_controller(key_controller);
+
+        let motion_controller = gtk::EventControllerMotion::new();
+        let last_activity = self.last_activity.clone();
+        motion_controller.connect_motion(move |_, _, _| {
+            last_activity.set(Instant::now());
+        });
+        widget.add_controller(motion_controller);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.source.borrow().is_some()
+    }
+
+    /// Calls `callback` once the timeout passes without activity.
+    pub fn start(&self, callback: impl Fn() + 'static) {
+        self.stop();
+        self.last_activity.set(Instant::now());
+
+        let last_activity = self.last_activity.clone();
+        let source = self.source.clone();
+        let source_id = glib::timeout_add_seconds_local(CHECK_INTERVAL_SECONDS, move || {
+            let timeout = Duration::from_secs(Self::timeout_minutes() as u64 * 60);
+            if timeout.is_zero() || last_activity.get().elapsed() < timeout {
+                return glib::ControlFlow::Continue;
+            }
+            source.take();
+            callback();
+            glib::ControlFlow::Break
+        });
+        self.source.replace(Some(source_id));
+    }
+
+    pub fn stop(&self) {
+        if let Some(source_id) = self.source.take() {
+            source_id.remove();
+        }
+    }
+
+}
@@ -0,0 +1,82 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::Data;
+
+use gtk::gio;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// Watches the document shown in a window for changes made by other programs,
+/// such as a sync client, and holds its advisory lock while it is open.
+pub struct FileWatcher {
+    monitor: RefCell<Option<gio::FileMonitor>>,
+    lock: RefCell<Option<FileLock>>,
+    path: RefCell<PathBuf>,
+    synced: RefCell<Data>,
+}
+
+impl FileWatcher {
+
+    pub fn new() -> Self {
+        Self {
+            monitor: RefCell::new(None),
+            lock: RefCell::new(None),
+            path: RefCell::new(PathBuf::new()),
+            synced: RefCell::new(Data::default()),
+        }
+    }
+
+    /// Serialized form of a document, used to tell documents apart.
+    pub fn fingerprint(data: &Data) -> String {
+        serde_json::to_string(data).unwrap_or_default()
+    }
+
+    pub fn is_watching(&self, file: &DataFile) -> bool {
+        self.monitor.borrow().is_some() && *self.path.borrow() == file.path
+    }
+
+    /// Starts watching `file`, whose content on disk matches `data`.
+    /// Fails when another instance holds the lock, but watches the file anyway.
+    pub fn watch(&self, file: &DataFile, data: &Data, callback: impl Fn() + 'static) -> std::io::Result<()> {
+        self.stop();
+        self.path.replace(file.path.clone());
+        self.synced.replace(data.clone());
+
+        let monitor = gio::File::for_path(&file.path)
+            .monitor_file(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+            .map_err(std::io::Error::other)?;
+        monitor.connect_changed(move |_, _, _, event| {
+            if matches!(event, gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created | gio::FileMonitorEvent::MovedIn | gio::FileMonitorEvent::Renamed) {
+                callback();
+            }
+        });
+        self.monitor.replace(Some(monitor));
+
+        let lock = FileLock::acquire(file)?;
+        self.lock.replace(Some(lock));
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        if let Some(monitor) = self.monitor.take() {
+            monitor.cancel();
+        }
+        self.lock.take();
+        self.path.replace(PathBuf::new());
+    }
+
+    /// Whether `data` differs from the document last read from or written to disk.
+    pub fn has_changes(&self, data: &Data) -> bool {
+        Self::fingerprint(&self.synced.borrow()) != Self::fingerprint(data)
+    }
+
+    /// The document last read from or written to disk, the common ancestor when merging.
+    pub fn synced(&self) -> Data {
+        self.synced.borrow().clone()
+    }
+
+    pub fn set_synced(&self, data: &Data) {
+        self.synced.replace(data.clone());
+    }
+
+}
@@ -2,15 +2,47 @@ mod status;
 mod overview;
 mod transaction;
 mod wallet;
+mod upcoming;
+mod payees;
+mod payee;
+mod rules;
+mod rule;
+mod search;
 mod navigation_page;
+mod sidebar;
+mod file_watcher;
+mod auto_lock;
 
 use crate::prelude::*;
 use crate::context::*;
+use crate::data::{Data, DataError, Preferences, RateProvider};
+use crate::utils::{AlertButton, AlertButtonType};
+use crate::metadata;
+use crate::settings::Settings;
 
+use gtk::{gio, glib};
 use navigation_page::NavigationPage;
-use std::rc::Rc;
+use sidebar::Sidebar;
+use file_watcher::FileWatcher;
+pub use auto_lock::AutoLock;
+pub use upcoming::DEFAULT_DAYS as DEFAULT_UPCOMING_DAYS;
+use std::rc::{Rc, Weak};
 use std::collections::HashMap;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const UNDO_LIMIT: usize = 50;
+/// Below this width the sidebar is folded away and the pages fill the window.
+const SIDEBAR_BREAKPOINT: &str = "max-width: 720sp";
+/// First wait before fetching exchange rates again, doubled after each failure up to 32 minutes.
+const RATE_RETRY_SECONDS: u32 = 60;
+const RATE_RETRY_DOUBLINGS: u32 = 5;
+/// How often a change seen on disk while this window was saving is checked again.
+const SAVE_POLL_MILLISECONDS: u64 = 200;
+
+/// Pages of every open window, shared so a document is only open in one window at a time.
+pub type OpenPages = Rc<RefCell<Vec<Weak<Pages>>>>;
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Page {
@@ -18,42 +50,113 @@ pub enum Page {
     Status,
     Overview,
     Transaction,
-    Wallet
+    Wallet,
+    Upcoming,
+    Payees,
+    Payee,
+    Rules,
+    Rule,
+    Search
+}
+
+impl Page {
+
+    /// Pages without text entries, where Ctrl+Z can't be meant for an entry.
+    const fn allows_undo(&self) -> bool {
+        matches!(self, Page::Overview | Page::Upcoming | Page::Payees | Page::Rules)
+    }
+
+    /// Pages that may hold edits which are only saved on request.
+    const fn is_editor(&self) -> bool {
+        matches!(self, Page::Transaction | Page::Wallet | Page::Payee | Page::Rule)
+    }
+
+    /// Pages that show the same content after the document is reloaded from disk.
+    const fn survives_reload(&self) -> bool {
+        matches!(self, Page::Overview | Page::Upcoming | Page::Payees | Page::Search)
+    }
+
 }
 
 pub struct Pages {
+    breakpoint_bin: adw::BreakpointBin,
+    split_view: adw::OverlaySplitView,
+    sidebar: Rc<Sidebar>,
     navigation_view: adw::NavigationView,
     pages: HashMap<Page, NavigationPage>,
+    action_group: gio::SimpleActionGroup,
     history: RefCell<Vec<NavigationAction>>,
-    last_context: RefCell<Context>
+    undo_history: RefCell<Vec<Data>>,
+    is_undoing: Cell<bool>,
+    last_context: RefCell<Context>,
+    open_pages: OpenPages,
+    file_watcher: FileWatcher,
+    auto_lock: AutoLock,
+    background: Background,
+    rates_requested: Cell<Option<RateProvider>>,
+    rate_failures: Cell<u32>,
+    is_waiting_for_save: Cell<bool>,
 }
 
 impl Pages {
 
-    pub fn new() -> Rc<Self> {
-        let pages = Self::create_pages();
+    pub fn new(open_pages: &OpenPages, settings: &Settings) -> Rc<Self> {
+        let pages = Self::create_pages(settings);
         let navigation_view = Self::build_navigation_view();
+        let sidebar = Sidebar::new();
+        let split_view = Self::build_split_view(&sidebar, &navigation_view);
+        let breakpoint_bin = Self::build_breakpoint_bin(&split_view);
+        let action_group = Self::build_action_group();
         Self::add_pages(&navigation_view, &pages);
         let this = Rc::new(Self {
+            breakpoint_bin,
+            split_view,
+            sidebar,
             pages,
             navigation_view,
+            action_group,
             history: RefCell::new(Vec::new()),
-            last_context: RefCell::new(Context::default())
+            undo_history: RefCell::new(Vec::new()),
+            is_undoing: Cell::new(false),
+            last_context: RefCell::new(Context::default()),
+            open_pages: open_pages.clone(),
+            file_watcher: FileWatcher::new(),
+            auto_lock: AutoLock::new(),
+            background: Background::default(),
+            rates_requested: Cell::new(None),
+            rate_failures: Cell::new(0),
+            is_waiting_for_save: Cell::new(false),
         });
+        open_pages.borrow_mut().push(Rc::downgrade(&this));
+        this.auto_lock.track(&this.breakpoint_bin);
+        this.connect_background();
         this.connect_events();
+        this.connect_actions();
         this
     }
 
-    fn create_pages() -> HashMap<Page, NavigationPage> {
+    fn create_pages(settings: &Settings) -> HashMap<Page, NavigationPage> {
         let mut pages = HashMap::new();
         let status_page = NavigationPage::new(status::Status::new());
-        let overview = NavigationPage::new(overview::Overview::new());
+        let overview = NavigationPage::new(overview::Overview::new(settings.clone()));
         let transaction = NavigationPage::new(transaction::TransactionPage::new());
-        let wallet = NavigationPage::new(wallet::WalletPage::new());
+        let wallet = NavigationPage::new(wallet::WalletPage::new(settings.clone()));
+        let upcoming = NavigationPage::new(upcoming::Upcoming::new());
+        let payees = NavigationPage::new(payees::Payees::new());
+        let payee = NavigationPage::new(payee::PayeePage::new());
+        let rules = NavigationPage::new(rules::Rules::new());
+        let rule = NavigationPage::new(rule::RulePage::new());
+        let search = NavigationPage::new(search::Search::new());
         pages.insert(Page::Status, status_page);
         pages.insert(Page::Overview, overview);
         pages.insert(Page::Transaction, transaction);
         pages.insert(Page::Wallet, wallet);
+        pages.insert(Page::Upcoming, upcoming);
+        pages.insert(Page::Payees, payees);
+        pages.insert(Page::Payee, payee);
+        pages.insert(Page::Rules, rules);
+        pages.insert(Page::Rule, rule);
+        pages.insert(Page::Search, search);
         pages
     }
 
@@ -75,6 +178,79 @@ impl Pages {
         navigation_view
     }
 
+    fn build_split_view(sidebar: &Rc<Sidebar>, navigation_view: &adw::NavigationView) -> adw::OverlaySplitView {
+        let split_view = adw::OverlaySplitView::new();
+        split_view.set_sidebar(Some(sidebar.widget()));
+        split_view.set_content(Some(navigation_view));
+        split_view.set_show_sidebar(false);
+        split_view
+    }
+
+    fn build_breakpoint_bin(split_view: &adw::OverlaySplitView) -> adw::BreakpointBin {
+        let breakpoint = adw::Breakpoint::new(
+            adw::BreakpointCondition::parse(SIDEBAR_BREAKPOINT).expect("Invalid breakpoint condition")
+        );
+        breakpoint.add_setter(split_view, "collapsed", Some(&true.to_value()));
+
+        let breakpoint_bin = adw::BreakpointBin::new();
+        breakpoint_bin.set_width_request(360);
+        breakpoint_bin.set_height_request(294);
+        breakpoint_bin.add_breakpoint(breakpoint);
+        breakpoint_bin.set_child(Some(split_view));
+        breakpoint_bin
+    }
+
+    fn build_action_group() -> gio::SimpleActionGroup {
+        let actions: Vec<gio::SimpleAction> = Shortcut::as_slice()
+            .iter()
+            .filter(|shortcut| shortcut.group_name() == WINDOW_ACTIONS)
+            .map(Shortcut::build_action)
+            .collect();
+        build_action_group(&actions.iter().collect::<Vec<_>>())
+    }
+
+    pub fn current_context(&self) -> Context {
+        self.last_context.borrow().clone()
+    }
+
+    /// Work running off the main thread for this window, shown by the page spinners.
+    pub fn background(&self) -> Background {
+        self.background.clone()
+    }
+
+    pub fn window(&self) -> Option<gtk::Window> {
+        self.navigation_view.root().and_downcast()
+    }
+
+    /// Whether a document is shown, rather than the status page.
+    pub fn is_data_open(&self) -> bool {
+        self.current_page().is_some_and(|p| p != Page::Status)
+    }
+
+    pub fn is_file_open(&self, file: &DataFile) -> bool {
+        self.is_data_open() && self.last_context.borrow().file().path == file.path
+    }
+
+    /// Finds another window that already shows the document about to be opened here.
+    fn find_pages_with_file(&self, context: &Context) -> Option<Rc<Pages>> {
+        if context.file().path.as_os_str().is_empty() || self.last_context.borrow().file().path == context.file().path {
+            return None;
+        }
+        self.open_pages
+            .borrow()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|pages| !std::ptr::eq(pages.as_ref(), self) && pages.is_file_open(context.file()))
+    }
+
+    pub fn close(&self) {
+        self.file_watcher.stop();
+        self.auto_lock.stop();
+        self.open_pages
+            .borrow_mut()
+            .retain(|pages| pages.upgrade().is_some_and(|pages| !std::ptr::eq(pages.as_ref(), self)));
+    }
+
     fn get_navigation_stack(&self) -> Vec<adw::NavigationPage> {
         self.navigation_view
             .navigation_stack()
@@ -89,6 +265,12 @@ impl Pages {
             NavigationAction::NavigateToOverview => Some(Page::Overview),
             NavigationAction::NavigateToTransaction { wallet: _, transaction: _ } => Some(Page::Transaction),
             NavigationAction::NavigateToWallet { wallet: _ } => Some(Page::Wallet),
+            NavigationAction::NavigateToUpcoming { days: _ } => Some(Page::Upcoming),
+            NavigationAction::NavigateToPayees => Some(Page::Payees),
+            NavigationAction::NavigateToPayee { payee: _ } => Some(Page::Payee),
+            NavigationAction::NavigateToRules { wallet: _ } => Some(Page::Rules),
+            NavigationAction::NavigateToRule { rule: _ } => Some(Page::Rule),
+            NavigationAction::NavigateToSearch => Some(Page::Search),
             _ => None
         }
     }
@@ -138,6 +320,16 @@ impl Pages {
         history.push(next_action);
     }
 
+    fn connect_background(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.background.connect_busy_changed(move |is_busy| {
+            let Some(this) = this.upgrade() else { return };
+            for page in this.pages.values() {
+                page.set_busy(is_busy);
+            }
+        });
+    }
+
     fn connect_events(self: &Rc<Self>) {
         let this = self.clone();
         self.navigation_view.connect_popped(move |nv, _| {
@@ -148,22 +340,365 @@ impl Pages {
                 .and_then(|p| this.pages.get(&p))
                 .map(|p| Some(p.widget()) == nv.visible_page().as_ref());
             if result == Some(false) {
-                this.pop_navigation_action()
+                this.pop_navigation_action();
+                this.update_actions();
+                this.update_sidebar();
             }
         });
+
+        let this = Rc::downgrade(self);
+        self.split_view.connect_collapsed_notify(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.update_sidebar();
+        });
     }
 
-}
+    fn current_page(&self) -> Option<Page> {
+        self.history
+            .borrow()
+            .last()
+            .and_then(|a| self.get_page_from_action(a))
+    }
 
-impl Propagator<NavigationAction> for Rc<Pages> {
-    fn propagate(&self, action: NavigationAction, context: &Context) {
-        self.last_context.replace(context.clone());
+    fn find_action(&self, shortcut: Shortcut) -> Option<gio::SimpleAction> {
+        self.action_group
+            .lookup_action(shortcut.name())
+            .and_downcast::<gio::SimpleAction>()
+    }
+
+    fn update_actions(&self) {
+        let current_page = self.current_page();
+        let is_data_open = current_page.is_some_and(|p| p != Page::Status);
+        let can_undo = current_page.is_some_and(|p| p.allows_undo()) && !self.undo_history.borrow().is_empty();
+        let can_lock = is_data_open && self.last_context.borrow().file().has_passphrase();
+
+        for (shortcut, is_enabled) in [
+            (Shortcut::Search, is_data_open),
+            (Shortcut::Lock, can_lock),
+            (Shortcut::NewWallet, is_data_open),
+            (Shortcut::Undo, can_undo),
+        ] {
+            if let Some(action) = self.find_action(shortcut) {
+                action.set_enabled(is_enabled);
+            }
+        }
+
+        let Some(root) = self.navigation_view.root() else { return };
+        let page_action_group = current_page
+            .and_then(|p| self.pages.get(&p))
+            .and_then(|p| p.action_group());
+        root.insert_action_group(WINDOW_ACTIONS, Some(&self.action_group));
+        root.insert_action_group(PAGE_ACTIONS, page_action_group);
+    }
 
+    /// Shows the sidebar next to an open document on wide windows.
+    /// When collapsed it only slides in on request and hides again after navigating.
+    fn update_sidebar(&self) {
+        let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        let last_action = self.history.borrow().last().copied();
+        self.sidebar.update(last_action, &self.last_context.borrow());
+        self.split_view.set_enable_show_gesture(is_data_open);
+        self.split_view.set_show_sidebar(is_data_open && !self.split_view.is_collapsed());
+    }
+
+    fn update_file_watcher(self: &Rc<Self>) {
+        let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        let context = self.current_context();
+        if !is_data_open {
+            return self.file_watcher.stop();
+        }
+        if self.file_watcher.is_watching(context.file()) {
+            return;
+        }
+
+        let this = Rc::downgrade(self);
+        let result = self.file_watcher.watch(context.file(), context.data(), move || {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_file_changed();
+        });
+        if result.is_err() {
+            context
+                .with_ui_action(UiAction::push_notification(gettext("Document is locked by another instance")))
+                .propagate();
+        }
+    }
+
+    /// Adds a document to the recent list when it gets opened from the status page.
+    fn record_recent_document(&self, was_data_open: bool) {
+        let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        if was_data_open || !is_data_open {
+            return;
+        }
+        if let Err(e) = RecentDocuments::add(&self.last_context.borrow().file().path) {
+            glib::g_warning!(metadata::APP_NAME, "Failed to update recent documents: {}", e);
+        }
+    }
+
+    /// Runs the inactivity timer while an encrypted document is open.
+    fn update_auto_lock(self: &Rc<Self>) {
+        let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        if !is_data_open || !self.last_context.borrow().file().has_passphrase() {
+            return self.auto_lock.stop();
+        }
+        if self.auto_lock.is_running() {
+            return;
+        }
+
+        let this = Rc::downgrade(self);
+        self.auto_lock.start(move || {
+            let Some(this) = this.upgrade() else { return };
+            this.current_context().lock();
+        });
+    }
+
+    /// Fetches exchange rates on a worker thread the first time an open document
+    /// has wallets in other currencies, then shows the converted amounts.
+    /// Failed fetches are retried later.
+    fn update_exchange_rates(self: &Rc<Self>) {
+        let provider = Preferences::current().rate_provider;
+        let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        if !is_data_open
+            || !self.last_context.borrow().data().needs_exchange_rates()
+            || provider.has_rates()
+            || self.rates_requested.get() == Some(provider) {
+            return;
+        }
+
+        self.rates_requested.set(Some(provider));
+        let this = Rc::downgrade(self);
+        self.background.spawn(
+            move || provider.load_rates(),
+            move |result| {
+                let Some(this) = this.upgrade() else { return };
+                if let Err(e) = result {
+                    return this.retry_exchange_rates(e);
+                }
+                this.rate_failures.set(0);
+                let context = this.current_context();
+                if this.current_page().is_some_and(|p| p.survives_reload()) {
+                    context
+                        .with_navigation_action(NavigationAction::NavigateToCurrent)
+                        .propagate();
+                }
+            }
+        );
+    }
+
+    /// Tries again with a growing delay, telling only about the first failure.
+    fn retry_exchange_rates(self: &Rc<Self>, error: DataError) {
+        let failures = self.rate_failures.get();
+        self.rate_failures.set(failures + 1);
+        if failures == 0 {
+            self.current_context()
+                .with_ui_action(UiAction::push_error(gettext("Exchange rates are unavailable"), error))
+                .propagate();
+        }
+
+        let delay = RATE_RETRY_SECONDS << failures.min(RATE_RETRY_DOUBLINGS);
+        let this = Rc::downgrade(self);
+        glib::timeout_add_seconds_local_once(delay, move || {
+            let Some(this) = this.upgrade() else { return };
+            this.rates_requested.set(None);
+            this.update_exchange_rates();
+        });
+    }
+
+    /// Reloads a document changed by another program, or asks what to keep
+    /// when this window changed it too.
+    fn handle_file_changed(self: &Rc<Self>) {
+        let file = self.current_context().file().clone();
+        if SaveQueue::is_pending(&file.path) {
+            return self.handle_file_changed_after_save();
+        }
+
+        let this = Rc::downgrade(self);
+        self.background.spawn(
+            move || file.load().map(|data| (file.path, data)),
+            move |result| {
+                let Some(this) = this.upgrade() else { return };
+                let Ok((path, data)) = result else { return };
+                this.handle_file_loaded(path, data);
+            }
+        );
+    }
+
+    /// Checks the file again once the saves of this window are written, since a
+    /// change seen meanwhile is this window's own save or comes on top of it.
+    fn handle_file_changed_after_save(self: &Rc<Self>) {
+        if self.is_waiting_for_save.replace(true) { return }
+        let this = Rc::downgrade(self);
+        glib::timeout_add_local(Duration::from_millis(SAVE_POLL_MILLISECONDS), move || {
+            let Some(this) = this.upgrade() else { return glib::ControlFlow::Break };
+            if SaveQueue::is_pending(&this.current_context().file().path) {
+                return glib::ControlFlow::Continue;
+            }
+            this.is_waiting_for_save.set(false);
+            this.handle_file_changed();
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Compares what was read from disk with the open document, once the read has finished.
+    /// An open editor counts as a local edit, since reloading would throw away what was typed in it.
+    fn handle_file_loaded(self: &Rc<Self>, path: PathBuf, data: Data) {
+        let context = self.current_context();
+        if context.file().path != path { return }
+        if SaveQueue::is_pending(&path) {
+            return self.handle_file_changed_after_save();
+        }
+        if FileWatcher::fingerprint(&data) == FileWatcher::fingerprint(context.data()) {
+            return self.file_watcher.set_synced(&data);
+        }
+
+        let has_local_edits = self.file_watcher.has_changes(context.data()) || self.current_page().is_some_and(|p| p.is_editor());
+        let base = self.file_watcher.synced();
+        self.file_watcher.set_synced(&data);
+        if has_local_edits {
+            self.open_conflict_dialog(context, base, data);
+        } else {
+            self.reload(data);
+        }
+    }
+
+    fn open_conflict_dialog(self: &Rc<Self>, context: Context, base: Data, data: Data) {
+        let this = Rc::downgrade(self);
+        context.with_ui_action(UiAction::OpenAlertDialog {
+            title: gettext("Document changed on disk"),
+            message: gettext("Another program changed this document while you were editing it. Merge both versions, reload it and lose your changes, or keep your changes and replace the other version?"),
+            buttons: vec![
+                AlertButton::overwrite(),
+                AlertButton::reload(),
+                AlertButton::merge(),
+            ],
+            callback: Box::new(move |button| {
+                let Some(this) = this.upgrade() else { return };
+                match button.map(|b| &b.button_type) {
+                    Some(AlertButtonType::Reload) => this.reload(data),
+                    Some(AlertButtonType::Overwrite) => this.overwrite(),
+                    Some(AlertButtonType::Merge) => this.current_context().merge(Some(&base), &data),
+                    _ => {}
+                }
+            })
+        }).propagate();
+    }
+
+    fn reload(&self, data: Data) {
+        let action = if self.current_page().is_some_and(|p| p.survives_reload()) {
+            NavigationAction::NavigateToCurrent
+        } else {
+            NavigationAction::NavigateToOverview
+        };
+        self.undo_history.borrow_mut().clear();
+        self.current_context()
+            .with_loaded_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Document reloaded")))
+            .with_navigation_action(action)
+            .propagate();
+    }
+
+    fn overwrite(&self) {
+        let context = self.current_context();
+        let data = context.data().clone();
+        context
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Kept your changes")))
+            .propagate();
+    }
+
+    fn record_undo(&self, context: &Context) {
+        let last_context = self.last_context.borrow();
+        let mut undo_history = self.undo_history.borrow_mut();
+        if last_context.file().path != context.file().path {
+            undo_history.clear();
+        } else if context.has_changed_data() && !self.is_undoing.get() && !last_context.data().is_empty() {
+            undo_history.push(last_context.data().clone());
+            if undo_history.len() > UNDO_LIMIT {
+                undo_history.remove(0);
+            }
+        }
+    }
+
+    fn undo(&self) {
+        let Some(data) = self.undo_history.borrow_mut().pop() else { return };
+        let context = self.current_context();
+
+        self.is_undoing.set(true);
+        context
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Change undone")))
+            .with_navigation_action(NavigationAction::NavigateToCurrent)
+            .propagate();
+        self.is_undoing.set(false);
+    }
+
+    fn activate_shortcut(&self, shortcut: Shortcut) {
+        let context = self.current_context();
+        match shortcut {
+            Shortcut::Open => status::Status::load_file(context),
+            Shortcut::Search => context
+                .with_navigation_action(NavigationAction::NavigateToSearch)
+                .propagate(),
+            Shortcut::Undo => self.undo(),
+            Shortcut::ShowShortcuts => context
+                .with_ui_action(UiAction::OpenShortcutsWindow)
+                .propagate(),
+            Shortcut::CommandPalette => {
+                let is_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+                context
+                    .with_ui_action(UiAction::OpenCommandPalette { is_data_open })
+                    .propagate()
+            },
+            Shortcut::Lock => context.lock(),
+            Shortcut::NewWallet => context
+                .with_navigation_action(NavigationAction::navigate_to_new_wallet())
+                .propagate(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn connect_actions(self: &Rc<Self>) {
+        for shortcut in Shortcut::as_slice() {
+            let Some(action) = self.find_action(shortcut) else { continue };
+            let this = Rc::downgrade(self);
+            action.connect_activate(move |_, _| {
+                let Some(this) = this.upgrade() else { return };
+                this.activate_shortcut(shortcut);
+            });
+        }
+    }
+
+    /// Pops the pages showing something a merge or a reload removed, such as a
+    /// transaction of a wallet deleted in the other copy.
+    fn leave_unavailable_pages(&self, data: &Data) {
+        let mut dropped_actions = Vec::new();
+        {
+            let mut history = self.history.borrow_mut();
+            while history.len() > 1 && history.last().is_some_and(|a| !a.is_available(data)) {
+                dropped_actions.extend(history.pop());
+            }
+        }
+        if dropped_actions.is_empty() {
+            return
+        }
+
+        for dropped_action in &dropped_actions {
+            if let Some(page) = self.get_navigation_page_from_action(dropped_action) {
+                page.deactivate();
+            }
+        }
+        let last_action = self.history.borrow().last().copied();
+        if let Some(page) = last_action.and_then(|a| self.get_navigation_page_from_action(&a)) {
+            self.navigation_view.pop_to_page(page.widget());
+        }
+    }
+
+    fn navigate(&self, action: NavigationAction, context: &Context) {
         if action.is_navigation_previous() {
             self.pop_navigation_action();
             self.navigation_view.pop();
             return
         } else if action.is_navigation_current() {
+            self.leave_unavailable_pages(context.data());
             if let Some(last_action) = self.history.borrow().last() {
                 let page = self.get_navigation_page_from_action(last_action).unwrap();
                 page.deactivate();
@@ -173,8 +708,21 @@ impl Propagator<NavigationAction> for Rc<Pages> {
         }
 
         let mut history = self.history.borrow_mut();
-        let last_action = history.last();
         let current_page_type = self.get_page_from_action(&action).unwrap();
+
+        // Returning to a page deeper in the stack drops the pages above it,
+        // as the sidebar can jump from a transaction straight to another wallet.
+        if let Some(position) = history
+            .iter()
+            .rposition(|a| self.get_page_from_action(a) == Some(current_page_type)) {
+                for dropped_action in history.drain(position + 1..) {
+                    if let Some(page) = self.get_navigation_page_from_action(&dropped_action) {
+                        page.deactivate();
+                    }
+                }
+            }
+
+        let last_action = history.last();
         let previous_page_type = last_action.and_then(|a| self.get_page_from_action(&a));
         let current_navigation_page = self.pages.get(&current_page_type).unwrap();
         let is_same_action = Some(current_page_type) == previous_page_type;
@@ -200,10 +748,35 @@ impl Propagator<NavigationAction> for Rc<Pages> {
                 .deactivate();
         }
     }
+
+}
+
+impl Propagator<NavigationAction> for Rc<Pages> {
+    fn propagate(&self, action: NavigationAction, context: &Context) {
+        if let Some(pages) = self.find_pages_with_file(context) {
+            if let Some(window) = pages.window() {
+                window.present();
+            }
+            return pages
+                .current_context()
+                .with_ui_action(UiAction::push_notification(gettext("Document is already open in this window")))
+                .propagate();
+        }
+        let was_data_open = self.current_page().is_some_and(|p| p != Page::Status);
+        self.record_undo(context);
+        self.last_context.replace(context.clone());
+        self.navigate(action, context);
+        self.record_recent_document(was_data_open);
+        self.update_actions();
+        self.update_sidebar();
+        self.update_file_watcher();
+        self.update_auto_lock();
+        self.update_exchange_rates();
+    }
 }
 
 impl HasWidget<gtk::Widget> for Rc<Pages> {
     fn widget(&self) -> &gtk::Widget {
-        self.navigation_view.upcast_ref()
+        self.breakpoint_bin.upcast_ref()
     }
 }
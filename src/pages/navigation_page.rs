@@ -2,11 +2,20 @@ use crate::prelude::*;
 use crate::context::*;
 use crate::metadata;
 
+use gtk::glib;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Quick work finishes before the spinner would show, so it doesn't flicker.
+const SPINNER_DELAY: Duration = Duration::from_millis(300);
+
 pub struct NavigationPage {
     title_label: gtk::Label,
     navigation_page: adw::NavigationPage,
     revealer: gtk::Revealer,
     spinner: gtk::Spinner,
+    spinner_source: Rc<RefCell<Option<glib::SourceId>>>,
     page_content: Box<dyn PageContent>,
 }
 
@@ -20,12 +29,14 @@ impl NavigationPage {
         let header_bar = Self::build_header_bar(&title_label);
         let toolbar_view = Self::build_toolbar_view(&header_bar, &overlay);
         let navigation_page = Self::build_navigation_page(&toolbar_view);
+        navigation_page.insert_action_group(PAGE_ACTIONS, page_content.action_group());
 
         Self {
             title_label,
             navigation_page,
             revealer,
             spinner,
+            spinner_source: Rc::new(RefCell::new(None)),
             page_content
         }
     }
@@ -47,7 +58,7 @@ impl NavigationPage {
     }
 
     fn build_title_label() -> gtk::Label {
-        let label = gtk::Label::new(Some("Loading..."));
+        let label = gtk::Label::new(Some(&gettext("Loading...")));
         label.set_halign(gtk::Align::Center);
         label.set_valign(gtk::Align::Center);
         label.add_css_class("title-3");
@@ -90,6 +101,29 @@ impl NavigationPage {
         navigation_page
     }
 
+    pub fn action_group(&self) -> Option<&gtk::gio::SimpleActionGroup> {
+        self.page_content.action_group()
+    }
+
+    /// Shows the spinner over the page while work runs in the background for longer than a moment.
+    pub fn set_busy(&self, is_busy: bool) {
+        if let Some(source_id) = self.spinner_source.take() {
+            source_id.remove();
+        }
+        if !is_busy {
+            self.spinner.stop();
+            return;
+        }
+
+        let spinner = self.spinner.clone();
+        let spinner_source = self.spinner_source.clone();
+        let source_id = glib::timeout_add_local_once(SPINNER_DELAY, move || {
+            spinner_source.take();
+            spinner.start();
+        });
+        self.spinner_source.replace(Some(source_id));
+    }
+
 }
 
 impl HasWidget<adw::NavigationPage> for NavigationPage {
@@ -101,16 +135,14 @@ impl HasWidget<adw::NavigationPage> for NavigationPage {
 impl LifeCycle<NavigationAction> for NavigationPage {
 
     fn activate(&self, action: NavigationAction, context: &Context) {
-        self.spinner.start();
         self.page_content.activate(action, context);
-        self.spinner.stop();
         self.revealer.set_reveal_child(true);
         self.title_label.set_text(&self.page_content.title());
     }
 
     fn deactivate(&self) {
         self.revealer.set_reveal_child(false);
-        self.title_label.set_text("Loading...");
+        self.title_label.set_text(&gettext("Loading..."));
         self.page_content.deactivate();
     }
 }
\ No newline at end of file
@@ -19,9 +19,9 @@ pub struct BalanceRow {
 impl BalanceRow {
 
     pub fn new() -> Self {
-        let (income_box, income_label) = Self::build_balance_box("Income", false);
-        let (expense_box, expense_label) = Self::build_balance_box("Expense", false);
-        let (total_box, total_label) = Self::build_balance_box("Total", true);
+        let (income_box, income_label) = Self::build_balance_box(&gettext("Income"), false);
+        let (expense_box, expense_label) = Self::build_balance_box(&gettext("Expense"), false);
+        let (total_box, total_label) = Self::build_balance_box(&gettext("Total"), true);
         let v_box = Self::build_box(vec![
             &income_box,
             &expense_box,
@@ -51,14 +51,14 @@ impl BalanceRow {
     fn build_button_list() -> ButtonList<Currency> {
         let button_list = ButtonList::new(false);
         for currency in Currency::as_slice() {
-            button_list.add_with_prefixed_label(currency, currency.as_symbol(), currency.as_long_str());
+            button_list.add_with_prefixed_label(currency, currency.as_symbol(), &gettext(currency.as_long_str()));
         }
         button_list
     }
 
     fn build_action_row(child: &impl IsA<gtk::Widget>) -> adw::ActionRow {
         let action_row = adw::ActionRow::new();
-        action_row.set_title("Balance");
+        action_row.set_title(&gettext("Balance"));
         action_row.add_suffix(child);
         action_row.set_activatable(true);
         action_row
@@ -130,11 +130,11 @@ impl BalanceRow {
         });
     }
 
-    pub fn set_balance(&self, balance: Balance, currency: Currency) {
+    pub fn set_balance(&self, balance: Balance, currency: Currency, is_converted: bool) {
         self.button_list.activate_button(&currency);
         self.income_label.set_text(&currency.format_amount(balance.income));
         self.expense_label.set_text(&currency.format_amount(balance.expense));
-        self.total_label.set_text(&currency.format_amount(balance.net_balance()));
+        self.total_label.set_text(&currency.format_total(balance.net_balance(), is_converted));
     }
 
     pub fn connect_activated(&self, callback: impl Fn(Currency) + 'static) {
@@ -0,0 +1,86 @@
+use super::*;
+
+use chrono::NaiveDate;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct State {
+    series: Vec<(NaiveDate, Balance)>,
+    currency: Currency,
+    date_label: Option<DateLabel>,
+}
+
+pub struct BarChart {
+    drawing_area: gtk::DrawingArea,
+    state: Rc<RefCell<State>>,
+}
+
+impl BarChart {
+
+    pub fn new() -> Self {
+        let this = Self {
+            drawing_area: build_drawing_area(180),
+            state: Rc::new(RefCell::new(State::default())),
+        };
+        this.connect_draw();
+        this
+    }
+
+    fn draw(area: &gtk::DrawingArea, cr: &cairo::Context, width: f64, height: f64, state: &State) {
+        let Some(((first_date, _), (last_date, _))) = state.series.first().zip(state.series.last()) else { return };
+
+        let max_income = state.series.iter().map(|(_, b)| b.income).fold(0.0, f64::max);
+        let max_expense = state.series.iter().map(|(_, b)| b.expense).fold(0.0, f64::max);
+        let plot_top = MARGIN + FONT_SIZE;
+        let plot_height = height - plot_top - MARGIN - FONT_SIZE * 2.0;
+        let plot_width = width - MARGIN * 2.0;
+        let scale = if max_income + max_expense > 0.0 { plot_height / (max_income + max_expense) } else { 0.0 };
+        let axis = plot_top + max_income * scale;
+        let slot_width = plot_width / state.series.len() as f64;
+        let bar_width = (slot_width * 0.7).max(1.0);
+
+        for (index, (_, balance)) in state.series.iter().enumerate() {
+            let x = MARGIN + slot_width * index as f64 + (slot_width - bar_width) / 2.0;
+
+            set_source_color(cr, INCOME_COLOR, 1.0);
+            cr.rectangle(x, axis - balance.income * scale, bar_width, balance.income * scale);
+            let _ = cr.fill();
+
+            set_source_color(cr, EXPENSE_COLOR, 1.0);
+            cr.rectangle(x, axis, bar_width, balance.expense * scale);
+            let _ = cr.fill();
+        }
+
+        set_source_foreground(cr, area, 0.3);
+        cr.set_line_width(1.0);
+        cr.move_to(MARGIN, axis);
+        cr.line_to(width - MARGIN, axis);
+        let _ = cr.stroke();
+
+        set_source_foreground(cr, area, 0.7);
+        draw_text(cr, &state.currency.format_amount(max_income), MARGIN, MARGIN, TextAlign::Start);
+        draw_text(cr, &state.currency.format_amount(-max_expense), MARGIN, height - MARGIN - FONT_SIZE, TextAlign::Start);
+        draw_text(cr, &state.date_label.map_or_else(String::new, |label| label(*first_date)), MARGIN, height - MARGIN / 2.0, TextAlign::Start);
+        draw_text(cr, &state.date_label.map_or_else(String::new, |label| label(*last_date)), width - MARGIN, height - MARGIN / 2.0, TextAlign::End);
+    }
+
+    fn connect_draw(&self) {
+        let state = self.state.clone();
+        self.drawing_area.set_draw_func(move |area, cr, width, height| {
+            Self::draw(area, cr, width as f64, height as f64, &state.borrow());
+        });
+    }
+
+    pub fn set_series(&self, series: Vec<(NaiveDate, Balance)>, currency: Currency, date_label: DateLabel) {
+        self.state.replace(State { series, currency, date_label: Some(date_label) });
+        self.drawing_area.queue_draw();
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for BarChart {
+    fn widget(&self) -> &gtk::Widget {
+        self.drawing_area.upcast_ref()
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+const LEGEND_WIDTH: f64 = 180.0;
+
+#[derive(Default)]
+struct State {
+    shares: Vec<(String, f64)>,
+    currency: Currency,
+}
+
+pub struct DonutChart {
+    drawing_area: gtk::DrawingArea,
+    state: Rc<RefCell<State>>,
+}
+
+impl DonutChart {
+
+    pub fn new() -> Self {
+        let this = Self {
+            drawing_area: build_drawing_area(180),
+            state: Rc::new(RefCell::new(State::default())),
+        };
+        this.connect_draw();
+        this
+    }
+
+    fn draw(area: &gtk::DrawingArea, cr: &cairo::Context, width: f64, height: f64, state: &State) {
+        let total: f64 = state.shares.iter().map(|(_, amount)| amount).sum();
+        if total <= 0.0 {
+            return;
+        }
+
+        let radius = ((height - MARGIN * 2.0) / 2.0).min((width - LEGEND_WIDTH - MARGIN * 2.0) / 2.0).max(10.0);
+        let center_x = MARGIN + radius;
+        let center_y = height / 2.0;
+        let mut angle = -PI / 2.0;
+
+        cr.set_line_width(radius * 0.4);
+        for (index, (_, amount)) in state.shares.iter().enumerate() {
+            let sweep = amount / total * PI * 2.0;
+            set_source_color(cr, PALETTE[index % PALETTE.len()], 1.0);
+            cr.new_path();
+            cr.arc(center_x, center_y, radius * 0.8, angle, angle + sweep);
+            let _ = cr.stroke();
+            angle += sweep;
+        }
+
+        set_source_foreground(cr, area, 0.9);
+        draw_text(cr, &state.currency.format_amount(total), center_x, center_y + FONT_SIZE / 3.0, TextAlign::Center);
+
+        let legend_x = center_x + radius + MARGIN * 2.0;
+        let line_height = FONT_SIZE * 1.8;
+        let legend_top = center_y - line_height * state.shares.len() as f64 / 2.0;
+        for (index, (name, amount)) in state.shares.iter().enumerate() {
+            let y = legend_top + line_height * index as f64;
+            set_source_color(cr, PALETTE[index % PALETTE.len()], 1.0);
+            cr.rectangle(legend_x, y, FONT_SIZE, FONT_SIZE);
+            let _ = cr.fill();
+
+            set_source_foreground(cr, area, 0.8);
+            let text = format!("{} · {:.0}%", name, amount / total * 100.0);
+            draw_text(cr, &text, legend_x + FONT_SIZE * 1.5, y + FONT_SIZE - 1.0, TextAlign::Start);
+        }
+    }
+
+    fn connect_draw(&self) {
+        let state = self.state.clone();
+        self.drawing_area.set_draw_func(move |area, cr, width, height| {
+            Self::draw(area, cr, width as f64, height as f64, &state.borrow());
+        });
+    }
+
+    pub fn set_shares(&self, shares: Vec<(String, f64)>, currency: Currency) {
+        self.state.replace(State { shares, currency });
+        self.drawing_area.queue_draw();
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for DonutChart {
+    fn widget(&self) -> &gtk::Widget {
+        self.drawing_area.upcast_ref()
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+use chrono::NaiveDate;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct State {
+    series: Vec<(NaiveDate, f64)>,
+    currency: Currency,
+    date_label: Option<DateLabel>,
+}
+
+pub struct LineChart {
+    drawing_area: gtk::DrawingArea,
+    state: Rc<RefCell<State>>,
+}
+
+impl LineChart {
+
+    pub fn new() -> Self {
+        let this = Self {
+            drawing_area: build_drawing_area(160),
+            state: Rc::new(RefCell::new(State::default())),
+        };
+        this.connect_draw();
+        this
+    }
+
+    fn draw(area: &gtk::DrawingArea, cr: &cairo::Context, width: f64, height: f64, state: &State) {
+        let Some(((first_date, _), (last_date, _))) = state.series.first().zip(state.series.last()) else { return };
+
+        let min_value = state.series.iter().map(|(_, v)| *v).fold(0.0, f64::min);
+        let max_value = state.series.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+        let range = if max_value > min_value { max_value - min_value } else { 1.0 };
+        let plot_top = MARGIN + FONT_SIZE;
+        let plot_height = height - plot_top - MARGIN - FONT_SIZE * 2.0;
+        let plot_width = width - MARGIN * 2.0;
+        let step = plot_width / (state.series.len().max(2) - 1) as f64;
+        let to_y = |value: f64| plot_top + (max_value - value) / range * plot_height;
+
+        set_source_foreground(cr, area, 0.3);
+        cr.set_line_width(1.0);
+        cr.set_dash(&[4.0, 4.0], 0.0);
+        cr.move_to(MARGIN, to_y(0.0));
+        cr.line_to(width - MARGIN, to_y(0.0));
+        let _ = cr.stroke();
+        cr.set_dash(&[], 0.0);
+
+        for (index, (_, value)) in state.series.iter().enumerate() {
+            let x = MARGIN + step * index as f64;
+            if index == 0 {
+                cr.move_to(x, to_y(*value));
+            } else {
+                cr.line_to(x, to_y(*value));
+            }
+        }
+        set_source_color(cr, PALETTE[0], 1.0);
+        cr.set_line_width(2.0);
+        let _ = cr.stroke_preserve();
+
+        let last_x = MARGIN + step * (state.series.len() - 1) as f64;
+        cr.line_to(last_x, to_y(0.0));
+        cr.line_to(MARGIN, to_y(0.0));
+        cr.close_path();
+        set_source_color(cr, PALETTE[0], 0.2);
+        let _ = cr.fill();
+
+        set_source_foreground(cr, area, 0.7);
+        draw_text(cr, &state.currency.format_amount(max_value), MARGIN, MARGIN, TextAlign::Start);
+        draw_text(cr, &state.currency.format_amount(min_value), MARGIN, height - MARGIN - FONT_SIZE, TextAlign::Start);
+        draw_text(cr, &state.date_label.map_or_else(String::new, |label| label(*first_date)), MARGIN, height - MARGIN / 2.0, TextAlign::Start);
+        draw_text(cr, &state.date_label.map_or_else(String::new, |label| label(*last_date)), width - MARGIN, height - MARGIN / 2.0, TextAlign::End);
+    }
+
+    fn connect_draw(&self) {
+        let state = self.state.clone();
+        self.drawing_area.set_draw_func(move |area, cr, width, height| {
+            Self::draw(area, cr, width as f64, height as f64, &state.borrow());
+        });
+    }
+
+    pub fn set_series(&self, series: Vec<(NaiveDate, f64)>, currency: Currency, date_label: DateLabel) {
+        self.state.replace(State { series, currency, date_label: Some(date_label) });
+        self.drawing_area.queue_draw();
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for LineChart {
+    fn widget(&self) -> &gtk::Widget {
+        self.drawing_area.upcast_ref()
+    }
+}
@@ -0,0 +1,151 @@
+mod bar_chart;
+mod line_chart;
+mod donut_chart;
+
+use crate::prelude::*;
+use crate::data::*;
+
+use chrono::NaiveDate;
+use gtk::cairo;
+
+const MARGIN: f64 = 12.0;
+const FONT_SIZE: f64 = 11.0;
+const INCOME_COLOR: (f64, f64, f64) = (0.18, 0.76, 0.49);
+const EXPENSE_COLOR: (f64, f64, f64) = (0.88, 0.11, 0.14);
+const PALETTE: [(f64, f64, f64); 7] = [
+    (0.21, 0.52, 0.89),
+    (0.18, 0.76, 0.49),
+    (0.96, 0.83, 0.18),
+    (1.00, 0.47, 0.00),
+    (0.88, 0.11, 0.14),
+    (0.57, 0.25, 0.67),
+    (0.60, 0.42, 0.24),
+];
+
+#[derive(Clone, Copy)]
+enum TextAlign {
+    Start,
+    Center,
+    End,
+}
+
+fn build_drawing_area(height: i32) -> gtk::DrawingArea {
+    let drawing_area = gtk::DrawingArea::new();
+    drawing_area.set_content_height(height);
+    drawing_area.set_hexpand(true);
+    drawing_area.set_margin_top(6);
+    drawing_area.set_margin_bottom(6);
+    drawing_area
+}
+
+fn set_source_color(cr: &cairo::Context, (red, green, blue): (f64, f64, f64), alpha: f64) {
+    cr.set_source_rgba(red, green, blue, alpha);
+}
+
+fn set_source_foreground(cr: &cairo::Context, widget: &impl IsA<gtk::Widget>, alpha: f64) {
+    let color = widget.color();
+    cr.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        color.alpha() as f64 * alpha
+    );
+}
+
+fn draw_text(cr: &cairo::Context, text: &str, x: f64, y: f64, align: TextAlign) {
+    cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+    cr.set_font_size(FONT_SIZE);
+    let width = cr.text_extents(text).map_or(0.0, |extents| extents.x_advance());
+    let x = match align {
+        TextAlign::Start => x,
+        TextAlign::Center => x - width / 2.0,
+        TextAlign::End => x - width,
+    };
+    cr.move_to(x, y);
+    let _ = cr.show_text(text);
+}
+
+type DateLabel = fn(NaiveDate) -> String;
+
+fn date_label(period: Period) -> DateLabel {
+    match period {
+        Period::Year | Period::All => Locale::short_month_and_year,
+        _ => Locale::day_and_short_month,
+    }
+}
+
+pub struct Charts {
+    container: gtk::Box,
+    bar_chart: bar_chart::BarChart,
+    line_chart: line_chart::LineChart,
+    net_worth_chart: line_chart::LineChart,
+    donut_chart: donut_chart::DonutChart,
+    category_chart: donut_chart::DonutChart,
+}
+
+impl Charts {
+
+    pub fn new() -> Self {
+        let bar_chart = bar_chart::BarChart::new();
+        let line_chart = line_chart::LineChart::new();
+        let net_worth_chart = line_chart::LineChart::new();
+        let donut_chart = donut_chart::DonutChart::new();
+        let category_chart = donut_chart::DonutChart::new();
+        let container = Self::build_container(vec![
+            Self::build_card(&gettext("Income and Expense"), bar_chart.widget()),
+            Self::build_card(&gettext("Balance Trend"), line_chart.widget()),
+            Self::build_card(&gettext("Net Worth"), net_worth_chart.widget()),
+            Self::build_card(&gettext("Wallet Share"), donut_chart.widget()),
+            Self::build_card(&gettext("Spending by Category"), category_chart.widget()),
+        ]);
+
+        Self {
+            container,
+            bar_chart,
+            line_chart,
+            net_worth_chart,
+            donut_chart,
+            category_chart,
+        }
+    }
+
+    fn build_card(title: &str, child: &impl IsA<gtk::Widget>) -> gtk::Box {
+        let title_label = gtk::Label::new(Some(title));
+        title_label.add_css_class("heading");
+        title_label.set_halign(gtk::Align::Start);
+
+        let v_box = gtk::Box::new(gtk::Orientation::Vertical, 5);
+        v_box.add_css_class("card");
+        v_box.set_margin_bottom(5);
+        v_box.append(&title_label);
+        v_box.append(child);
+        title_label.set_margin_top(12);
+        title_label.set_margin_start(12);
+        v_box
+    }
+
+    fn build_container(cards: Vec<gtk::Box>) -> gtk::Box {
+        let v_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        v_box.set_hexpand(true);
+        for card in cards {
+            v_box.append(&card);
+        }
+        v_box
+    }
+
+    pub fn set_data(&self, data: &Data) {
+        let label = date_label(data.period);
+        self.bar_chart.set_series(data.balance_series(), data.currency, label);
+        self.line_chart.set_series(data.cumulative_series(), data.currency, label);
+        self.net_worth_chart.set_series(data.net_worth_series(), data.currency, label);
+        self.donut_chart.set_shares(data.wallet_shares(), data.currency);
+        self.category_chart.set_shares(data.expense_shares_by_category(), data.currency);
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Charts {
+    fn widget(&self) -> &gtk::Widget {
+        self.container.upcast_ref()
+    }
+}
@@ -40,7 +40,7 @@ impl HeaderRow {
     }
 
     fn build_period_label() -> gtk::Label {
-        let label = gtk::Label::new(Some(Period::default().as_str()));
+        let label = gtk::Label::new(Some(&gettext(Period::default().as_str())));
             label.set_hexpand(true);
             label.set_halign(gtk::Align::Center);
             label
@@ -59,20 +59,20 @@ impl HeaderRow {
             button.set_valign(gtk::Align::Center);
             button.set_focusable(false);
             button.set_child(Some(&h_box));
-            button.set_tooltip_text(Some("Period"));
+            button.set_tooltip_text(Some(&gettext("Period")));
             button
     }
 
     fn build_button_list() -> ButtonList<Period> {
         let button_list = ButtonList::new(false);
         for period in Period::as_slice() {
-            button_list.add_with_text(period, period.as_str());
+            button_list.add_with_text(period, &gettext(period.as_str()));
         }
         button_list
     }
 
     fn build_horizontal_box(child: &impl IsA<gtk::Widget>) -> gtk::Box {
-        let title_label = gtk::Label::new(Some("Wallets"));
+        let title_label = gtk::Label::new(Some(&gettext("Wallets")));
         title_label.add_css_class("title-3");
         title_label.set_halign(gtk::Align::Start);
         title_label.set_hexpand(true);
@@ -91,7 +91,7 @@ impl HeaderRow {
         let main_button_label_weak = self.main_button_label.downgrade();
         self.button_list.connect_activated(move |period, _, _| {
             let Some(main_button_label) = main_button_label_weak.upgrade() else { return; };
-            main_button_label.set_label(period.as_str());
+            main_button_label.set_label(&gettext(period.as_str()));
             let callback = callback.borrow().clone();
             if let Some(callback) = callback  {
                 callback(period);
@@ -111,7 +111,7 @@ impl HeaderRow {
     }
 
     pub fn set_period(&self, period: Period) {
-        self.main_button_label.set_label(period.as_str());
+        self.main_button_label.set_label(&gettext(period.as_str()));
         self.button_list.activate_button(&period);
     }
 
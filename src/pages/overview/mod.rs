@@ -1,60 +1,127 @@
 mod header_row;
 mod balance_row;
 mod wallet_group;
+mod charts;
 
-use crate::data::Wallet;
+use crate::data::{Period, Preferences, ViewOptions, Wallet};
 use crate::prelude::*;
 use crate::context::*;
-use crate::utils::ScrollablePane;
+use crate::settings::Settings;
+use crate::utils::{ScrollablePane, ViewOptionsRow};
 
+use gtk::gio;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 pub struct Overview {
     scrollable_pane: ScrollablePane,
     balance_row: balance_row::BalanceRow,
+    charts: charts::Charts,
     header_row: header_row::HeaderRow,
-    insert_wallet_row: adw::ButtonRow,
+    upcoming_row: adw::ButtonRow,
+    payees_row: adw::ButtonRow,
+    rules_row: adw::ButtonRow,
+    view_options_row: Rc<ViewOptionsRow>,
     wallets_box: gtk::Box,
+    switch_period_action: gio::SimpleAction,
+    action_group: gio::SimpleActionGroup,
+    settings: Settings,
 
     context: RefCell<Context>
 }
 
 impl Overview {
-    pub fn new() -> Rc<Self> {
+    pub fn new(settings: Settings) -> Rc<Self> {
         
         let wallets_box = gtk::Box::new(gtk::Orientation::Vertical, 20);
         let header_row = header_row::HeaderRow::new();
         let balance_row = balance_row::BalanceRow::new();
+        let charts = charts::Charts::new();
         let insert_wallet_row = Self::build_insert_button_row();
+        let upcoming_row = Self::build_upcoming_button_row();
+        let payees_row = Self::build_payees_button_row();
+        let rules_row = Self::build_rules_button_row();
+        let search_row = Self::build_search_button_row();
+        let view_options_row = ViewOptionsRow::new();
+        let switch_period_action = Shortcut::SwitchPeriod.build_action();
+        let action_group = build_action_group(&[&switch_period_action]);
 
         let mut scrollable_pane = ScrollablePane::new();
 
-        scrollable_pane.add_group(vec![header_row.widget()]);
+        scrollable_pane.add_group(vec![header_row.widget(), view_options_row.widget()]);
         scrollable_pane.add_group(vec![&wallets_box]);
         scrollable_pane.add_group(vec![&insert_wallet_row]);
         scrollable_pane.add_separator();
         scrollable_pane.add_group(vec![balance_row.widget()]);
+        scrollable_pane.add_group(vec![&upcoming_row, &payees_row, &rules_row, &search_row]);
+        scrollable_pane.add_separator();
+        scrollable_pane.add_group(vec![charts.widget()]);
 
         let this = Rc::new(Self {
             scrollable_pane,
             balance_row,
+            charts,
             header_row,
-            insert_wallet_row,
+            upcoming_row,
+            payees_row,
+            rules_row,
+            view_options_row,
             wallets_box,
+            switch_period_action,
+            action_group,
+            settings,
             context: Default::default()
         });
         this.connect_balance_row_activated();
         this.connect_header_row_activated();
-        this.connect_insert_wallet_row_activated();
+        this.connect_switch_period_action();
+        this.connect_upcoming_row_activated();
+        this.connect_payees_row_activated();
+        this.connect_rules_row_activated();
+        this.connect_view_options_changed();
         this
     }
 
     fn build_insert_button_row() -> adw::ButtonRow {
         let button_row = adw::ButtonRow::new();
         button_row.set_activatable(true);
-        button_row.set_title("Insert Wallet");
+        button_row.set_title(&gettext("Insert Wallet"));
         button_row.set_start_icon_name(Some("list-add-symbolic"));
+        button_row.set_action_name(Some(&Shortcut::NewWallet.detailed_name()));
+        button_row
+    }
+
+    fn build_upcoming_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_activatable(true);
+        button_row.set_title(&gettext("Upcoming Payments"));
+        button_row.set_start_icon_name(Some("month-symbolic"));
+        button_row
+    }
+
+    fn build_payees_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_activatable(true);
+        button_row.set_title(&gettext("Payees"));
+        button_row.set_start_icon_name(Some("system-users-symbolic"));
+        button_row
+    }
+
+    fn build_rules_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_activatable(true);
+        button_row.set_title(&gettext("Rules"));
+        button_row.set_start_icon_name(Some("edit-find-replace-symbolic"));
+        button_row
+    }
+
+    fn build_search_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_activatable(true);
+        button_row.set_title(&gettext("Search"));
+        button_row.set_start_icon_name(Some("system-search-symbolic"));
+        button_row.set_tooltip_text(Some("Ctrl+F"));
+        button_row.set_action_name(Some(&Shortcut::Search.detailed_name()));
         button_row
     }
 
@@ -75,10 +142,10 @@ impl Overview {
         }
     }
 
-    fn add_wallet_groups(&self, wallets: Vec<Wallet>, context: &Context) {
+    fn add_wallet_groups(&self, wallets: Vec<Wallet>, view_options: ViewOptions, context: &Context) {
         for wallet in wallets {
             let context = context.clone();
-            let wallet_group = wallet_group::WalletGroup::new(&wallet);
+            let wallet_group = wallet_group::WalletGroup::new(&wallet, view_options);
             wallet_group.connect_activated(move |activate_type| {
                 Self::handle_wallet_group_activated(activate_type, &context)
             });
@@ -92,35 +159,90 @@ impl Overview {
         }
     }
 
-    fn connect_insert_wallet_row_activated(self: &Rc<Self>) {
+    fn connect_upcoming_row_activated(self: &Rc<Self>) {
         let this = Rc::downgrade(&self);
-        self.insert_wallet_row.connect_activated(move |_| {
+        self.upcoming_row.connect_activated(move |_| {
             let Some(this) = this.upgrade() else { unreachable!() };
             let context = this.context.borrow().clone();
 
             context
-                .with_navigation_action(NavigationAction::navigate_to_new_wallet())
+                .with_navigation_action(NavigationAction::navigate_to_upcoming(super::upcoming::DEFAULT_DAYS))
                 .propagate()
         });
     }
 
-    fn connect_header_row_activated(self: &Rc<Self>) {
-        let this = Rc::downgrade(&self);
-        self.header_row.connect_activated(move |new_period| {
+    fn connect_payees_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.payees_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let context = this.context.borrow().clone();
+
+            context
+                .with_navigation_action(NavigationAction::NavigateToPayees)
+                .propagate()
+        });
+    }
+
+    fn connect_rules_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.rules_row.connect_activated(move |_| {
             let Some(this) = this.upgrade() else { unreachable!() };
             let context = this.context.borrow().clone();
 
-            let mut new_data = context.data().clone();
-            new_data.period = new_period;
+            context
+                .with_navigation_action(NavigationAction::navigate_to_rules(None))
+                .propagate()
+        });
+    }
+
+    fn connect_view_options_changed(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.view_options_row.connect_changed(move |view_options| {
+            let Some(this) = this.upgrade() else { return };
+            let context = this.context.borrow().clone();
+
+            let mut views = Preferences::current().views;
+            views.overview = view_options;
+            this.settings.set_views(views);
 
             context
-                .with_data(new_data)
-                .with_ui_action(UiAction::push_notification("Period changed"))
                 .with_navigation_action(NavigationAction::NavigateToCurrent)
                 .propagate()
         });
     }
 
+    fn set_period(&self, new_period: Period) {
+        let context = self.context.borrow().clone();
+
+        let mut new_data = context.data().clone();
+        new_data.period = new_period;
+
+        context
+            .with_data(new_data)
+            .with_ui_action(UiAction::push_notification(gettext("Period changed")))
+            .with_navigation_action(NavigationAction::NavigateToCurrent)
+            .propagate()
+    }
+
+    fn connect_header_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(&self);
+        self.header_row.connect_activated(move |new_period| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            this.set_period(new_period);
+        });
+    }
+
+    fn connect_switch_period_action(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.switch_period_action.connect_activate(move |_, _| {
+            let Some(this) = this.upgrade() else { return };
+            let period = this.context.borrow().data().period;
+            let periods = Period::as_slice();
+            let position = periods.iter().position(|p| *p == period).unwrap_or_default();
+            this.set_period(periods[(position + 1) % periods.len()]);
+        });
+    }
+
     fn connect_balance_row_activated(self: &Rc<Self>) {
         let this = Rc::downgrade(&self);
         self.balance_row.connect_activated(move |new_currency| {
@@ -137,7 +259,7 @@ impl Overview {
 
             context
                 .with_data(new_data)
-                .with_ui_action(UiAction::push_notification("Currency changed"))
+                .with_ui_action(UiAction::push_notification(gettext("Currency changed")))
                 .with_navigation_action(NavigationAction::NavigateToCurrent)
                 .propagate()
         });
@@ -158,11 +280,14 @@ impl LifeCycle<NavigationAction> for Rc<Overview> {
         let mut data = context.data().clone();
         data.sort_by_name();
 
-        self.balance_row.set_balance(data.total_balance_for_period(), data.currency);
+        self.balance_row.set_balance(data.total_balance_for_period(), data.currency, !data.has_unconverted_amounts());
         self.header_row.set_period(data.period);
+        self.charts.set_data(&data);
 
         let wallets = data.wallets_for_period();
-        self.add_wallet_groups(wallets, context);
+        let view_options = Preferences::current().views.overview;
+        self.view_options_row.set_options(view_options);
+        self.add_wallet_groups(wallets, view_options, context);
 
         self.context.replace(context.clone());
     }
@@ -174,7 +299,11 @@ impl LifeCycle<NavigationAction> for Rc<Overview> {
 }
 
 impl PageContent for Rc<Overview> {
-    fn title(&self) -> &str {
-        "Overview"
+    fn title(&self) -> String {
+        gettext("Overview")
+    }
+
+    fn action_group(&self) -> Option<&gio::SimpleActionGroup> {
+        Some(&self.action_group)
     }
 }
\ No newline at end of file
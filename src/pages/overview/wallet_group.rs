@@ -122,10 +122,14 @@ pub struct WalletGroup {
 
 impl WalletGroup {
 
-    pub fn new(wallet: &Wallet) -> Self {
+    pub fn new(wallet: &Wallet, view_options: ViewOptions) -> Self {
         let wallet_row = WalletRow::new(wallet);
-        let transaction_rows = Self::create_transaction_rows(wallet);
-        let preferences_group = Self::build_preferences_group(&wallet_row, &transaction_rows);
+        let sections = Self::create_transaction_rows(wallet, view_options);
+        let preferences_group = Self::build_preferences_group(&wallet_row, &sections);
+        let transaction_rows = sections
+            .into_iter()
+            .flat_map(|(_, rows)| rows)
+            .collect();
 
         let this = Self {
             wallet_row,
@@ -136,24 +140,49 @@ impl WalletGroup {
         this
     }
 
-    fn create_transaction_rows(wallet: &Wallet) -> Vec<TransactionRow> {
-        let mut wallet = wallet.clone();
-        wallet.sort_transactions_by_amount();
-        wallet.transactions
-            .iter()
-            .map(|t| TransactionRow::new(t, &wallet))
+    fn create_transaction_rows(wallet: &Wallet, view_options: ViewOptions) -> Vec<(Option<String>, Vec<TransactionRow>)> {
+        view_options
+            .arrange(wallet.transactions.iter())
+            .into_iter()
+            .map(|(title, transactions)| {
+                let rows = transactions
+                    .into_iter()
+                    .map(|t| TransactionRow::new(t, wallet))
+                    .collect();
+                (title, rows)
+            })
             .collect()
     }
 
-    fn build_expander_action_row(transaction_rows: &Vec<TransactionRow>) -> adw::ActionRow {
+    fn build_section_row(title: &str) -> gtk::ListBoxRow {
+        let label = gtk::Label::new(Some(title));
+        label.set_halign(gtk::Align::Start);
+        label.add_css_class("caption-heading");
+        label.add_css_class("dim-label");
+        label.set_margin_top(6);
+        label.set_margin_bottom(6);
+        label.set_margin_start(12);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&label));
+        row
+    }
+
+    fn build_expander_action_row(sections: &Vec<(Option<String>, Vec<TransactionRow>)>) -> adw::ActionRow {
         let nested_list_box = gtk::ListBox::new();
             nested_list_box.set_selection_mode(gtk::SelectionMode::None);
             nested_list_box.set_focusable(false);
             nested_list_box.add_css_class("boxed-list");
             nested_list_box.add_css_class("nested");
 
-            for transaction_row in transaction_rows {
-                nested_list_box.append(&transaction_row.action_row);
+            for (title, transaction_rows) in sections {
+                if let Some(title) = title {
+                    nested_list_box.append(&Self::build_section_row(title));
+                }
+                for transaction_row in transaction_rows {
+                    nested_list_box.append(&transaction_row.action_row);
+                }
             }
 
         let expander_action_row = adw::ActionRow::new();
@@ -164,8 +193,8 @@ impl WalletGroup {
             expander_action_row
     }
 
-    fn build_preferences_group(wallet_row: &WalletRow, transaction_rows: &Vec<TransactionRow>) -> adw::PreferencesGroup {
-        let nested_list_box = Self::build_expander_action_row(transaction_rows);
+    fn build_preferences_group(wallet_row: &WalletRow, sections: &Vec<(Option<String>, Vec<TransactionRow>)>) -> adw::PreferencesGroup {
+        let nested_list_box = Self::build_expander_action_row(sections);
         let preferences_group = adw::PreferencesGroup::new();
         preferences_group.add(&wallet_row.action_row);
         preferences_group.add(&nested_list_box);
@@ -0,0 +1,369 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::*;
+use crate::utils::{ScrollablePane, AlertButton, AlertButtonType, LocaleSpinExtension};
+
+use gtk::gio;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct State {
+    context: Context,
+    payee: Payee,
+}
+
+pub struct PayeePage {
+    scrollable_pane: ScrollablePane,
+    name_entry_row: adw::EntryRow,
+    aliases_entry_row: adw::EntryRow,
+    category_entry_row: adw::EntryRow,
+    amount_spin_row: adw::SpinRow,
+    cycle_combo_row: adw::ComboRow,
+    spent_label: gtk::Label,
+    received_label: gtk::Label,
+    transactions_list_box: gtk::ListBox,
+    save_action: gio::SimpleAction,
+    remove_action: gio::SimpleAction,
+    action_group: gio::SimpleActionGroup,
+
+    state: RefCell<State>,
+}
+
+impl PayeePage {
+    pub fn new() -> Rc<Self> {
+        let name_entry_row = Self::build_entry_row(&gettext("Name"));
+        let aliases_entry_row = Self::build_entry_row(&gettext("Aliases, separated by commas"));
+        let category_entry_row = Self::build_entry_row(&gettext("Default Category"));
+        let amount_spin_row = Self::build_amount_spin_row();
+        let cycle_combo_row = Self::build_cycle_combo_row();
+        let (spent_row, spent_label) = Self::build_label_row(&gettext("Spent"));
+        let (received_row, received_label) = Self::build_label_row(&gettext("Received"));
+        let transactions_list_box = Self::build_list_box();
+        let save_button_row = Self::build_button_row(&gettext("Save"), "document-save-symbolic", "suggested-action");
+            save_button_row.set_action_name(Some(&Shortcut::Save.detailed_name()));
+        let remove_button_row = Self::build_button_row(&gettext("Remove"), "user-trash-symbolic", "destructive-action");
+            remove_button_row.set_action_name(Some(&Shortcut::Delete.detailed_name()));
+        let save_action = Shortcut::Save.build_action();
+        let remove_action = Shortcut::Delete.build_action();
+        let action_group = build_action_group(&[&save_action, &remove_action]);
+
+        let mut scrollable_pane = ScrollablePane::new();
+            scrollable_pane.add_header(&gettext("Payee"));
+            scrollable_pane.add_group(vec![&name_entry_row, &aliases_entry_row]);
+            scrollable_pane.add_header(&gettext("Defaults"));
+            scrollable_pane.add_group(vec![
+                category_entry_row.upcast_ref::<gtk::Widget>(),
+                amount_spin_row.upcast_ref(),
+                cycle_combo_row.upcast_ref(),
+            ]);
+            scrollable_pane.add_separator();
+            scrollable_pane.add_header(&gettext("Spending"));
+            scrollable_pane.add_group(vec![&spent_row, &received_row]);
+            scrollable_pane.add_group(vec![&transactions_list_box]);
+            scrollable_pane.add_separator();
+            scrollable_pane.add_group(vec![&remove_button_row]);
+            scrollable_pane.add_group(vec![&save_button_row]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            name_entry_row,
+            aliases_entry_row,
+            category_entry_row,
+            amount_spin_row,
+            cycle_combo_row,
+            spent_label,
+            received_label,
+            transactions_list_box,
+            save_action,
+            remove_action,
+            action_group,
+
+            state: RefCell::new(State::default()),
+        });
+        this.connect_entries_change_event();
+        this.connect_save_event();
+        this.connect_remove_event();
+        this
+    }
+
+    fn build_entry_row(title: &str) -> adw::EntryRow {
+        let entry_row = adw::EntryRow::new();
+        entry_row.set_show_apply_button(false);
+        entry_row.set_title(title);
+        entry_row
+    }
+
+    fn build_amount_spin_row() -> adw::SpinRow {
+        let adjustment = gtk::Adjustment::new(0.0, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Default Amount"));
+        spin_row
+    }
+
+    fn build_cycle_combo_row() -> adw::ComboRow {
+        let cycles: Vec<String> = std::iter::once(gettext("None"))
+            .chain(Cycle::as_slice().iter().map(|c| c.to_string()))
+            .collect();
+        let cycles: Vec<&str> = cycles.iter().map(String::as_str).collect();
+        let combo_row = adw::ComboRow::new();
+        combo_row.set_title(&gettext("Default Cycle"));
+        combo_row.set_model(Some(&gtk::StringList::new(&cycles)));
+        combo_row
+    }
+
+    fn build_label_row(title: &str) -> (adw::ActionRow, gtk::Label) {
+        let label = gtk::Label::new(None);
+        label.add_css_class("numeric");
+        label.add_css_class("heading");
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(title);
+        action_row.add_suffix(&label);
+        (action_row, label)
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box
+    }
+
+    fn build_button_row(title: &str, icon: &str, css_class: &str) -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(title);
+        button_row.set_start_icon_name(Some(icon));
+        button_row.add_css_class(css_class);
+        button_row
+    }
+
+    fn build_transaction_row(wallet: &Wallet, transaction: &Transaction, context: &Context) -> adw::ActionRow {
+        let amount_label = gtk::Label::new(Some(&wallet.currency.format_amount(transaction.amount)));
+        amount_label.add_css_class("numeric");
+        amount_label.add_css_class("caption");
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&transaction.name);
+        action_row.set_subtitle(&format!("{} · {}", wallet.name, transaction.cycle));
+        action_row.set_activatable(true);
+        action_row.add_suffix(&amount_label);
+
+        let context = context.clone();
+        let (wallet_id, transaction_id) = (wallet.id, transaction.id);
+        action_row.connect_activated(move |_| {
+            context
+                .clone()
+                .with_navigation_action(NavigationAction::navigate_to_transaction(wallet_id, transaction_id))
+                .propagate();
+        });
+        action_row
+    }
+
+    fn set_report(&self, payee: &Payee, context: &Context) {
+        let data = context.data();
+        let balance = data
+            .payee_totals()
+            .into_iter()
+            .find(|(id, _)| *id == payee.id)
+            .map(|(_, balance)| balance)
+            .unwrap_or_default();
+        self.spent_label.set_text(&data.currency.format_amount(balance.expense));
+        self.received_label.set_text(&data.currency.format_amount(balance.income));
+
+        for (wallet, transaction) in data.payee_transactions(payee.id) {
+            self.transactions_list_box.append(&Self::build_transaction_row(wallet, transaction, context));
+        }
+    }
+
+    fn set_data(&self, payee: &Payee, has_transactions: bool) {
+        let scroll_page_children = self.scrollable_pane.get_children();
+        for (index, child) in scroll_page_children.iter().enumerate() {
+            if !payee.is_created() && [4, 5, 6, 7, 9].contains(&index) {
+                child.set_visible(false);
+            } else if !has_transactions && index == 7 {
+                child.set_visible(false);
+            } else {
+                child.set_visible(true);
+            }
+        }
+
+        self.name_entry_row.set_text(&payee.name);
+        self.aliases_entry_row.set_text(&payee.aliases.join(", "));
+        self.category_entry_row.set_text(payee.category.as_deref().unwrap_or_default());
+        self.amount_spin_row.set_value(payee.amount.unwrap_or(0.0));
+        let cycle_position = payee.cycle
+            .and_then(|cycle| Cycle::as_slice().iter().position(|c| *c == cycle))
+            .map_or(0, |position| position + 1);
+        self.cycle_combo_row.set_selected(cycle_position as u32);
+        self.save_action.set_enabled(false);
+        self.remove_action.set_enabled(payee.is_created());
+    }
+
+    fn get_data(&self) -> Payee {
+        let aliases = self.aliases_entry_row
+            .text()
+            .split(',')
+            .map(|alias| alias.trim().to_string())
+            .filter(|alias| !alias.is_empty())
+            .collect();
+        let category = self.category_entry_row.text().trim().to_string();
+        let amount = self.amount_spin_row.value();
+        let cycle = (self.cycle_combo_row.selected() as usize)
+            .checked_sub(1)
+            .and_then(|index| Cycle::as_slice().get(index).copied());
+
+        Payee {
+            name: self.name_entry_row.text().trim().to_string(),
+            aliases,
+            category: (!category.is_empty()).then_some(category),
+            amount: (amount != 0.0).then_some(amount),
+            cycle,
+            ..self.state.borrow().payee.clone()
+        }
+    }
+
+    fn clear_data(&self) {
+        self.name_entry_row.set_text("");
+        self.aliases_entry_row.set_text("");
+        self.category_entry_row.set_text("");
+        self.amount_spin_row.set_value(0.0);
+        self.cycle_combo_row.set_selected(0);
+        self.transactions_list_box.remove_all();
+    }
+
+    fn handle_changes(&self) {
+        let previous_data = &self.state.borrow().payee;
+        let data = self.get_data();
+        if data.is_valid() && data.is_different(previous_data) {
+            self.save_action.set_enabled(true);
+        } else {
+            self.save_action.set_enabled(false);
+        }
+    }
+
+    fn connect_entries_change_event(self: &Rc<Self>) {
+        for entry_row in [&self.name_entry_row, &self.aliases_entry_row, &self.category_entry_row] {
+            let this = self.clone();
+            entry_row.connect_changed(move |_| this.handle_changes());
+        }
+
+        let this = self.clone();
+        self.amount_spin_row.connect_value_notify(move |_| this.handle_changes());
+
+        let this = self.clone();
+        self.cycle_combo_row.connect_selected_notify(move |_| this.handle_changes());
+    }
+
+    fn connect_save_event(self: &Rc<Self>) {
+        let this = self.clone();
+        self.save_action.connect_activate(move |_, _| {
+            let context = this.state.borrow().context.clone();
+            let mut data = context.data().clone();
+            let payee = this.get_data().assign_global_id();
+            let payee_id = payee.id;
+
+            if data.payees.iter().any(|p| p.id != payee_id && payee.names().any(|name| p.matches(name))) {
+                return context
+                    .with_ui_action(UiAction::push_notification(gettext("Payee name or alias already exists")))
+                    .propagate();
+            }
+
+            data.add_or_update_payee(payee);
+
+            context
+                .with_data(data)
+                .with_navigation_action(NavigationAction::navigate_to_payee(payee_id))
+                .with_ui_action(UiAction::push_notification(gettext("Payee saved")))
+                .propagate()
+        });
+    }
+
+    fn handle_remove_confirmation(self: &Rc<Self>) {
+        let state = self.state.borrow();
+        let context = state.context.clone();
+        let payee_id = state.payee.id;
+        drop(state);
+
+        let mut data = context.data().clone();
+        data.remove_payee_by_id(payee_id);
+
+        context
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Payee removed")))
+            .with_navigation_action(NavigationAction::NavigateToPrevious)
+            .propagate();
+    }
+
+    fn connect_remove_event(self: &Rc<Self>) {
+        let this = self.clone();
+        self.remove_action.connect_activate(move |_, _| {
+            let context = this.state.borrow().context.clone();
+            let this_clone = this.clone();
+            context.with_ui_action(UiAction::OpenAlertDialog {
+                title: gettext("Remove payee"),
+                message: gettext("Are you sure you want to remove this payee? Its transactions are kept."),
+                buttons: vec![
+                    AlertButton::cancel(),
+                    AlertButton::remove().destructive(),
+                ],
+                callback: Box::new(move |button| {
+                    if let Some(button) = button {
+                        if button.button_type == AlertButtonType::Remove {
+                            this_clone.handle_remove_confirmation();
+                        }
+                    }
+                })
+            }).propagate();
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<PayeePage> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<PayeePage> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        let NavigationAction::NavigateToPayee { payee: payee_id } = action else {
+            unreachable!();
+        };
+
+        let payee = payee_id
+            .and_then(|id| context.data().find_payee_by_id(id))
+            .cloned()
+            .unwrap_or_default();
+        let has_transactions = !context.data().payee_transactions(payee.id).is_empty();
+
+        self.set_data(&payee, has_transactions);
+        if payee.is_created() {
+            self.set_report(&payee, context);
+        }
+        *self.state.borrow_mut() = State {
+            context: context.clone(),
+            payee,
+        };
+    }
+
+    fn deactivate(&self) {
+        self.clear_data();
+        *self.state.borrow_mut() = State::default();
+    }
+
+}
+
+impl PageContent for Rc<PayeePage> {
+    fn title(&self) -> String {
+        gettext("Payee")
+    }
+
+    fn action_group(&self) -> Option<&gio::SimpleActionGroup> {
+        Some(&self.action_group)
+    }
+}
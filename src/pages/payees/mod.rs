@@ -0,0 +1,114 @@
+mod payee_row;
+
+use crate::data::Data;
+use crate::prelude::*;
+use crate::context::*;
+use crate::utils::ScrollablePane;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct Payees {
+    scrollable_pane: ScrollablePane,
+    payees_list_box: gtk::ListBox,
+    insert_payee_row: adw::ButtonRow,
+
+    context: RefCell<Context>
+}
+
+impl Payees {
+    pub fn new() -> Rc<Self> {
+        let payees_list_box = Self::build_list_box();
+        let insert_payee_row = Self::build_insert_button_row();
+
+        let mut scrollable_pane = ScrollablePane::new();
+        scrollable_pane.add_header(&gettext("Spending by Payee"));
+        scrollable_pane.add_group(vec![&payees_list_box]);
+        scrollable_pane.add_group(vec![&insert_payee_row]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            payees_list_box,
+            insert_payee_row,
+            context: Default::default()
+        });
+        this.connect_insert_payee_row_activated();
+        this
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let placeholder = gtk::Label::new(Some(&gettext("No payees")));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_margin_top(12);
+        placeholder.set_margin_bottom(12);
+
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box.set_placeholder(Some(&placeholder));
+        list_box
+    }
+
+    fn build_insert_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(&gettext("Insert Payee"));
+        button_row.set_start_icon_name(Some("list-add-symbolic"));
+        button_row
+    }
+
+    fn add_payee_rows(&self, data: &Data, context: &Context) {
+        for (payee_id, balance) in data.payee_totals() {
+            let Some(payee) = data.find_payee_by_id(payee_id) else { continue };
+            let transactions_count = data.payee_transactions(payee_id).len();
+            let payee_row = payee_row::PayeeRow::new(payee, &balance, transactions_count, data.currency);
+            let context = context.clone();
+            payee_row.connect_activated(move |payee_id| {
+                context
+                    .clone()
+                    .with_navigation_action(NavigationAction::navigate_to_payee(payee_id))
+                    .propagate();
+            });
+            self.payees_list_box.append(payee_row.widget());
+        }
+    }
+
+    fn connect_insert_payee_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.insert_payee_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let context = this.context.borrow().clone();
+
+            context
+                .with_navigation_action(NavigationAction::navigate_to_new_payee())
+                .propagate()
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Payees> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<Payees> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        if !matches!(action, NavigationAction::NavigateToPayees) { unreachable!() };
+        self.add_payee_rows(context.data(), context);
+        self.context.replace(context.clone());
+    }
+
+    fn deactivate(&self) {
+        self.payees_list_box.remove_all();
+        self.context.take();
+    }
+}
+
+impl PageContent for Rc<Payees> {
+    fn title(&self) -> String {
+        gettext("Payees")
+    }
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+use crate::data::*;
+
+pub struct PayeeRow {
+    payee_id: usize,
+    action_row: adw::ActionRow
+}
+
+impl PayeeRow {
+
+    pub fn new(payee: &Payee, balance: &Balance, transactions_count: usize, currency: Currency) -> Self {
+        let action_row = Self::build_action_row(payee, balance, transactions_count, currency);
+        Self {
+            payee_id: payee.id,
+            action_row
+        }
+    }
+
+    fn build_label(text: &str, css_class: &str) -> gtk::Label {
+        let label = gtk::Label::new(Some(text));
+            label.set_halign(gtk::Align::End);
+            label.add_css_class("numeric");
+            label.add_css_class("caption");
+            label.add_css_class(css_class);
+        label
+    }
+
+    fn build_amounts_box(balance: &Balance, currency: Currency) -> gtk::Box {
+        let v_box = gtk::Box::new(gtk::Orientation::Vertical, 5);
+        v_box.set_valign(gtk::Align::Center);
+        v_box.set_halign(gtk::Align::End);
+        v_box.append(&Self::build_label(&currency.format_amount(-balance.expense), "error"));
+        if balance.income > 0.0 {
+            v_box.append(&Self::build_label(&currency.format_amount(balance.income), "success"));
+        }
+        v_box
+    }
+
+    fn build_subtitle(payee: &Payee, transactions_count: usize) -> String {
+        let transactions_count = transactions_count as u32;
+        let count = ngettext_f("{count} transaction", "{count} transactions", transactions_count, &[("count", &transactions_count.to_string())]);
+        if payee.aliases.is_empty() {
+            count
+        } else {
+            format!("{} · {}", count, payee.aliases.join(", "))
+        }
+    }
+
+    fn build_action_row(payee: &Payee, balance: &Balance, transactions_count: usize, currency: Currency) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&payee.name);
+        action_row.set_subtitle(&Self::build_subtitle(payee, transactions_count));
+        action_row.set_activatable(true);
+        action_row.add_suffix(&Self::build_amounts_box(balance, currency));
+        action_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        action_row
+    }
+
+    pub fn connect_activated(&self, callback: impl Fn(usize) + 'static) {
+        let payee_id = self.payee_id;
+        self.action_row.connect_activated(move |_| callback(payee_id));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for PayeeRow {
+    fn widget(&self) -> &gtk::Widget {
+        self.action_row.upcast_ref()
+    }
+}
@@ -0,0 +1,354 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::*;
+use crate::utils::{ScrollablePane, AlertButton, AlertButtonType, LocaleSpinExtension};
+
+use gtk::gio;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct State {
+    context: Context,
+    rule: Rule,
+}
+
+pub struct RulePage {
+    scrollable_pane: ScrollablePane,
+    name_entry_row: adw::EntryRow,
+    enabled_switch_row: adw::SwitchRow,
+    pattern_entry_row: adw::EntryRow,
+    min_amount_expander_row: adw::ExpanderRow,
+    min_amount_spin_row: adw::SpinRow,
+    max_amount_expander_row: adw::ExpanderRow,
+    max_amount_spin_row: adw::SpinRow,
+    category_entry_row: adw::EntryRow,
+    payee_entry_row: adw::EntryRow,
+    description_entry_row: adw::EntryRow,
+    matches_label: gtk::Label,
+    save_action: gio::SimpleAction,
+    remove_action: gio::SimpleAction,
+    action_group: gio::SimpleActionGroup,
+
+    state: RefCell<State>,
+}
+
+impl RulePage {
+    pub fn new() -> Rc<Self> {
+        let name_entry_row = Self::build_entry_row(&gettext("Name"));
+        let enabled_switch_row = Self::build_enabled_switch_row();
+        let pattern_entry_row = Self::build_entry_row(&gettext("Transaction Name Matches (Regular Expression)"));
+        let (min_amount_expander_row, min_amount_spin_row) = Self::build_amount_expander_row(&gettext("Minimum Amount"));
+        let (max_amount_expander_row, max_amount_spin_row) = Self::build_amount_expander_row(&gettext("Maximum Amount"));
+        let category_entry_row = Self::build_entry_row(&gettext("Set Category"));
+        let payee_entry_row = Self::build_entry_row(&gettext("Set Payee"));
+        let description_entry_row = Self::build_entry_row(&gettext("Set Description"));
+        let (matches_row, matches_label) = Self::build_label_row(&gettext("Matching Transactions"));
+        let save_button_row = Self::build_button_row(&gettext("Save"), "document-save-symbolic", "suggested-action");
+            save_button_row.set_action_name(Some(&Shortcut::Save.detailed_name()));
+        let remove_button_row = Self::build_button_row(&gettext("Remove"), "user-trash-symbolic", "destructive-action");
+            remove_button_row.set_action_name(Some(&Shortcut::Delete.detailed_name()));
+        let save_action = Shortcut::Save.build_action();
+        let remove_action = Shortcut::Delete.build_action();
+        let action_group = build_action_group(&[&save_action, &remove_action]);
+
+        let mut scrollable_pane = ScrollablePane::new();
+            scrollable_pane.add_header(&gettext("Rule"));
+            scrollable_pane.add_group(vec![
+                name_entry_row.upcast_ref::<gtk::Widget>(),
+                enabled_switch_row.upcast_ref(),
+            ]);
+            scrollable_pane.add_header(&gettext("Conditions"));
+            scrollable_pane.add_group(vec![
+                pattern_entry_row.upcast_ref::<gtk::Widget>(),
+                min_amount_expander_row.upcast_ref(),
+                max_amount_expander_row.upcast_ref(),
+            ]);
+            scrollable_pane.add_header(&gettext("Actions"));
+            scrollable_pane.add_group(vec![&category_entry_row, &payee_entry_row, &description_entry_row]);
+            scrollable_pane.add_group(vec![&matches_row]);
+            scrollable_pane.add_separator();
+            scrollable_pane.add_group(vec![&remove_button_row]);
+            scrollable_pane.add_group(vec![&save_button_row]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            name_entry_row,
+            enabled_switch_row,
+            pattern_entry_row,
+            min_amount_expander_row,
+            min_amount_spin_row,
+            max_amount_expander_row,
+            max_amount_spin_row,
+            category_entry_row,
+            payee_entry_row,
+            description_entry_row,
+            matches_label,
+            save_action,
+            remove_action,
+            action_group,
+
+            state: RefCell::new(State::default()),
+        });
+        this.connect_entries_change_event();
+        this.connect_save_event();
+        this.connect_remove_event();
+        this
+    }
+
+    fn build_entry_row(title: &str) -> adw::EntryRow {
+        let entry_row = adw::EntryRow::new();
+        entry_row.set_show_apply_button(false);
+        entry_row.set_title(title);
+        entry_row
+    }
+
+    fn build_enabled_switch_row() -> adw::SwitchRow {
+        let switch_row = adw::SwitchRow::new();
+        switch_row.set_title(&gettext("Enabled"));
+        switch_row.set_subtitle(&gettext("Apply this rule when transactions are saved"));
+        switch_row
+    }
+
+    fn build_amount_expander_row(title: &str) -> (adw::ExpanderRow, adw::SpinRow) {
+        let adjustment = gtk::Adjustment::new(0.0, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Amount"));
+
+        let expander_row = adw::ExpanderRow::new();
+        expander_row.set_title(title);
+        expander_row.set_show_enable_switch(true);
+        expander_row.set_enable_expansion(false);
+        expander_row.add_row(&spin_row);
+        (expander_row, spin_row)
+    }
+
+    fn build_label_row(title: &str) -> (adw::ActionRow, gtk::Label) {
+        let label = gtk::Label::new(None);
+        label.add_css_class("numeric");
+        label.add_css_class("heading");
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(title);
+        action_row.add_suffix(&label);
+        (action_row, label)
+    }
+
+    fn build_button_row(title: &str, icon: &str, css_class: &str) -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(title);
+        button_row.set_start_icon_name(Some(icon));
+        button_row.add_css_class(css_class);
+        button_row
+    }
+
+    fn set_amount(expander_row: &adw::ExpanderRow, spin_row: &adw::SpinRow, amount: Option<f64>) {
+        expander_row.set_enable_expansion(amount.is_some());
+        spin_row.set_value(amount.unwrap_or(0.0));
+    }
+
+    fn get_amount(expander_row: &adw::ExpanderRow, spin_row: &adw::SpinRow) -> Option<f64> {
+        expander_row.enables_expansion().then(|| spin_row.value())
+    }
+
+    fn get_text(entry_row: &adw::EntryRow) -> Option<String> {
+        let text = entry_row.text().trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn set_data(&self, rule: &Rule) {
+        let scroll_page_children = self.scrollable_pane.get_children();
+        for (index, child) in scroll_page_children.iter().enumerate() {
+            child.set_visible(rule.is_created() || index != 9);
+        }
+
+        self.name_entry_row.set_text(&rule.name);
+        self.enabled_switch_row.set_active(rule.enabled);
+        self.pattern_entry_row.set_text(&rule.pattern);
+        Self::set_amount(&self.min_amount_expander_row, &self.min_amount_spin_row, rule.min_amount);
+        Self::set_amount(&self.max_amount_expander_row, &self.max_amount_spin_row, rule.max_amount);
+        self.category_entry_row.set_text(rule.category.as_deref().unwrap_or_default());
+        self.payee_entry_row.set_text(rule.payee.as_deref().unwrap_or_default());
+        self.description_entry_row.set_text(rule.description.as_deref().unwrap_or_default());
+        self.save_action.set_enabled(false);
+        self.remove_action.set_enabled(rule.is_created());
+    }
+
+    fn get_data(&self) -> Rule {
+        Rule {
+            name: self.name_entry_row.text().trim().to_string(),
+            pattern: self.pattern_entry_row.text().to_string(),
+            min_amount: Self::get_amount(&self.min_amount_expander_row, &self.min_amount_spin_row),
+            max_amount: Self::get_amount(&self.max_amount_expander_row, &self.max_amount_spin_row),
+            category: Self::get_text(&self.category_entry_row),
+            payee: Self::get_text(&self.payee_entry_row),
+            description: Self::get_text(&self.description_entry_row),
+            enabled: self.enabled_switch_row.is_active(),
+            ..self.state.borrow().rule.clone()
+        }
+    }
+
+    fn clear_data(&self) {
+        self.name_entry_row.set_text("");
+        self.enabled_switch_row.set_active(true);
+        self.pattern_entry_row.set_text("");
+        Self::set_amount(&self.min_amount_expander_row, &self.min_amount_spin_row, None);
+        Self::set_amount(&self.max_amount_expander_row, &self.max_amount_spin_row, None);
+        self.category_entry_row.set_text("");
+        self.payee_entry_row.set_text("");
+        self.description_entry_row.set_text("");
+        self.matches_label.set_text("");
+    }
+
+    fn update_matches(&self, rule: &Rule) {
+        if rule.compile().is_ok() {
+            self.pattern_entry_row.remove_css_class("error");
+        } else {
+            self.pattern_entry_row.add_css_class("error");
+        }
+
+        let count = self.state.borrow().context.data().count_rule_matches(rule);
+        self.matches_label.set_text(&count.to_string());
+    }
+
+    fn handle_changes(&self) {
+        let data = self.get_data();
+        self.update_matches(&data);
+
+        let previous_data = &self.state.borrow().rule;
+        if data.is_valid() && data.is_different(previous_data) {
+            self.save_action.set_enabled(true);
+        } else {
+            self.save_action.set_enabled(false);
+        }
+    }
+
+    fn connect_entries_change_event(self: &Rc<Self>) {
+        let entry_rows = [
+            &self.name_entry_row,
+            &self.pattern_entry_row,
+            &self.category_entry_row,
+            &self.payee_entry_row,
+            &self.description_entry_row,
+        ];
+        for entry_row in entry_rows {
+            let this = self.clone();
+            entry_row.connect_changed(move |_| this.handle_changes());
+        }
+
+        for expander_row in [&self.min_amount_expander_row, &self.max_amount_expander_row] {
+            let this = self.clone();
+            expander_row.connect_enable_expansion_notify(move |_| this.handle_changes());
+        }
+
+        for spin_row in [&self.min_amount_spin_row, &self.max_amount_spin_row] {
+            let this = self.clone();
+            spin_row.connect_value_notify(move |_| this.handle_changes());
+        }
+
+        let this = self.clone();
+        self.enabled_switch_row.connect_active_notify(move |_| this.handle_changes());
+    }
+
+    fn connect_save_event(self: &Rc<Self>) {
+        let this = self.clone();
+        self.save_action.connect_activate(move |_, _| {
+            let context = this.state.borrow().context.clone();
+            let mut data = context.data().clone();
+            let rule = this.get_data().assign_global_id();
+            let rule_id = rule.id;
+
+            data.add_or_update_rule(rule);
+
+            context
+                .with_data(data)
+                .with_navigation_action(NavigationAction::navigate_to_rule(rule_id))
+                .with_ui_action(UiAction::push_notification(gettext("Rule saved")))
+                .propagate()
+        });
+    }
+
+    fn handle_remove_confirmation(self: &Rc<Self>) {
+        let state = self.state.borrow();
+        let context = state.context.clone();
+        let rule_id = state.rule.id;
+        drop(state);
+
+        let mut data = context.data().clone();
+        data.remove_rule_by_id(rule_id);
+
+        context
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(gettext("Rule removed")))
+            .with_navigation_action(NavigationAction::NavigateToPrevious)
+            .propagate();
+    }
+
+    fn connect_remove_event(self: &Rc<Self>) {
+        let this = self.clone();
+        self.remove_action.connect_activate(move |_, _| {
+            let context = this.state.borrow().context.clone();
+            let this_clone = this.clone();
+            context.with_ui_action(UiAction::OpenAlertDialog {
+                title: gettext("Remove rule"),
+                message: gettext("Are you sure you want to remove this rule? Transactions it already changed are kept."),
+                buttons: vec![
+                    AlertButton::cancel(),
+                    AlertButton::remove().destructive(),
+                ],
+                callback: Box::new(move |button| {
+                    if let Some(button) = button {
+                        if button.button_type == AlertButtonType::Remove {
+                            this_clone.handle_remove_confirmation();
+                        }
+                    }
+                })
+            }).propagate();
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<RulePage> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<RulePage> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        let NavigationAction::NavigateToRule { rule: rule_id } = action else {
+            unreachable!();
+        };
+
+        let rule = rule_id
+            .and_then(|id| context.data().find_rule_by_id(id))
+            .cloned()
+            .unwrap_or_default();
+
+        *self.state.borrow_mut() = State {
+            context: context.clone(),
+            rule: rule.clone(),
+        };
+        self.set_data(&rule);
+        self.update_matches(&rule);
+    }
+
+    fn deactivate(&self) {
+        self.clear_data();
+        *self.state.borrow_mut() = State::default();
+    }
+
+}
+
+impl PageContent for Rc<RulePage> {
+    fn title(&self) -> String {
+        gettext("Rule")
+    }
+
+    fn action_group(&self) -> Option<&gio::SimpleActionGroup> {
+        Some(&self.action_group)
+    }
+}
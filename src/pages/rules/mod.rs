@@ -0,0 +1,224 @@
+mod rule_row;
+
+use crate::data::*;
+use crate::prelude::*;
+use crate::context::*;
+use crate::utils::ScrollablePane;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct State {
+    context: Context,
+    wallet_ids: Vec<usize>,
+}
+
+pub struct Rules {
+    scrollable_pane: ScrollablePane,
+    rules_list_box: gtk::ListBox,
+    insert_rule_row: adw::ButtonRow,
+    wallet_combo_row: adw::ComboRow,
+    apply_button_row: adw::ButtonRow,
+    changes_list_box: gtk::ListBox,
+
+    state: RefCell<State>
+}
+
+impl Rules {
+    pub fn new() -> Rc<Self> {
+        let rules_list_box = Self::build_list_box(&gettext("No rules"));
+        let insert_rule_row = Self::build_button_row(&gettext("Insert Rule"), "list-add-symbolic");
+        let wallet_combo_row = Self::build_wallet_combo_row();
+        let apply_button_row = Self::build_button_row(&gettext("Apply Rules"), "object-select-symbolic");
+        apply_button_row.add_css_class("suggested-action");
+        let changes_list_box = Self::build_list_box(&gettext("No changes"));
+
+        let mut scrollable_pane = ScrollablePane::new();
+        scrollable_pane.add_header(&gettext("Rules"));
+        scrollable_pane.add_group(vec![&rules_list_box]);
+        scrollable_pane.add_group(vec![&insert_rule_row]);
+        scrollable_pane.add_separator();
+        scrollable_pane.add_header(&gettext("Preview"));
+        scrollable_pane.add_group(vec![&wallet_combo_row]);
+        scrollable_pane.add_group(vec![&changes_list_box]);
+        scrollable_pane.add_group(vec![&apply_button_row]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            rules_list_box,
+            insert_rule_row,
+            wallet_combo_row,
+            apply_button_row,
+            changes_list_box,
+            state: Default::default()
+        });
+        this.connect_insert_rule_row_activated();
+        this.connect_wallet_combo_row_changed();
+        this.connect_apply_button_row_activated();
+        this
+    }
+
+    fn build_list_box(placeholder_text: &str) -> gtk::ListBox {
+        let placeholder = gtk::Label::new(Some(placeholder_text));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_margin_top(12);
+        placeholder.set_margin_bottom(12);
+
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box.set_placeholder(Some(&placeholder));
+        list_box
+    }
+
+    fn build_button_row(title: &str, icon: &str) -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(title);
+        button_row.set_start_icon_name(Some(icon));
+        button_row
+    }
+
+    fn build_wallet_combo_row() -> adw::ComboRow {
+        let combo_row = adw::ComboRow::new();
+        combo_row.set_title(&gettext("Wallet"));
+        combo_row
+    }
+
+    fn build_change_row(change: &RuleChange) -> adw::ActionRow {
+        let subtitle = change
+            .changed_fields()
+            .into_iter()
+            .map(|(field, before, after)| {
+                format!("{}: {} → {}", gettext(field), before.unwrap_or("—"), after.unwrap_or("—"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&change.after.name);
+        action_row.set_subtitle(&subtitle);
+        action_row
+    }
+
+    fn add_rule_rows(&self, data: &Data, context: &Context) {
+        for rule in data.rules.iter() {
+            let rule_row = rule_row::RuleRow::new(rule, data.count_rule_matches(rule));
+            let context = context.clone();
+            rule_row.connect_activated(move |rule_id| {
+                context
+                    .clone()
+                    .with_navigation_action(NavigationAction::navigate_to_rule(rule_id))
+                    .propagate();
+            });
+            self.rules_list_box.append(rule_row.widget());
+        }
+    }
+
+    fn set_wallets(&self, data: &Data, wallet_id: Option<usize>) {
+        let names: Vec<&str> = data.wallets.iter().map(|w| w.name.as_str()).collect();
+        let wallet_ids: Vec<usize> = data.wallets.iter().map(|w| w.id).collect();
+        let position = wallet_id
+            .and_then(|id| wallet_ids.iter().position(|w| *w == id))
+            .unwrap_or(0);
+
+        self.state.borrow_mut().wallet_ids = wallet_ids;
+        self.wallet_combo_row.set_model(Some(&gtk::StringList::new(&names)));
+        self.wallet_combo_row.set_selected(position as u32);
+    }
+
+    fn selected_wallet_id(&self) -> Option<usize> {
+        self.state
+            .borrow()
+            .wallet_ids
+            .get(self.wallet_combo_row.selected() as usize)
+            .copied()
+    }
+
+    fn update_preview(&self) {
+        self.changes_list_box.remove_all();
+        let Some(wallet_id) = self.selected_wallet_id() else {
+            self.apply_button_row.set_sensitive(false);
+            return;
+        };
+
+        let changes = self.state.borrow().context.data().preview_rules(wallet_id);
+        for change in changes.iter() {
+            self.changes_list_box.append(&Self::build_change_row(change));
+        }
+        self.apply_button_row.set_sensitive(!changes.is_empty());
+    }
+
+    fn connect_insert_rule_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.insert_rule_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let context = this.state.borrow().context.clone();
+
+            context
+                .with_navigation_action(NavigationAction::navigate_to_new_rule())
+                .propagate()
+        });
+    }
+
+    fn connect_wallet_combo_row_changed(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.wallet_combo_row.connect_selected_notify(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.update_preview();
+        });
+    }
+
+    fn connect_apply_button_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.apply_button_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let Some(wallet_id) = this.selected_wallet_id() else { return };
+            let context = this.state.borrow().context.clone();
+            let mut data = context.data().clone();
+            let count = data.run_rules(wallet_id);
+            let count = count as u32;
+            let message = ngettext_f("{count} transaction updated", "{count} transactions updated", count, &[("count", &count.to_string())]);
+
+            context
+                .with_data(data)
+                .with_navigation_action(NavigationAction::navigate_to_rules(Some(wallet_id)))
+                .with_ui_action(UiAction::push_notification(message))
+                .propagate()
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Rules> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<Rules> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        let NavigationAction::NavigateToRules { wallet: wallet_id } = action else {
+            unreachable!();
+        };
+
+        self.state.borrow_mut().context = context.clone();
+        self.add_rule_rows(context.data(), context);
+        self.set_wallets(context.data(), wallet_id);
+        self.update_preview();
+    }
+
+    fn deactivate(&self) {
+        self.rules_list_box.remove_all();
+        self.changes_list_box.remove_all();
+        self.state.take();
+    }
+}
+
+impl PageContent for Rc<Rules> {
+    fn title(&self) -> String {
+        gettext("Rules")
+    }
+}
@@ -0,0 +1,51 @@
+use crate::prelude::*;
+use crate::data::*;
+
+pub struct RuleRow {
+    rule_id: usize,
+    action_row: adw::ActionRow
+}
+
+impl RuleRow {
+
+    pub fn new(rule: &Rule, matches_count: usize) -> Self {
+        let action_row = Self::build_action_row(rule, matches_count);
+        Self {
+            rule_id: rule.id,
+            action_row
+        }
+    }
+
+    fn build_subtitle(rule: &Rule, matches_count: usize) -> String {
+        let matches_count = matches_count as u32;
+        let count = ngettext_f("{count} match", "{count} matches", matches_count, &[("count", &matches_count.to_string())]);
+        format!("{} · {}", rule.pattern, count)
+    }
+
+    fn build_action_row(rule: &Rule, matches_count: usize) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&rule.name);
+        action_row.set_subtitle(&Self::build_subtitle(rule, matches_count));
+        action_row.set_activatable(true);
+        if !rule.enabled {
+            let disabled_label = gtk::Label::new(Some(&gettext("Disabled")));
+            disabled_label.add_css_class("dim-label");
+            disabled_label.add_css_class("caption");
+            action_row.add_suffix(&disabled_label);
+        }
+        action_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        action_row
+    }
+
+    pub fn connect_activated(&self, callback: impl Fn(usize) + 'static) {
+        let rule_id = self.rule_id;
+        self.action_row.connect_activated(move |_| callback(rule_id));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for RuleRow {
+    fn widget(&self) -> &gtk::Widget {
+        self.action_row.upcast_ref()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::data::*;
+use crate::prelude::*;
+use crate::context::*;
+use crate::i18n::gettext_noop;
+use crate::utils::ScrollablePane;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const QUERY_HINT: &str = gettext_noop("Filter with amount:>100, amount:10..50, from:2024-01-01, to:2024-12-31, cycle:monthly or wallet:name");
+
+pub struct Search {
+    scrollable_pane: ScrollablePane,
+    search_entry: gtk::SearchEntry,
+    results_label: gtk::Label,
+    results_list_box: gtk::ListBox,
+
+    context: RefCell<Context>
+}
+
+impl Search {
+    pub fn new() -> Rc<Self> {
+        let search_entry = Self::build_search_entry();
+        let hint_label = Self::build_hint_label();
+        let results_label = Self::build_results_label();
+        let results_list_box = Self::build_list_box();
+
+        let mut scrollable_pane = ScrollablePane::new();
+        scrollable_pane.add_group(vec![search_entry.upcast_ref::<gtk::Widget>(), hint_label.upcast_ref()]);
+        scrollable_pane.add_group(vec![&results_label]);
+        scrollable_pane.add_group(vec![&results_list_box]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            search_entry,
+            results_label,
+            results_list_box,
+            context: Default::default()
+        });
+        this.connect_search_changed();
+        this
+    }
+
+    fn build_search_entry() -> gtk::SearchEntry {
+        let search_entry = gtk::SearchEntry::new();
+        search_entry.set_placeholder_text(Some(&gettext("Search transactions")));
+        search_entry.set_hexpand(true);
+        search_entry
+    }
+
+    fn build_hint_label() -> gtk::Label {
+        let label = gtk::Label::new(Some(&gettext(QUERY_HINT)));
+        label.add_css_class("dim-label");
+        label.add_css_class("caption");
+        label.set_halign(gtk::Align::Start);
+        label.set_wrap(true);
+        label.set_margin_top(6);
+        label
+    }
+
+    fn build_results_label() -> gtk::Label {
+        let label = gtk::Label::new(None);
+        label.add_css_class("heading");
+        label.set_halign(gtk::Align::Start);
+        label
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let placeholder = gtk::Label::new(Some(&gettext("No matching transactions")));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_margin_top(12);
+        placeholder.set_margin_bottom(12);
+
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box.set_placeholder(Some(&placeholder));
+        list_box
+    }
+
+    fn build_result_row(wallet: &Wallet, transaction: &Transaction, context: &Context) -> adw::ActionRow {
+        let amount_label = gtk::Label::new(Some(&wallet.currency.format_amount(transaction.amount)));
+        amount_label.add_css_class("numeric");
+        amount_label.add_css_class(if transaction.amount < 0.0 { "error" } else { "success" });
+
+        let subtitle = format!(
+            "{} · {} · {}",
+            wallet.name,
+            transaction.cycle,
+            Preferences::current().locale.format_date(transaction.start_date)
+        );
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&transaction.name);
+        action_row.set_subtitle(&subtitle);
+        action_row.set_activatable(true);
+        action_row.add_suffix(&amount_label);
+        action_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        let context = context.clone();
+        let (wallet_id, transaction_id) = (wallet.id, transaction.id);
+        action_row.connect_activated(move |_| {
+            context
+                .clone()
+                .with_navigation_action(NavigationAction::navigate_to_transaction(wallet_id, transaction_id))
+                .propagate();
+        });
+        action_row
+    }
+
+    /// Looks the query up in the storage index on a worker thread, falling back
+    /// to the loaded data when the storage has no index for it.
+    fn update_results(self: &Rc<Self>) {
+        self.results_list_box.remove_all();
+        let query = self.search_entry.text();
+
+        let Ok(filter) = query.parse::<TransactionFilter>() else {
+            self.search_entry.add_css_class("error");
+            self.results_label.set_text(&gettext("Invalid filter"));
+            return;
+        };
+        self.search_entry.remove_css_class("error");
+
+        if filter.is_empty() {
+            self.results_label.set_text("");
+            return;
+        }
+
+        let context = self.context.borrow().clone();
+        let file = context.file().clone();
+        let this = Rc::downgrade(self);
+        context.background().spawn(
+            move || {
+                let ids = file.search(&filter);
+                (filter, ids)
+            },
+            move |(filter, ids)| {
+                let Some(this) = this.upgrade() else { return };
+                // A later query replaced this one while it was running.
+                if this.search_entry.text() != query { return }
+                this.show_results(&filter, ids);
+            }
+        );
+    }
+
+    fn show_results(&self, filter: &TransactionFilter, ids: Option<Vec<(usize, usize)>>) {
+        self.results_list_box.remove_all();
+        let context = self.context.borrow();
+        let mut results = match ids {
+            Some(ids) => context.data()
+                .find_transactions(&ids)
+                .into_iter()
+                .filter(|(_, t)| filter.matches(t))
+                .collect(),
+            None => context.data().search(filter),
+        };
+        results.sort_by_key(|(_, t)| std::cmp::Reverse(t.start_date));
+
+        let count = results.len() as u32;
+        self.results_label.set_text(&ngettext_f("{count} result", "{count} results", count, &[("count", &count.to_string())]));
+        for (wallet, transaction) in results {
+            self.results_list_box.append(&Self::build_result_row(wallet, transaction, &context));
+        }
+    }
+
+    fn connect_search_changed(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.search_entry.connect_search_changed(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.update_results();
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Search> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<Search> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        if !matches!(action, NavigationAction::NavigateToSearch) { unreachable!() };
+        self.context.replace(context.clone());
+        self.update_results();
+        self.search_entry.grab_focus();
+    }
+
+    fn deactivate(&self) {
+        self.results_list_box.remove_all();
+        self.context.take();
+    }
+}
+
+impl PageContent for Rc<Search> {
+    fn title(&self) -> String {
+        gettext("Search")
+    }
+}
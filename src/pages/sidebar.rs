@@ -0,0 +1,135 @@
+use crate::prelude::*;
+use crate::context::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Wallet list shown next to the pages on wide windows.
+/// The first row leads to the overview, the others to each wallet.
+pub struct Sidebar {
+    toolbar_view: adw::ToolbarView,
+    window_title: adw::WindowTitle,
+    list_box: gtk::ListBox,
+    wallets: RefCell<Vec<(usize, String)>>,
+    context: RefCell<Context>,
+}
+
+impl Sidebar {
+
+    pub fn new() -> Rc<Self> {
+        let list_box = Self::build_list_box();
+        let window_title = adw::WindowTitle::new(&gettext("Wallets"), "");
+        let toolbar_view = Self::build_toolbar_view(&window_title, &list_box);
+        let this = Rc::new(Self {
+            toolbar_view,
+            window_title,
+            list_box,
+            wallets: RefCell::new(Vec::new()),
+            context: RefCell::new(Context::default()),
+        });
+        this.connect_row_activated();
+        this
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::Single);
+        list_box.add_css_class("navigation-sidebar");
+        list_box.append(&Self::build_row(&gettext("Overview"), "go-home-symbolic"));
+        list_box
+    }
+
+    fn build_toolbar_view(window_title: &adw::WindowTitle, list_box: &gtk::ListBox) -> adw::ToolbarView {
+        let header_bar = adw::HeaderBar::new();
+        header_bar.set_title_widget(Some(window_title));
+
+        let scrolled_window = gtk::ScrolledWindow::new();
+        scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+        scrolled_window.set_vexpand(true);
+        scrolled_window.set_child(Some(list_box));
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header_bar);
+        toolbar_view.set_content(Some(&scrolled_window));
+        toolbar_view
+    }
+
+    fn build_row(title: &str, icon_name: &str) -> gtk::ListBoxRow {
+        let label = gtk::Label::new(Some(title));
+        label.set_halign(gtk::Align::Start);
+        label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+        let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        row_box.append(&gtk::Image::from_icon_name(icon_name));
+        row_box.append(&label);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_child(Some(&row_box));
+        row
+    }
+
+    fn update_rows(&self, context: &Context) {
+        let wallets: Vec<(usize, String)> = context
+            .data()
+            .wallets
+            .iter()
+            .map(|wallet| (wallet.id, wallet.name.clone()))
+            .collect();
+        if *self.wallets.borrow() == wallets {
+            return;
+        }
+
+        while let Some(row) = self.list_box.row_at_index(1) {
+            self.list_box.remove(&row);
+        }
+        for (_, name) in &wallets {
+            self.list_box.append(&Self::build_row(name, "go-next-symbolic"));
+        }
+        self.wallets.replace(wallets);
+    }
+
+    /// Highlights the wallet the current page belongs to, or the overview row.
+    fn update_selection(&self, action: Option<NavigationAction>) {
+        let wallet = match action {
+            Some(NavigationAction::NavigateToWallet { wallet }) => wallet,
+            Some(NavigationAction::NavigateToTransaction { wallet, .. }) => Some(wallet),
+            _ => None,
+        };
+        let index = wallet
+            .and_then(|id| self.wallets.borrow().iter().position(|(wallet_id, _)| *wallet_id == id))
+            .map_or(0, |position| position + 1);
+        self.list_box.select_row(self.list_box.row_at_index(index as i32).as_ref());
+    }
+
+    pub fn update(&self, action: Option<NavigationAction>, context: &Context) {
+        self.context.replace(context.clone());
+        self.window_title.set_subtitle(&context.data().name);
+        self.update_rows(context);
+        self.update_selection(action);
+    }
+
+    fn connect_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.list_box.connect_row_activated(move |_, row| {
+            let Some(this) = this.upgrade() else { return };
+            let action = match row.index() {
+                0 => NavigationAction::NavigateToOverview,
+                index => {
+                    let Some((wallet_id, _)) = this.wallets.borrow().get(index as usize - 1).cloned() else { return };
+                    NavigationAction::navigate_to_wallet(wallet_id)
+                }
+            };
+            let context = this.context.borrow().clone();
+            context
+                .with_navigation_action(action)
+                .propagate();
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Sidebar> {
+    fn widget(&self) -> &gtk::Widget {
+        self.toolbar_view.upcast_ref()
+    }
+}
@@ -1,36 +1,45 @@
 use crate::prelude::*;
+use crate::context::Shortcut;
 
 pub enum ButtonClick {
     LoadPrevious,
-    Load,
     Create,
-    About
+    About,
+    Preferences
 }
 
+#[derive(Clone)]
 pub struct Buttons {
     container: gtk::Box,
     load_previous_button: gtk::Button,
     about_button: gtk::Button,
+    preferences_button: gtk::Button,
     create_button: gtk::Button,
-    load_button: gtk::Button,
 }
 
 impl Buttons {
 
     pub fn new() -> Self {
-        let load_previous_button = Self::build_button("document-open-recent-symbolic", "Load Previous document");
+        let load_previous_button = Self::build_button("document-open-recent-symbolic", &gettext("Load Previous document"));
             load_previous_button.add_css_class("suggested-action");
-        let create_button = Self::build_button("document-new-symbolic", "Create new document");
-        let load_button = Self::build_button("document-open-symbolic", "Load document");
-        let about_button = Self::build_about_button();
-        let container = Self::build_container(&load_previous_button, &about_button, &load_button, &create_button);
+        let create_button = Self::build_button("document-new-symbolic", &gettext("Create new document"));
+        let load_button = Self::build_button("document-open-symbolic", &gettext("Load document"));
+            load_button.set_action_name(Some(&Shortcut::Open.detailed_name()));
+        let about_button = Self::build_link_button("help-about-symbolic", &gettext("About"));
+        let preferences_button = Self::build_link_button("preferences-system-symbolic", &gettext("Preferences"));
+        let shortcuts_button = Self::build_link_button("preferences-desktop-keyboard-shortcuts-symbolic", &gettext("Shortcuts"));
+            shortcuts_button.set_action_name(Some(&Shortcut::ShowShortcuts.detailed_name()));
+        let new_window_button = Self::build_link_button("window-new-symbolic", &gettext("New Window"));
+            new_window_button.set_action_name(Some(&Shortcut::NewWindow.detailed_name()));
+        let link_buttons = [&new_window_button, &preferences_button, &shortcuts_button, &about_button];
+        let container = Self::build_container(&load_previous_button, &link_buttons, &load_button, &create_button);
         
         Self {
             container,
             load_previous_button,
             about_button,
+            preferences_button,
             create_button,
-            load_button,
         }
     }
 
@@ -47,8 +56,8 @@ impl Buttons {
         button
     }
 
-    fn build_about_button() -> gtk::Button {
-        let button = Self::build_button("help-about-symbolic", "About");
+    fn build_link_button(icon_name: &str, text: &str) -> gtk::Button {
+        let button = Self::build_button(icon_name, text);
         button.add_css_class("link");
         button.set_focusable(false);
         button
@@ -62,9 +71,18 @@ impl Buttons {
         alternative_buttons
     }
 
+    fn build_link_container(buttons: &[&gtk::Button]) -> gtk::Box {
+        let link_buttons = gtk::Box::new(gtk::Orientation::Horizontal, 10);
+        link_buttons.set_homogeneous(true);
+        for button in buttons {
+            link_buttons.append(*button);
+        }
+        link_buttons
+    }
+
     fn build_container(
         load_previous_button: &gtk::Button,
-        about_button: &gtk::Button,
+        link_buttons: &[&gtk::Button],
         load_button: &gtk::Button,
         create_button: &gtk::Button
     ) -> gtk::Box {
@@ -72,7 +90,7 @@ impl Buttons {
         buttons.set_homogeneous(true);
         buttons.append(load_previous_button);
         buttons.append(&Self::build_alternative_container(create_button, load_button));
-        buttons.append(about_button);
+        buttons.append(&Self::build_link_container(link_buttons));
         buttons
     }
 
@@ -81,13 +99,13 @@ impl Buttons {
         self.about_button.connect_clicked(move |_| callback_clone(ButtonClick::About));
 
         let callback_clone = callback.clone();
-        self.load_previous_button.connect_clicked(move |_| callback_clone(ButtonClick::LoadPrevious));
+        self.preferences_button.connect_clicked(move |_| callback_clone(ButtonClick::Preferences));
 
         let callback_clone = callback.clone();
-        self.create_button.connect_clicked(move |_| callback_clone(ButtonClick::Create));
+        self.load_previous_button.connect_clicked(move |_| callback_clone(ButtonClick::LoadPrevious));
 
         let callback_clone = callback.clone();
-        self.load_button.connect_clicked(move |_| callback_clone(ButtonClick::Load));
+        self.create_button.connect_clicked(move |_| callback_clone(ButtonClick::Create));
     }
 
     pub fn set_load_previous_button_sensitive(&self, is_sensitive: bool) {
@@ -1,9 +1,12 @@
 mod buttons;
+mod unlock;
+mod recent_list;
 
 use crate::context::*;
-use crate::data::Data;
+use crate::data::{Data, Preferences};
 use crate::prelude::*;
 use crate::metadata;
+use crate::utils::{AlertButton, AlertButtonType};
 
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -11,10 +14,16 @@ use std::cell::RefCell;
 
 use gtk::{gdk, gio};
 
+const BUTTONS_CHILD: &str = "buttons";
+const UNLOCK_CHILD: &str = "unlock";
+
 pub struct Status {
     status_page: adw::StatusPage,
+    stack: gtk::Stack,
     drop_target: gtk::DropTarget,
     buttons: buttons::Buttons,
+    unlock: unlock::Unlock,
+    recent_list: recent_list::RecentList,
     context: Rc<RefCell<Context>>,
 }
 
@@ -22,96 +31,159 @@ impl Status {
 
     pub fn new() -> Self {
         let buttons = buttons::Buttons::new();
-        let status_page = Self::build_status_page(&buttons);
+        let unlock = unlock::Unlock::new();
+        let recent_list = recent_list::RecentList::new();
+        let stack = Self::build_stack(&buttons, &unlock);
+        let status_page = Self::build_status_page(&stack, &recent_list);
         let drop_target = gtk::DropTarget::new(gio::File::static_type(), gdk::DragAction::COPY);
         status_page.add_controller(drop_target.clone());
 
         let this = Self {
             status_page,
+            stack,
             buttons,
+            unlock,
+            recent_list,
             drop_target,
             context: Rc::new(RefCell::new(Default::default())),
         };
         this.connect_drag_and_drop();
         this.connect_buttons_events();
+        this.connect_unlock_events();
+        this.connect_recent_events();
         this
     }
 
-    fn build_status_child(buttons: &buttons::Buttons) -> adw::Clamp {
+    fn build_stack(buttons: &buttons::Buttons, unlock: &unlock::Unlock) -> gtk::Stack {
+        let stack = gtk::Stack::new();
+        stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        stack.add_named(buttons.widget(), Some(BUTTONS_CHILD));
+        stack.add_named(unlock.widget(), Some(UNLOCK_CHILD));
+        stack
+    }
+
+    fn build_clamp(child: &impl IsA<gtk::Widget>, maximum_size: i32) -> adw::Clamp {
         let clamp = adw::Clamp::new();
-        clamp.set_child(Some(buttons.widget()));
-        clamp.set_maximum_size(300);
+        clamp.set_child(Some(child));
+        clamp.set_maximum_size(maximum_size);
         clamp
     }
 
-    fn build_status_page(buttons: &buttons::Buttons) -> adw::StatusPage {
+    fn build_status_child(stack: &gtk::Stack, recent_list: &recent_list::RecentList) -> gtk::Box {
+        let status_child = gtk::Box::new(gtk::Orientation::Vertical, 36);
+        status_child.append(&Self::build_clamp(stack, 300));
+        status_child.append(&Self::build_clamp(recent_list.widget(), 480));
+        status_child
+    }
+
+    fn build_status_page(stack: &gtk::Stack, recent_list: &recent_list::RecentList) -> adw::StatusPage {
         let status_page = adw::StatusPage::new();
-        status_page.set_description(Some("Select a data document or create a new one"));
         status_page.set_icon_name(Some(metadata::APP_ICON_NAME));
-        status_page.set_child(Some(&Self::build_status_child(buttons)));
+        status_page.set_child(Some(&Self::build_status_child(stack, recent_list)));
         status_page
     }
 
+    /// Opens `file`, or comes back to the status page to ask for its passphrase when it is encrypted.
+    /// The file is read on a worker thread.
+    fn open_file(context: Context, file: DataFile) {
+        context.background().clone().spawn(
+            move || {
+                let data = if file.is_locked() { None } else { Some(file.load()) };
+                (file, data)
+            },
+            move |(file, data)| match data {
+                None => context
+                    .with_file(file)
+                    .with_navigation_action(NavigationAction::NavigateToStatus)
+                    .propagate(),
+                Some(Ok(data)) => context
+                    .with_file(file)
+                    .with_data(data)
+                    .with_navigation_action(NavigationAction::NavigateToOverview)
+                    .propagate(),
+                Some(Err(e)) => context
+                    .with_ui_action(UiAction::push_error(gettext("Failed to load data"), e))
+                    .propagate(),
+            }
+        );
+    }
+
     fn load_previous_file(context: Context) {
-        let mut context = context;
-        if !context.file().exists() {
-            let Some(file) = context
-                .directory()
-                .find_most_recent_data_file()
-                .ok()
-                .flatten() else {
-                    return context
-                        .with_ui_action(UiAction::push_notification("No data files found"))
-                        .propagate();
-                };
-            context = context.with_file(file);
+        if context.file().exists() {
+            let file = context.file().clone();
+            return Self::open_file(context, file);
         }
 
-        if let Ok(data) = context.file().load() {
-            context
-                .with_data(data)
-                .with_navigation_action(NavigationAction::NavigateToOverview)
-                .propagate();
-        } else {
-            context
-                .with_ui_action(UiAction::push_notification("Failed to load data"))
-                .propagate();
-        }
+        let directory = context.directory().clone();
+        context.background().clone().spawn(
+            move || directory.find_most_recent_data_file().ok().flatten(),
+            move |file| match file {
+                Some(file) => Self::open_file(context, file),
+                None => context
+                    .with_ui_action(UiAction::push_notification(gettext("No data files found")))
+                    .propagate(),
+            }
+        );
     }
 
     fn handle_file_loaded(path: Option<PathBuf>, context: &Context) {
         let Some(path) = path else { return };
-        let data_file = DataFile::new(path);
-        if let Ok(data) = data_file.load() {
-            context
-                .clone()
-                .with_file(data_file)
-                .with_data(data)
-                .with_navigation_action(NavigationAction::NavigateToOverview)
-                .propagate();
-        } else {
-            context
-                .clone()
-                .with_ui_action(UiAction::push_notification("Failed to load data"))
-                .propagate();
-        }
+        Self::open_file(context.clone(), DataFile::new(path));
     }
 
-    fn load_file(context: Context) {
+    /// Derives the key from `passphrase` on a worker thread, since that is slow on purpose.
+    fn unlock_file(context: Context, passphrase: String, unlock: unlock::Unlock) {
+        let file = context.file().clone();
+        context.background().clone().spawn(
+            move || file.unlock(&passphrase),
+            move |result| match result {
+                Ok(file) => Self::open_file(context, file),
+                Err(e) if e.io_kind() == Some(std::io::ErrorKind::PermissionDenied) => {
+                    unlock.show_error();
+                    context
+                        .with_ui_action(UiAction::push_notification(gettext("Wrong passphrase")))
+                        .propagate();
+                },
+                Err(e) => context
+                    .with_ui_action(UiAction::push_error(gettext("Failed to load data"), e))
+                    .propagate(),
+            }
+        );
+    }
+
+    pub(super) fn load_file(context: Context) {
         context
             .with_ui_action(UiAction::open_file_chooser(Self::handle_file_loaded))
             .propagate();
     }
 
+    /// Asks for the name of a new document and creates its file in the data directory.
     fn create_file(context: Context) {
-        let data = Data::default();
-        let Ok(data_file) = context
-            .directory()
-            .create_new_data_file() else {
-                return context
-                    .with_ui_action(UiAction::push_notification("Failed to create new data file"))
-                    .propagate();
-            };
+        let callback_context = context.clone();
+        context.with_ui_action(UiAction::OpenEntryDialog {
+            title: gettext("New Document"),
+            text: gettext("Untitled Document"),
+            button: AlertButton::new(gettext("Create"), AlertButtonType::Ok).suggested(),
+            callback: Box::new(move |name| {
+                let Some(name) = name else { return };
+                Self::create_named_file(callback_context, name.trim());
+            }),
+        }).propagate();
+    }
+
+    fn create_named_file(context: Context, name: &str) {
+        let name = if name.is_empty() { gettext("Untitled Document") } else { name.to_owned() };
+        let data = Data {
+            name: name.clone(),
+            currency: Preferences::current().default_currency,
+            ..Data::default()
+        };
+        let data_file = match context.directory().create_new_data_file(&name) {
+            Ok(data_file) => data_file,
+            Err(e) => return context
+                .with_ui_action(UiAction::push_error(gettext("Failed to create new data file"), e.into()))
+                .propagate(),
+        };
 
         context
             .with_file(data_file)
@@ -124,6 +196,10 @@ impl Status {
         context.with_ui_action(UiAction::OpenAboutDialog).propagate();
     }
 
+    fn show_preferences(context: Context) {
+        context.with_ui_action(UiAction::OpenPreferencesDialog).propagate();
+    }
+
     fn connect_buttons_events(&self) {
         let context = self.context.clone();
         self.buttons.connect_events(move |event| {
@@ -131,18 +207,177 @@ impl Status {
             match event {
                 buttons::ButtonClick::LoadPrevious => Self::load_previous_file(context),
                 buttons::ButtonClick::Create => Self::create_file(context),
-                buttons::ButtonClick::Load => Self::load_file(context),
                 buttons::ButtonClick::About => Self::show_about(context),
+                buttons::ButtonClick::Preferences => Self::show_preferences(context),
+            }
+        });
+    }
+
+    fn connect_unlock_events(&self) {
+        let context = self.context.clone();
+        let unlock = self.unlock.clone();
+        self.unlock.connect_events(move |event| {
+            let context = context.borrow().clone();
+            match event {
+                unlock::UnlockEvent::Unlock(passphrase) => Self::unlock_file(context, passphrase, unlock.clone()),
+                unlock::UnlockEvent::Cancel => context
+                    .with_file(DataFile::default())
+                    .with_navigation_action(NavigationAction::NavigateToStatus)
+                    .propagate(),
             }
         });
     }
 
+    fn refresh(context: Context) {
+        context
+            .with_navigation_action(NavigationAction::NavigateToStatus)
+            .propagate();
+    }
+
+    fn notify(context: Context, message: String) {
+        context
+            .with_ui_action(UiAction::push_notification(message))
+            .with_navigation_action(NavigationAction::NavigateToStatus)
+            .propagate();
+    }
+
+    fn notify_error(context: Context, message: String, error: std::io::Error) {
+        context
+            .with_ui_action(UiAction::push_error(message, error.into()))
+            .with_navigation_action(NavigationAction::NavigateToStatus)
+            .propagate();
+    }
+
+    fn rename_document(context: Context, path: PathBuf, name: String) {
+        let callback_context = context.clone();
+        let callback_path = path.clone();
+        context.with_ui_action(UiAction::OpenEntryDialog {
+            title: gettext("Rename Document"),
+            text: name,
+            button: AlertButton::new(gettext("Rename"), AlertButtonType::Ok).suggested(),
+            callback: Box::new(move |name| {
+                let Some(name) = name else { return };
+                let path = callback_path.clone();
+                callback_context.background().clone().spawn(
+                    move || RecentDocuments::rename(&path, &name),
+                    move |result| match result {
+                        Ok(new_path) => {
+                            RecentDocuments::publish_move(&callback_path, &new_path);
+                            Self::refresh(callback_context)
+                        },
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                            Self::notify(callback_context, gettext("A document with this name already exists"))
+                        },
+                        Err(e) => Self::notify_error(callback_context, gettext("Failed to rename document"), e),
+                    }
+                );
+            }),
+        }).propagate();
+    }
+
+    fn duplicate_document(context: Context, path: PathBuf) {
+        context.background().clone().spawn(
+            move || RecentDocuments::duplicate(&path),
+            move |result| match result {
+                Ok(new_path) => {
+                    RecentDocuments::publish(&new_path);
+                    Self::notify(context, gettext("Document duplicated"))
+                },
+                Err(e) => Self::notify_error(context, gettext("Failed to duplicate document"), e),
+            }
+        );
+    }
+
+    fn remove_document(context: Context, path: PathBuf, name: String, is_in_directory: bool) {
+        if !is_in_directory {
+            return match RecentDocuments::forget(&path) {
+                Ok(()) => Self::refresh(context),
+                Err(e) => Self::notify_error(context, gettext("Failed to remove document"), e),
+            };
+        }
+
+        let callback_context = context.clone();
+        context.with_ui_action(UiAction::OpenAlertDialog {
+            title: gettext("Move Document to Trash?"),
+            message: gettext_f("{name} will be moved to the trash.", &[("name", &name)]),
+            buttons: vec![AlertButton::cancel(), AlertButton::remove()],
+            callback: Box::new(move |button| {
+                if button.is_none_or(|b| b.button_type != AlertButtonType::Remove) {
+                    return;
+                }
+                match RecentDocuments::remove(&path) {
+                    Ok(()) => Self::notify(callback_context, gettext("Document moved to the trash")),
+                    Err(e) => Self::notify_error(callback_context, gettext("Failed to remove document"), e),
+                }
+            }),
+        }).propagate();
+    }
+
+    fn connect_recent_events(&self) {
+        let context = self.context.clone();
+        self.recent_list.connect_events(move |event| {
+            let context = context.borrow().clone();
+            match event {
+                recent_list::RecentEvent::Open(path) => Self::open_file(context, DataFile::new(path)),
+                recent_list::RecentEvent::Pin(path, is_pinned) => match RecentDocuments::set_pinned(&path, is_pinned) {
+                    Ok(()) => Self::refresh(context),
+                    Err(e) => Self::notify_error(context, gettext("Failed to update recent documents"), e),
+                },
+                recent_list::RecentEvent::Rename(path, name) => Self::rename_document(context, path, name),
+                recent_list::RecentEvent::Duplicate(path) => Self::duplicate_document(context, path),
+                recent_list::RecentEvent::Remove(path, name, is_in_directory) => Self::remove_document(context, path, name, is_in_directory),
+            }
+        });
+    }
+
+    /// Shows the passphrase prompt for a locked document, or the buttons otherwise.
+    fn update_child(&self, context: &Context) {
+        if context.file().is_locked() {
+            let file_name = context.file().path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.status_page.set_description(Some(&gettext_f("Enter the passphrase of {file}", &[("file", &file_name)])));
+            self.status_page.set_icon_name(Some("channel-secure-symbolic"));
+            self.stack.set_visible_child_name(UNLOCK_CHILD);
+            self.recent_list.widget().set_visible(false);
+            self.unlock.reset();
+        } else {
+            self.status_page.set_description(Some(&gettext("Select a data document or create a new one")));
+            self.status_page.set_icon_name(Some(metadata::APP_ICON_NAME));
+            self.stack.set_visible_child_name(BUTTONS_CHILD);
+            self.update_recent(context);
+        }
+    }
+
+    /// Scans the data directory on a worker thread, then fills the recent list with what it found.
+    fn update_recent(&self, context: &Context) {
+        let directory = context.directory().clone();
+        let has_file = context.file().exists();
+        let recent_list = self.recent_list.clone();
+        let buttons = self.buttons.clone();
+        context.background().spawn(
+            move || {
+                let has_recent_file = has_file || directory
+                    .find_most_recent_data_file()
+                    .ok()
+                    .flatten()
+                    .is_some();
+                (RecentDocuments::list(&directory), has_recent_file)
+            },
+            move |(documents, has_recent_file)| {
+                buttons.set_load_previous_button_sensitive(has_recent_file);
+                recent_list.update(&documents);
+            }
+        );
+    }
+
     fn connect_drag_and_drop(&self) {
         let context = self.context.clone();
         self.drop_target.connect_accept(move |_, drop| {
             let formats = drop.formats();
             for mime_type in formats.mime_types() {
-                if mime_type.contains("application/json") || mime_type.contains("text/plain") {
+                if mime_type.contains("application/json") || mime_type.contains("sqlite") || mime_type.contains("text/plain") {
                     return true
                 }
             }
@@ -159,18 +394,6 @@ impl Status {
         });
     }
 
-    fn has_recent_file(&self, context: &Context) -> bool {
-        context
-            .file()
-            .exists() 
-        || context
-            .directory()
-            .find_most_recent_data_file()
-            .ok()
-            .flatten()
-            .is_some()
-    }
-
 }
 
 impl HasWidget<gtk::Widget> for Status {
@@ -184,7 +407,7 @@ impl LifeCycle<NavigationAction> for Status {
     fn activate(&self, action: NavigationAction, context: &Context) {
         if !matches!(action, NavigationAction::NavigateToStatus) { unreachable!() };
 
-        self.buttons.set_load_previous_button_sensitive(self.has_recent_file(&context));
+        self.update_child(context);
         self.context.replace(context.clone());
     }
 
@@ -195,7 +418,7 @@ impl LifeCycle<NavigationAction> for Status {
 }
 
 impl PageContent for Status {
-    fn title(&self) -> &str {
-        &metadata::APP_TITLE
+    fn title(&self) -> String {
+        metadata::APP_TITLE.to_string()
     }
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+use crate::prelude::*;
+use crate::context::RecentDocument;
+use crate::data::Preferences;
+
+use chrono::{DateTime, Local};
+use gtk::{gio, glib};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+const ROW_ACTIONS: &str = "recent";
+
+pub enum RecentEvent {
+    Open(PathBuf),
+    Pin(PathBuf, bool),
+    Rename(PathBuf, String),
+    Duplicate(PathBuf),
+    Remove(PathBuf, String, bool),
+}
+
+/// Recent documents on the status page, each with a menu to pin, rename, duplicate or remove it.
+#[derive(Clone)]
+pub struct RecentList {
+    group: adw::PreferencesGroup,
+    rows: Rc<RefCell<Vec<adw::ActionRow>>>,
+    callback: Rc<RefCell<Option<Rc<dyn Fn(RecentEvent)>>>>,
+}
+
+impl RecentList {
+
+    pub fn new() -> Self {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&gettext("Recent Documents"));
+        Self {
+            group,
+            rows: Rc::new(RefCell::new(Vec::new())),
+            callback: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn format_modified(document: &RecentDocument) -> String {
+        let Some(modified) = document.modified else { return String::new() };
+        let modified: DateTime<Local> = modified.into();
+        let locale = Preferences::current().locale;
+        format!("{} {}", locale.format_date(modified.date_naive()), modified.format("%H:%M"))
+    }
+
+    fn format_summary(document: &RecentDocument) -> String {
+        let Some((wallet_count, net_worth, currency, is_converted)) = document.summary else {
+            return gettext("Encrypted");
+        };
+        let wallets = ngettext_f("{count} wallet", "{count} wallets", wallet_count as u32, &[("count", &wallet_count.to_string())]);
+        format!("{} · {}", wallets, currency.format_total(net_worth, is_converted))
+    }
+
+    fn format_location(document: &RecentDocument) -> String {
+        let Some(parent) = document.file.path.parent() else { return String::new() };
+        let home = glib::home_dir();
+        match parent.strip_prefix(&home) {
+            Ok(relative) => format!("~/{}", relative.display()),
+            Err(_) => parent.display().to_string(),
+        }
+    }
+
+    fn build_menu(document: &RecentDocument) -> gio::Menu {
+        let menu = gio::Menu::new();
+        let pin_label = if document.is_pinned { gettext("Unpin") } else { gettext("Pin") };
+        menu.append(Some(&pin_label), Some(&format!("{}.pin", ROW_ACTIONS)));
+        menu.append(Some(&gettext("Rename…")), Some(&format!("{}.rename", ROW_ACTIONS)));
+        menu.append(Some(&gettext("Duplicate")), Some(&format!("{}.duplicate", ROW_ACTIONS)));
+        let remove_label = if document.is_in_directory { gettext("Move to Trash") } else { gettext("Remove From List") };
+        menu.append(Some(&remove_label), Some(&format!("{}.remove", ROW_ACTIONS)));
+        menu
+    }
+
+    fn build_event(name: &str, document: &RecentDocument) -> RecentEvent {
+        let path = document.file.path.clone();
+        match name {
+            "pin" => RecentEvent::Pin(path, !document.is_pinned),
+            "rename" => RecentEvent::Rename(path, document.name.clone()),
+            "duplicate" => RecentEvent::Duplicate(path),
+            _ => RecentEvent::Remove(path, document.name.clone(), document.is_in_directory),
+        }
+    }
+
+    fn build_action_group(&self, document: &RecentDocument) -> gio::SimpleActionGroup {
+        let action_group = gio::SimpleActionGroup::new();
+        for name in ["pin", "rename", "duplicate", "remove"] {
+            let action = gio::SimpleAction::new(name, None);
+            let callback = self.callback.clone();
+            let document = document.clone();
+            action.connect_activate(move |_, _| {
+                let callback = callback.borrow().clone();
+                if let Some(callback) = callback {
+                    callback(Self::build_event(name, &document));
+                }
+            });
+            action_group.add_action(&action);
+        }
+        action_group
+    }
+
+    fn build_row(&self, document: &RecentDocument) -> adw::ActionRow {
+        let menu_button = gtk::MenuButton::new();
+        menu_button.set_icon_name("view-more-symbolic");
+        menu_button.set_valign(gtk::Align::Center);
+        menu_button.add_css_class("flat");
+        menu_button.set_menu_model(Some(&Self::build_menu(document)));
+
+        let subtitle = format!(
+            "{}\n{} · {}",
+            Self::format_location(document),
+            Self::format_modified(document),
+            Self::format_summary(document)
+        );
+
+        let row = adw::ActionRow::new();
+        row.set_title(&glib::markup_escape_text(&document.name));
+        row.set_subtitle(&glib::markup_escape_text(&subtitle));
+        row.set_subtitle_lines(2);
+        row.set_activatable(true);
+        row.add_prefix(&gtk::Image::from_icon_name(document.icon.icon_name()));
+        if document.is_pinned {
+            row.add_suffix(&gtk::Image::from_icon_name("view-pin-symbolic"));
+        }
+        row.add_suffix(&menu_button);
+        row.insert_action_group(ROW_ACTIONS, Some(&self.build_action_group(document)));
+
+        let callback = self.callback.clone();
+        let path = document.file.path.clone();
+        row.connect_activated(move |_| {
+            let callback = callback.borrow().clone();
+            if let Some(callback) = callback {
+                callback(RecentEvent::Open(path.clone()));
+            }
+        });
+        row
+    }
+
+    pub fn update(&self, documents: &[RecentDocument]) {
+        for row in self.rows.borrow_mut().drain(..) {
+            self.group.remove(&row);
+        }
+        let rows: Vec<adw::ActionRow> = documents.iter().map(|document| self.build_row(document)).collect();
+        for row in &rows {
+            self.group.add(row);
+        }
+        self.rows.replace(rows);
+        self.group.set_visible(!documents.is_empty());
+    }
+
+    pub fn connect_events(&self, callback: impl Fn(RecentEvent) + 'static) {
+        self.callback.replace(Some(Rc::new(callback)));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for RecentList {
+    fn widget(&self) -> &gtk::Widget {
+        self.group.upcast_ref()
+    }
+}
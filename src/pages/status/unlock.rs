@@ -0,0 +1,82 @@
+use crate::prelude::*;
+
+pub enum UnlockEvent {
+    Unlock(String),
+    Cancel,
+}
+
+/// Passphrase prompt shown instead of the buttons when an encrypted document is opened.
+#[derive(Clone)]
+pub struct Unlock {
+    container: gtk::Box,
+    passphrase_row: adw::PasswordEntryRow,
+    unlock_button: gtk::Button,
+    cancel_button: gtk::Button,
+}
+
+impl Unlock {
+
+    pub fn new() -> Self {
+        let passphrase_row = adw::PasswordEntryRow::new();
+            passphrase_row.set_title(&gettext("Passphrase"));
+        let unlock_button = gtk::Button::with_label(&gettext("Unlock"));
+            unlock_button.add_css_class("pill");
+            unlock_button.add_css_class("suggested-action");
+        let cancel_button = gtk::Button::with_label(&gettext("Cancel"));
+            cancel_button.add_css_class("pill");
+        let container = Self::build_container(&passphrase_row, &unlock_button, &cancel_button);
+
+        Self {
+            container,
+            passphrase_row,
+            unlock_button,
+            cancel_button,
+        }
+    }
+
+    fn build_container(passphrase_row: &adw::PasswordEntryRow, unlock_button: &gtk::Button, cancel_button: &gtk::Button) -> gtk::Box {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.append(passphrase_row);
+
+        let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 10);
+        buttons.set_homogeneous(true);
+        buttons.append(cancel_button);
+        buttons.append(unlock_button);
+
+        let container = gtk::Box::new(gtk::Orientation::Vertical, 20);
+        container.append(&list_box);
+        container.append(&buttons);
+        container
+    }
+
+    pub fn connect_events(&self, callback: impl Fn(UnlockEvent) + Clone + 'static) {
+        let callback_clone = callback.clone();
+        self.cancel_button.connect_clicked(move |_| callback_clone(UnlockEvent::Cancel));
+
+        let callback_clone = callback.clone();
+        let passphrase_row = self.passphrase_row.clone();
+        self.unlock_button.connect_clicked(move |_| callback_clone(UnlockEvent::Unlock(passphrase_row.text().to_string())));
+
+        self.passphrase_row.connect_entry_activated(move |row| callback(UnlockEvent::Unlock(row.text().to_string())));
+    }
+
+    pub fn reset(&self) {
+        self.passphrase_row.set_text("");
+        self.passphrase_row.remove_css_class("error");
+        self.passphrase_row.grab_focus();
+    }
+
+    pub fn show_error(&self) {
+        self.passphrase_row.add_css_class("error");
+        self.passphrase_row.grab_focus();
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Unlock {
+    fn widget(&self) -> &gtk::Widget {
+        self.container.upcast_ref()
+    }
+}
@@ -21,10 +21,10 @@ impl CycleSelector {
         let repeat_button = Self::build_repeat_button();
 
         let button_list = ButtonList::new(true);
-            button_list.add_with_prefixed_icon(Cycle::Daily, Cycle::Daily.icon_name(), Cycle::Daily.as_str());
-            button_list.add_with_prefixed_icon(Cycle::Weekly, Cycle::Weekly.icon_name(), Cycle::Weekly.as_str());
-            button_list.add_with_prefixed_icon(Cycle::Monthly, Cycle::Monthly.icon_name(), Cycle::Monthly.as_str());
-            button_list.add_with_prefixed_icon(Cycle::Yearly, Cycle::Yearly.icon_name(), Cycle::Yearly.as_str());
+            button_list.add_with_prefixed_icon(Cycle::Daily, Cycle::Daily.icon_name(), &gettext(Cycle::Daily.as_str()));
+            button_list.add_with_prefixed_icon(Cycle::Weekly, Cycle::Weekly.icon_name(), &gettext(Cycle::Weekly.as_str()));
+            button_list.add_with_prefixed_icon(Cycle::Monthly, Cycle::Monthly.icon_name(), &gettext(Cycle::Monthly.as_str()));
+            button_list.add_with_prefixed_icon(Cycle::Yearly, Cycle::Yearly.icon_name(), &gettext(Cycle::Yearly.as_str()));
 
         let popover = gtk::Popover::new();
             popover.set_button_list(&button_list);
@@ -49,7 +49,7 @@ impl CycleSelector {
         let icon = gtk::Image::from_icon_name("media-playlist-repeat-symbolic");
             icon.set_halign(gtk::Align::Start);
 
-        let label = gtk::Label::new(Some("Repeat"));
+        let label = gtk::Label::new(Some(&gettext("Repeat")));
             label.set_hexpand(true);
             label.set_halign(gtk::Align::Center);
 
@@ -62,7 +62,7 @@ impl CycleSelector {
             button.set_valign(gtk::Align::Center);
             button.set_focusable(false);
             button.set_child(Some(&h_box));
-            button.set_tooltip_text(Some("Repeat"));
+            button.set_tooltip_text(Some(&gettext("Repeat")));
             button
     }
 
@@ -71,7 +71,7 @@ impl CycleSelector {
         container.append(repeat_button);
 
         let action_row = adw::ActionRow::new();
-        action_row.set_title("Cycle");
+        action_row.set_title(&gettext("Cycle"));
         action_row.add_suffix(&container);
         action_row.set_activatable(true);
         action_row
@@ -1,11 +1,14 @@
 mod cycle_selector;
 mod date_range_picker;
+mod split_editor;
+mod payee_completion;
 
 use crate::prelude::*;
 use crate::context::*;
 use crate::data::*;
-use crate::utils::{AlertButton, AlertButtonType, ScrollablePane};
+use crate::utils::{AlertButton, AlertButtonType, LocaleSpinExtension, ScrollablePane};
 
+use gtk::gio;
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -21,10 +24,15 @@ pub struct TransactionPage {
     cycle_selector_row: cycle_selector::CycleSelector,
     dates_pickers_row: date_range_picker::DateRangePicker,
     name_entry_row: adw::EntryRow,
+    payee_completion: Rc<payee_completion::PayeeCompletion>,
     description_entry_row: adw::EntryRow,
+    category_entry_row: adw::EntryRow,
     amount_spin_row: adw::SpinRow,
-    save_button_row: adw::ButtonRow,
-    remove_button_row: adw::ButtonRow,
+    split_editor: Rc<split_editor::SplitEditor>,
+    save_action: gio::SimpleAction,
+    remove_action: gio::SimpleAction,
+    action_group: gio::SimpleActionGroup,
+    unlock_button: gtk::Button,
 
     state: RefCell<State>,
 }
@@ -35,16 +43,26 @@ impl TransactionPage {
         let cycle_selector_row = cycle_selector::CycleSelector::new(dates_pickers_row.clone());
 
         let name_entry_row = Self::build_name_entry_row();
+        let payee_completion = payee_completion::PayeeCompletion::new(&name_entry_row);
         let description_entry_row = Self::build_description_entry_row();
+        let category_entry_row = Self::build_category_entry_row();
         let amount_spin_row = Self::build_amount_spin_row();
+        let split_editor = split_editor::SplitEditor::new();
         let save_button_row = Self::build_save_button_row();
         let remove_button_row = Self::build_remove_button_row();
+        let save_action = Shortcut::Save.build_action();
+        let remove_action = Shortcut::Delete.build_action();
+        let action_group = build_action_group(&[&save_action, &remove_action]);
+        let (lock_row, unlock_button) = Self::build_lock_row();
 
         let mut scrollable_pane = ScrollablePane::new();
-        scrollable_pane.add_group(vec![&name_entry_row, &description_entry_row]);
+        scrollable_pane.add_group(vec![&lock_row]);
+        scrollable_pane.add_group(vec![&name_entry_row, &description_entry_row, &category_entry_row]);
         scrollable_pane.add_group(vec![&amount_spin_row]);
         scrollable_pane.add_group(vec![dates_pickers_row.widget()]);
         scrollable_pane.add_group(vec![cycle_selector_row.widget()]);
+        scrollable_pane.add_header(&gettext("Splits"));
+        scrollable_pane.add_group(vec![split_editor.widget()]);
         scrollable_pane.add_separator();
         scrollable_pane.add_group(vec![&remove_button_row]);
         scrollable_pane.add_group(vec![&save_button_row]);
@@ -53,17 +71,24 @@ impl TransactionPage {
             cycle_selector_row,
             dates_pickers_row,
             name_entry_row,
+            payee_completion,
             description_entry_row,
+            category_entry_row,
             amount_spin_row,
-            save_button_row,
-            remove_button_row,
+            split_editor,
+            save_action,
+            remove_action,
+            action_group,
+            unlock_button,
             scrollable_pane,
 
             state: RefCell::new(State::default()),
         });
         this.connect_entries_changed();
-        this.connect_save_button_activated();
-        this.connect_remove_button_activated();
+        this.connect_save_action_activated();
+        this.connect_remove_action_activated();
+        this.connect_unlock_button_clicked();
+        this.connect_payee_selected();
         this
     }
 
@@ -72,7 +97,7 @@ impl TransactionPage {
         entry_row.set_enable_emoji_completion(true);
         entry_row.set_activates_default(true);
         entry_row.set_show_apply_button(false);
-        entry_row.set_title("Name");
+        entry_row.set_title(&gettext("Name"));
         entry_row
     }
 
@@ -80,10 +105,17 @@ impl TransactionPage {
         let description_row = adw::EntryRow::new();
         description_row.set_enable_emoji_completion(true);
         description_row.set_show_apply_button(false);
-        description_row.set_title("Description");
+        description_row.set_title(&gettext("Description"));
         description_row
     }
 
+    fn build_category_entry_row() -> adw::EntryRow {
+        let entry_row = adw::EntryRow::new();
+        entry_row.set_show_apply_button(false);
+        entry_row.set_title(&gettext("Category"));
+        entry_row
+    }
+
     fn build_amount_spin_row() -> adw::SpinRow {
         let adjustment = gtk::Adjustment::new(
             0.0,
@@ -95,35 +127,59 @@ impl TransactionPage {
         );
         let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
         spin_row.set_value(0.0);
-        spin_row.set_numeric(true);
-        spin_row.set_title("Amount");
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Amount"));
         spin_row
     }
 
     fn build_save_button_row() -> adw::ButtonRow {
         let button_row = adw::ButtonRow::new();
-        button_row.set_title("Save");
+        button_row.set_title(&gettext("Save"));
         button_row.set_start_icon_name(Some("document-save-symbolic"));
         button_row.add_css_class("suggested-action");
         button_row.add_css_class("pill");
-        button_row.set_sensitive(false);
+        button_row.set_action_name(Some(&Shortcut::Save.detailed_name()));
         button_row
     }
 
     fn build_remove_button_row() -> adw::ButtonRow {
         let button_row = adw::ButtonRow::new();
-        button_row.set_title("Remove");
+        button_row.set_title(&gettext("Remove"));
         button_row.set_start_icon_name(Some("user-trash-symbolic"));
         button_row.add_css_class("destructive-action");
         button_row.add_css_class("pill");
-        button_row.set_sensitive(false);
+        button_row.set_action_name(Some(&Shortcut::Delete.detailed_name()));
         button_row
     }
 
+    fn build_lock_row() -> (adw::ActionRow, gtk::Button) {
+        let unlock_button = gtk::Button::with_label(&gettext("Unlock"));
+        unlock_button.set_valign(gtk::Align::Center);
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("Reconciled"));
+        action_row.set_subtitle(&gettext("Some occurrences were reconciled against a statement; editing may change them"));
+        action_row.add_prefix(&gtk::Image::from_icon_name("channel-secure-symbolic"));
+        action_row.add_suffix(&unlock_button);
+        (action_row, unlock_button)
+    }
+
+    fn set_locked(&self, is_locked: bool) {
+        if let Some(lock_group) = self.scrollable_pane.get_children().first() {
+            lock_group.set_visible(is_locked);
+        }
+        self.amount_spin_row.set_sensitive(!is_locked);
+        self.dates_pickers_row.widget().set_sensitive(!is_locked);
+        self.cycle_selector_row.widget().set_sensitive(!is_locked);
+        self.split_editor.set_sensitive(!is_locked);
+        self.unlock_button.set_sensitive(is_locked);
+    }
+
     fn get_data(&self) -> Transaction {
         let end_date = self.dates_pickers_row.get_end_date();
         let start_date = self.dates_pickers_row.get_start_date();
         let description = self.description_entry_row.text().to_string();
+        let category = self.category_entry_row.text().trim().to_string();
         let cycle = if start_date == end_date {
             Cycle::OneTime
         } else {
@@ -143,11 +199,15 @@ impl TransactionPage {
         Transaction {
             name: self.name_entry_row.text().to_string(),
             description: (!description.is_empty()).then(|| description),
-            amount: self.amount_spin_row.text().parse().unwrap_or(0.0),
+            amount: self.amount_spin_row.locale_value(),
             start_date,
             end_date,
             cycle,
+            payee: self.state.borrow().transaction.payee.clone(),
+            category: (!category.is_empty()).then_some(category),
+            splits: self.split_editor.get_splits(),
             id: self.state.borrow().transaction.id,
+            statuses: self.state.borrow().transaction.statuses.clone(),
         }
     }
 
@@ -159,17 +219,24 @@ impl TransactionPage {
             self.dates_pickers_row.set_end_date(end_time);
         }
         self.name_entry_row.set_text(&transaction.name);
+        self.category_entry_row.set_text(transaction.category.as_deref().unwrap_or_default());
         self.amount_spin_row.set_value(transaction.amount as f64);
+        self.split_editor.set_splits(&transaction.splits);
+        self.split_editor.set_total(transaction.amount);
         self.dates_pickers_row.set_start_date(transaction.start_date);
         self.cycle_selector_row.set_selected_cycle(transaction.cycle.clone());
-        self.save_button_row.set_sensitive(false);
-        self.remove_button_row.set_sensitive(transaction.is_created());
+        self.save_action.set_enabled(false);
+        self.remove_action.set_enabled(transaction.is_created() && !transaction.is_locked());
+        self.set_locked(transaction.is_locked());
     }
 
     fn clear_data(&self) {
         self.name_entry_row.set_text("");
         self.description_entry_row.set_text("");
+        self.category_entry_row.set_text("");
         self.amount_spin_row.set_value(0.0);
+        self.split_editor.clear();
+        self.payee_completion.clear();
         self.dates_pickers_row.set_default_dates();
         self.cycle_selector_row.set_selected_cycle(Cycle::default());
     }
@@ -178,9 +245,9 @@ impl TransactionPage {
         let previous_data = &self.state.borrow().transaction;
         let data = self.get_data();
         if data.is_valid() && data.is_different(previous_data) {
-            self.save_button_row.set_sensitive(true);
+            self.save_action.set_enabled(true);
         } else {
-            self.save_button_row.set_sensitive(false);
+            self.save_action.set_enabled(false);
         }
     }
 
@@ -192,7 +259,16 @@ impl TransactionPage {
         self.description_entry_row.connect_changed(move |_| this.handle_changes());
 
         let this = self.clone();
-        self.amount_spin_row.connect_changed(move |_| this.handle_changes());
+        self.category_entry_row.connect_changed(move |_| this.handle_changes());
+
+        let this = self.clone();
+        self.amount_spin_row.connect_changed(move |spin_row| {
+            this.split_editor.set_total(spin_row.locale_value());
+            this.handle_changes();
+        });
+
+        let this = self.clone();
+        self.split_editor.connect_changed(move || this.handle_changes());
 
         let this = self.clone();
         self.dates_pickers_row.connect_changed(move |_, _| this.handle_changes());
@@ -215,19 +291,19 @@ impl TransactionPage {
 
         context
             .with_data(data)
-            .with_ui_action(UiAction::push_notification("Transaction removed"))
+            .with_ui_action(UiAction::push_notification(gettext("Transaction removed")))
             .with_navigation_action(NavigationAction::NavigateToPrevious)
             .propagate();
     }
 
-    fn connect_remove_button_activated(self: &Rc<Self>) {
+    fn connect_remove_action_activated(self: &Rc<Self>) {
         let this = self.clone();
-        self.remove_button_row.connect_activated(move |_| {
+        self.remove_action.connect_activate(move |_, _| {
             let context = this.state.borrow().context.clone();
             let this_clone = this.clone();
             context.with_ui_action(UiAction::OpenAlertDialog {
-                title: "Remove transaction".into(),
-                message: "Are you sure you want to remove this transaction?".into(),
+                title: gettext("Remove transaction"),
+                message: gettext("Are you sure you want to remove this transaction?"),
                 buttons: vec![
                     AlertButton::cancel(),
                     AlertButton::remove().destructive(),
@@ -243,17 +319,55 @@ impl TransactionPage {
         });
     }
 
-    fn connect_save_button_activated(self: &Rc<Self>) {
+    fn connect_unlock_button_clicked(self: &Rc<Self>) {
+        let this = self.clone();
+        self.unlock_button.connect_clicked(move |_| {
+            this.set_locked(false);
+            this.remove_action.set_enabled(true);
+        });
+    }
+
+    fn handle_payee_selected(&self, payee: &Payee) {
+        let is_new = !self.state.borrow().transaction.is_created();
+        self.name_entry_row.set_text(&payee.name);
+
+        if let Some(category) = &payee.category {
+            if self.category_entry_row.text().is_empty() {
+                self.category_entry_row.set_text(category);
+            }
+        }
+        if let Some(amount) = payee.amount {
+            if self.amount_spin_row.value() == 0.0 {
+                self.amount_spin_row.set_value(amount);
+            }
+        }
+        if let Some(cycle) = payee.cycle {
+            if is_new {
+                self.cycle_selector_row.set_selected_cycle(cycle);
+            }
+        }
+        self.handle_changes();
+    }
+
+    fn connect_payee_selected(self: &Rc<Self>) {
+        let this = self.clone();
+        self.payee_completion.connect_selected(move |payee| this.handle_payee_selected(payee));
+    }
+
+    fn connect_save_action_activated(self: &Rc<Self>) {
         let this = self.clone();
 
-        self.save_button_row.connect_activated(move |_| {
+        self.save_action.connect_activate(move |_, _| {
             let state = this.state.borrow();
             let context = state.context.clone();
             let mut wallet_data = state.wallet.clone();
             drop(state);
 
             let mut new_data = context.data().clone();
-            let transaction_data = this.get_data().assign_global_id();
+            let mut transaction_data = new_data.apply_rules(&this.get_data().assign_global_id());
+            if let Some(payee) = new_data.find_payee_by_name(&transaction_data.name) {
+                transaction_data.payee = Some(payee.name.clone());
+            }
 
             let wallet_id = wallet_data.id;
             let transaction_id = transaction_data.id;
@@ -277,7 +391,7 @@ impl TransactionPage {
             ) {
                 return context
                     .with_ui_action(UiAction::push_notification(
-                        "A transaction with the same name already exists within the specified date range."
+                        gettext("A transaction with the same name already exists within the specified date range.")
                     )).propagate();
             }
 
@@ -287,7 +401,7 @@ impl TransactionPage {
             context
                 .with_data(new_data)
                 .with_navigation_action(NavigationAction::navigate_to_transaction(wallet_id, transaction_id))
-                .with_ui_action(UiAction::push_notification("Transaction saved"))
+                .with_ui_action(UiAction::push_notification(gettext("Transaction saved")))
                 .propagate()
         });
     }
@@ -310,11 +424,8 @@ impl LifeCycle<NavigationAction> for Rc<TransactionPage> {
             unreachable!();
         };
 
-        let wallet = context
-            .data()
-            .find_wallet_by_id(wallet_id)
-            .cloned()
-            .unwrap();
+        // Pages removes a transaction page whose wallet is gone before it shows again.
+        let Some(wallet) = context.data().find_wallet_by_id(wallet_id).cloned() else { return };
 
         let transaction = transaction_id
             .and_then(|id| wallet.find_transaction_by_id(id))
@@ -322,6 +433,7 @@ impl LifeCycle<NavigationAction> for Rc<TransactionPage> {
             .unwrap_or_default();
 
         self.set_data(&transaction);
+        self.payee_completion.set_data(context.data());
         *self.state.borrow_mut() = State {
             context: context.clone(),
             wallet,
@@ -336,7 +448,11 @@ impl LifeCycle<NavigationAction> for Rc<TransactionPage> {
 }
 
 impl PageContent for Rc<TransactionPage> {
-    fn title(&self) -> &str {
-        "Transaction"
+    fn title(&self) -> String {
+        gettext("Transaction")
+    }
+
+    fn action_group(&self) -> Option<&gio::SimpleActionGroup> {
+        Some(&self.action_group)
     }
 }
\ No newline at end of file
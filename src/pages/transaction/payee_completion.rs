@@ -0,0 +1,146 @@
+use crate::prelude::*;
+use crate::data::*;
+
+use gtk::glib::clone::Downgrade;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+type SelectedCallback = RefCell<Option<Box<dyn Fn(&Payee)>>>;
+
+pub struct PayeeCompletion {
+    popover: gtk::Popover,
+    list_box: gtk::ListBox,
+
+    data: RefCell<Data>,
+    suggestions: RefCell<Vec<Payee>>,
+    callback: SelectedCallback,
+}
+
+impl PayeeCompletion {
+
+    pub fn new(entry_row: &adw::EntryRow) -> Rc<Self> {
+        let list_box = Self::build_list_box();
+        let popover = Self::build_popover(&list_box, entry_row);
+
+        let this = Rc::new(Self {
+            popover,
+            list_box,
+
+            data: RefCell::new(Data::default()),
+            suggestions: RefCell::new(Vec::new()),
+            callback: RefCell::new(None),
+        });
+        this.connect_entry_changed(entry_row);
+        this.connect_row_activated();
+        this
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("navigation-sidebar");
+        list_box
+    }
+
+    fn build_popover(list_box: &gtk::ListBox, entry_row: &adw::EntryRow) -> gtk::Popover {
+        let popover = gtk::Popover::new();
+        popover.set_child(Some(list_box));
+        popover.set_autohide(false);
+        popover.set_has_arrow(false);
+        popover.set_can_focus(false);
+        popover.set_position(gtk::PositionType::Bottom);
+        popover.set_halign(gtk::Align::Start);
+        popover.set_parent(entry_row);
+        popover
+    }
+
+    fn build_row(payee: &Payee) -> adw::ActionRow {
+        let subtitle = payee.aliases.join(", ");
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&payee.name);
+        action_row.set_subtitle(&subtitle);
+        action_row.set_activatable(true);
+        if let Some(category) = &payee.category {
+            let category_label = gtk::Label::new(Some(category));
+            category_label.add_css_class("dim-label");
+            category_label.add_css_class("caption");
+            action_row.add_suffix(&category_label);
+        }
+        action_row
+    }
+
+    fn update_suggestions(&self, query: &str) {
+        let suggestions: Vec<Payee> = {
+            let data = self.data.borrow();
+            let is_exact_match = data.payees.iter().any(|p| p.name == query);
+            if is_exact_match {
+                Vec::new()
+            } else {
+                data.payee_suggestions(query, MAX_SUGGESTIONS)
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            }
+        };
+
+        self.list_box.remove_all();
+        for payee in suggestions.iter() {
+            self.list_box.append(&Self::build_row(payee));
+        }
+
+        if suggestions.is_empty() {
+            self.popover.popdown();
+        } else {
+            self.popover.popup();
+        }
+        self.suggestions.replace(suggestions);
+    }
+
+    fn connect_entry_changed(self: &Rc<Self>, entry_row: &adw::EntryRow) {
+        let this = self.downgrade();
+        entry_row.connect_changed(move |entry_row| {
+            let Some(this) = this.upgrade() else { return };
+            this.update_suggestions(&entry_row.text());
+        });
+
+        let this = self.downgrade();
+        entry_row.connect_entry_activated(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.popover.popdown();
+        });
+    }
+
+    fn connect_row_activated(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.list_box.connect_row_activated(move |_, row| {
+            let Some(this) = this.upgrade() else { return };
+            let Some(payee) = this.suggestions.borrow().get(row.index() as usize).cloned() else { return };
+            this.popover.popdown();
+            let callback = this.callback.borrow();
+            if let Some(callback) = callback.as_ref() {
+                callback(&payee);
+            }
+        });
+    }
+
+    pub fn set_data(&self, data: &Data) {
+        self.data.replace(Data {
+            payees: data.payees.clone(),
+            ..Data::default()
+        });
+    }
+
+    pub fn clear(&self) {
+        self.popover.popdown();
+        self.list_box.remove_all();
+        self.suggestions.borrow_mut().clear();
+        self.data.replace(Data::default());
+    }
+
+    pub fn connect_selected(&self, callback: impl Fn(&Payee) + 'static) {
+        self.callback.replace(Some(Box::new(callback)));
+    }
+
+}
@@ -0,0 +1,266 @@
+use crate::prelude::*;
+use crate::data::*;
+use crate::utils::LocaleSpinExtension;
+
+use gtk::glib::clone::Downgrade;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+struct SplitLine {
+    row: gtk::ListBoxRow,
+    amount_spin_button: gtk::SpinButton,
+    category_entry: gtk::Entry,
+    memo_entry: gtk::Entry,
+}
+
+impl SplitLine {
+
+    fn get_split(&self) -> Split {
+        let memo = self.memo_entry.text().to_string();
+        Split {
+            amount: self.amount_spin_button.value(),
+            category: self.category_entry.text().trim().to_string(),
+            memo: (!memo.is_empty()).then_some(memo),
+        }
+    }
+
+}
+
+pub struct SplitEditor {
+    container: gtk::Box,
+    lines_list_box: gtk::ListBox,
+    unallocated_label: gtk::Label,
+    add_button_row: adw::ButtonRow,
+
+    lines: RefCell<Vec<SplitLine>>,
+    total: Cell<f64>,
+    callback: RefCell<Option<Box<dyn Fn()>>>,
+}
+
+impl SplitEditor {
+
+    pub fn new() -> Rc<Self> {
+        let lines_list_box = Self::build_list_box();
+        lines_list_box.set_visible(false);
+        let (unallocated_row, unallocated_label) = Self::build_unallocated_row();
+        let add_button_row = Self::build_add_button_row();
+
+        let summary_list_box = Self::build_list_box();
+        summary_list_box.append(&unallocated_row);
+        summary_list_box.append(&add_button_row);
+
+        let container = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        container.append(&lines_list_box);
+        container.append(&summary_list_box);
+
+        let this = Rc::new(Self {
+            container,
+            lines_list_box,
+            unallocated_label,
+            add_button_row,
+
+            lines: RefCell::new(Vec::new()),
+            total: Cell::new(0.0),
+            callback: RefCell::new(None),
+        });
+        this.connect_add_button_activated();
+        this.update_unallocated();
+        this
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box
+    }
+
+    fn build_unallocated_row() -> (adw::ActionRow, gtk::Label) {
+        let label = gtk::Label::new(None);
+        label.add_css_class("numeric");
+        label.set_valign(gtk::Align::Center);
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("Unallocated"));
+        action_row.set_subtitle(&gettext("Split lines must add up to the amount"));
+        action_row.add_suffix(&label);
+        (action_row, label)
+    }
+
+    fn build_add_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(&gettext("Add Split"));
+        button_row.set_start_icon_name(Some("list-add-symbolic"));
+        button_row
+    }
+
+    fn build_amount_spin_button(amount: f64) -> gtk::SpinButton {
+        let adjustment = gtk::Adjustment::new(amount, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_button = gtk::SpinButton::new(Some(&adjustment), 10.0, 2);
+        spin_button.set_locale_format();
+        spin_button.set_valign(gtk::Align::Center);
+        spin_button.set_width_chars(8);
+        spin_button
+    }
+
+    fn build_entry(placeholder: &str, text: &str) -> gtk::Entry {
+        let entry = gtk::Entry::new();
+        entry.set_placeholder_text(Some(placeholder));
+        entry.set_text(text);
+        entry.set_hexpand(true);
+        entry.set_valign(gtk::Align::Center);
+        entry
+    }
+
+    fn build_line(self: &Rc<Self>, split: &Split) -> SplitLine {
+        let amount_spin_button = Self::build_amount_spin_button(split.amount);
+        let category_entry = Self::build_entry(&gettext("Category"), &split.category);
+        let memo_entry = Self::build_entry(&gettext("Memo"), split.memo.as_deref().unwrap_or_default());
+
+        let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
+        remove_button.set_valign(gtk::Align::Center);
+        remove_button.set_tooltip_text(Some(&gettext("Remove split")));
+        remove_button.add_css_class("flat");
+
+        let h_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        h_box.set_margin_top(6);
+        h_box.set_margin_bottom(6);
+        h_box.set_margin_start(12);
+        h_box.set_margin_end(6);
+        h_box.append(&amount_spin_button);
+        h_box.append(&category_entry);
+        h_box.append(&memo_entry);
+        h_box.append(&remove_button);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&h_box));
+
+        let this = self.downgrade();
+        amount_spin_button.connect_value_changed(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+
+        let this = self.downgrade();
+        category_entry.connect_changed(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+
+        let this = self.downgrade();
+        memo_entry.connect_changed(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+
+        let this = self.downgrade();
+        let row_weak = ObjectExt::downgrade(&row);
+        remove_button.connect_clicked(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            let Some(row) = row_weak.upgrade() else { return };
+            this.remove_line(&row);
+        });
+
+        SplitLine {
+            row,
+            amount_spin_button,
+            category_entry,
+            memo_entry,
+        }
+    }
+
+    fn append_line(self: &Rc<Self>, split: &Split) {
+        let line = self.build_line(split);
+        self.lines_list_box.append(&line.row);
+        self.lines_list_box.set_visible(true);
+        self.lines.borrow_mut().push(line);
+    }
+
+    fn remove_line(&self, row: &gtk::ListBoxRow) {
+        self.lines_list_box.remove(row);
+        self.lines.borrow_mut().retain(|line| &line.row != row);
+        self.lines_list_box.set_visible(!self.lines.borrow().is_empty());
+        self.handle_changes();
+    }
+
+    fn unallocated_amount(&self) -> f64 {
+        self.total.get() - self.get_splits().iter().map(|s| s.amount).sum::<f64>()
+    }
+
+    fn update_unallocated(&self) {
+        let unallocated = self.unallocated_amount();
+        let is_allocated = self.lines.borrow().is_empty() || unallocated.abs() < 0.005;
+
+        self.unallocated_label.set_text(&Balance::format_value(unallocated, false));
+        if is_allocated {
+            self.unallocated_label.remove_css_class("error");
+        } else {
+            self.unallocated_label.add_css_class("error");
+        }
+    }
+
+    fn handle_changes(&self) {
+        self.update_unallocated();
+        if let Some(callback) = self.callback.borrow().as_ref() {
+            callback();
+        }
+    }
+
+    fn connect_add_button_activated(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.add_button_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            let split = Split {
+                amount: this.unallocated_amount(),
+                ..Split::default()
+            };
+            this.append_line(&split);
+            this.handle_changes();
+        });
+    }
+
+    pub fn get_splits(&self) -> Vec<Split> {
+        self.lines
+            .borrow()
+            .iter()
+            .map(|line| line.get_split())
+            .collect()
+    }
+
+    pub fn set_splits(self: &Rc<Self>, splits: &[Split]) {
+        self.clear();
+        for split in splits {
+            self.append_line(split);
+        }
+        self.update_unallocated();
+    }
+
+    pub fn set_total(&self, total: f64) {
+        self.total.set(total);
+        self.update_unallocated();
+    }
+
+    pub fn set_sensitive(&self, is_sensitive: bool) {
+        self.container.set_sensitive(is_sensitive);
+    }
+
+    pub fn clear(&self) {
+        self.lines_list_box.remove_all();
+        self.lines_list_box.set_visible(false);
+        self.lines.borrow_mut().clear();
+        self.update_unallocated();
+    }
+
+    pub fn connect_changed(&self, callback: impl Fn() + 'static) {
+        self.callback.replace(Some(Box::new(callback)));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<SplitEditor> {
+    fn widget(&self) -> &gtk::Widget {
+        self.container.upcast_ref()
+    }
+}
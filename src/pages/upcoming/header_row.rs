@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use crate::utils::{ButtonList, PopoverExtension};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const DAYS: [u32; 4] = [7, 14, 30, 90];
+
+fn format_days(days: u32) -> String {
+    ngettext_f("{count} day", "{count} days", days, &[("count", &days.to_string())])
+}
+
+pub struct HeaderRow {
+    horizontal_box: gtk::Box,
+    main_button: gtk::Button,
+    main_button_label: gtk::Label,
+    button_list: ButtonList<u32>,
+    popover: gtk::Popover,
+    callback: Rc<RefCell<Option<Rc<dyn Fn(u32)>>>>
+}
+
+impl HeaderRow {
+
+    pub fn new() -> Self {
+        let main_button_label = Self::build_days_label();
+        let main_button = Self::build_main_button(&main_button_label);
+        let horizontal_box = Self::build_horizontal_box(&main_button);
+        let button_list = Self::build_button_list();
+        let popover = gtk::Popover::new();
+            popover.set_button_list(&button_list);
+            popover.set_parent(&main_button);
+
+        let this = Self {
+            horizontal_box,
+            main_button,
+            main_button_label,
+            button_list,
+            popover,
+            callback: Rc::new(RefCell::new(None))
+        };
+
+        this.connect_button_list_activated();
+        this.connect_main_button_activated();
+        this
+    }
+
+    fn build_days_label() -> gtk::Label {
+        let label = gtk::Label::new(Some(&format_days(DAYS[1])));
+            label.set_hexpand(true);
+            label.set_halign(gtk::Align::Center);
+            label
+    }
+
+    fn build_main_button(label: &gtk::Label) -> gtk::Button {
+        let icon = gtk::Image::from_icon_name("month-symbolic");
+            icon.set_halign(gtk::Align::Start);
+
+        let h_box = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+            h_box.append(&icon);
+            h_box.append(label);
+            h_box.set_hexpand(false);
+
+        let button = gtk::Button::new();
+            button.set_valign(gtk::Align::Center);
+            button.set_focusable(false);
+            button.set_child(Some(&h_box));
+            button.set_tooltip_text(Some(&gettext("Days ahead")));
+            button
+    }
+
+    fn build_button_list() -> ButtonList<u32> {
+        let button_list = ButtonList::new(false);
+        for days in DAYS {
+            button_list.add_with_text(days, &format_days(days));
+        }
+        button_list
+    }
+
+    fn build_horizontal_box(child: &impl IsA<gtk::Widget>) -> gtk::Box {
+        let title_label = gtk::Label::new(Some(&gettext("Upcoming")));
+        title_label.add_css_class("title-3");
+        title_label.set_halign(gtk::Align::Start);
+        title_label.set_hexpand(true);
+
+        let h_box = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+        h_box.append(&title_label);
+        h_box.append(child);
+        h_box.set_valign(gtk::Align::Center);
+        h_box.set_halign(gtk::Align::Fill);
+        h_box.set_hexpand(true);
+        h_box
+    }
+
+    fn connect_button_list_activated(&self) {
+        let callback = self.callback.clone();
+        self.button_list.connect_activated(move |days, _, _| {
+            let callback = callback.borrow().clone();
+            if let Some(callback) = callback {
+                callback(days);
+            }
+        });
+    }
+
+    fn connect_main_button_activated(&self) {
+        let popover_weak = self.popover.downgrade();
+        self.main_button.connect_clicked(move |_| {
+            if let Some(popover) = popover_weak.upgrade() {
+                if !popover.is_visible() {
+                    popover.popup();
+                }
+            }
+        });
+    }
+
+    pub fn set_days(&self, days: u32) {
+        self.main_button_label.set_label(&format_days(days));
+        self.button_list.activate_button(&days);
+    }
+
+    pub fn connect_activated(&self, callback: impl Fn(u32) + 'static) {
+        self.callback.borrow_mut().replace(Rc::new(callback));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for HeaderRow {
+    fn widget(&self) -> &gtk::Widget {
+        self.horizontal_box.upcast_ref()
+    }
+}
@@ -0,0 +1,187 @@
+mod header_row;
+mod occurrence_row;
+
+use crate::data::Data;
+use crate::prelude::*;
+use crate::context::*;
+use crate::utils::ScrollablePane;
+
+use chrono::{Local, NaiveDate};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const DEFAULT_DAYS: u32 = header_row::DAYS[1];
+
+pub struct Upcoming {
+    scrollable_pane: ScrollablePane,
+    header_row: header_row::HeaderRow,
+    occurrences_list_box: gtk::ListBox,
+    current_balance_label: gtk::Label,
+    projected_balance_label: gtk::Label,
+    reminder_days_row: adw::SpinRow,
+
+    context: RefCell<Context>
+}
+
+impl Upcoming {
+    pub fn new() -> Rc<Self> {
+        let header_row = header_row::HeaderRow::new();
+        let occurrences_list_box = Self::build_list_box();
+        let (current_balance_row, current_balance_label) = Self::build_balance_row(&gettext("Current Balance"));
+        let (projected_balance_row, projected_balance_label) = Self::build_balance_row(&gettext("Projected Balance"));
+        let reminder_days_row = Self::build_reminder_days_row();
+
+        let mut scrollable_pane = ScrollablePane::new();
+        scrollable_pane.add_group(vec![header_row.widget()]);
+        scrollable_pane.add_group(vec![&occurrences_list_box]);
+        scrollable_pane.add_separator();
+        scrollable_pane.add_group(vec![&current_balance_row, &projected_balance_row]);
+        scrollable_pane.add_group(vec![&reminder_days_row]);
+
+        let this = Rc::new(Self {
+            scrollable_pane,
+            header_row,
+            occurrences_list_box,
+            current_balance_label,
+            projected_balance_label,
+            reminder_days_row,
+            context: Default::default()
+        });
+        this.connect_header_row_activated();
+        this.connect_reminder_days_row_changed();
+        this
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let placeholder = gtk::Label::new(Some(&gettext("No upcoming payments")));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_margin_top(12);
+        placeholder.set_margin_bottom(12);
+
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        list_box.set_placeholder(Some(&placeholder));
+        list_box
+    }
+
+    fn build_balance_row(title: &str) -> (adw::ActionRow, gtk::Label) {
+        let label = gtk::Label::new(None);
+        label.add_css_class("numeric");
+        label.add_css_class("heading");
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(title);
+        action_row.add_suffix(&label);
+        (action_row, label)
+    }
+
+    fn build_reminder_days_row() -> adw::SpinRow {
+        let spin_row = adw::SpinRow::with_range(0.0, 30.0, 1.0);
+        spin_row.set_title(&gettext("Reminders"));
+        spin_row.set_subtitle(&gettext("Days in advance to notify about due payments"));
+        spin_row.set_numeric(true);
+        spin_row
+    }
+
+    fn add_occurrence_rows(&self, data: &Data, days: u32, context: &Context) -> f64 {
+        // Occurrences due today are listed too, so start from the balance before today.
+        let yesterday = Local::now().naive_local().date().pred_opt().unwrap_or(NaiveDate::MIN);
+        let mut projected_balance = data.net_worth_at(yesterday);
+
+        for occurrence in data.upcoming_occurrences(days) {
+            let Some(wallet) = data.find_wallet_by_id(occurrence.wallet_id) else { continue };
+            let Some(transaction) = wallet.find_transaction_by_id(occurrence.transaction_id) else { continue };
+            projected_balance += occurrence.convert_amount(data.currency).unwrap_or(occurrence.amount);
+
+            let occurrence_row = occurrence_row::OccurrenceRow::new(
+                &occurrence,
+                transaction,
+                wallet,
+                data.currency,
+                projected_balance
+            );
+            let context = context.clone();
+            occurrence_row.connect_activated(move |wallet_id, transaction_id| {
+                context
+                    .clone()
+                    .with_navigation_action(NavigationAction::navigate_to_transaction(wallet_id, transaction_id))
+                    .propagate();
+            });
+            self.occurrences_list_box.append(occurrence_row.widget());
+        }
+
+        projected_balance
+    }
+
+    fn connect_reminder_days_row_changed(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.reminder_days_row.connect_value_notify(move |spin_row| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let context = this.context.borrow().clone();
+
+            let reminder_days = spin_row.value() as u32;
+            let data = context.data();
+            if data.is_empty() || data.reminder_days == reminder_days {
+                return;
+            }
+
+            let mut new_data = data.clone();
+            new_data.reminder_days = reminder_days;
+
+            context
+                .with_data(new_data)
+                .with_navigation_action(NavigationAction::NavigateToCurrent)
+                .propagate()
+        });
+    }
+
+    fn connect_header_row_activated(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.header_row.connect_activated(move |days| {
+            let Some(this) = this.upgrade() else { unreachable!() };
+            let context = this.context.borrow().clone();
+
+            context
+                .with_navigation_action(NavigationAction::navigate_to_upcoming(days))
+                .propagate()
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Upcoming> {
+    fn widget(&self) -> &gtk::Widget {
+        self.scrollable_pane.widget()
+    }
+}
+
+impl LifeCycle<NavigationAction> for Rc<Upcoming> {
+
+    fn activate(&self, action: NavigationAction, context: &Context) {
+        let NavigationAction::NavigateToUpcoming { days } = action else { unreachable!() };
+        let data = context.data();
+
+        self.header_row.set_days(days);
+        let current_balance = data.net_worth();
+        let projected_balance = self.add_occurrence_rows(data, days, context);
+        let is_converted = !data.has_unconverted_amounts();
+        self.current_balance_label.set_text(&data.currency.format_total(current_balance, is_converted));
+        self.projected_balance_label.set_text(&data.currency.format_total(projected_balance, is_converted));
+
+        self.context.replace(context.clone());
+        self.reminder_days_row.set_value(data.reminder_days as f64);
+    }
+
+    fn deactivate(&self) {
+        self.occurrences_list_box.remove_all();
+        self.context.take();
+    }
+}
+
+impl PageContent for Rc<Upcoming> {
+    fn title(&self) -> String {
+        gettext("Upcoming")
+    }
+}
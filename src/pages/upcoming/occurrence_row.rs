@@ -0,0 +1,103 @@
+use crate::prelude::*;
+use crate::data::*;
+
+pub struct OccurrenceRow {
+    wallet_id: usize,
+    transaction_id: usize,
+    action_row: adw::ActionRow
+}
+
+impl OccurrenceRow {
+
+    pub fn new(
+        occurrence: &Occurrence,
+        transaction: &Transaction,
+        wallet: &Wallet,
+        currency: Currency,
+        projected_balance: f64
+    ) -> Self {
+        let action_row = Self::build_action_row(occurrence, transaction, wallet, currency, projected_balance);
+        Self {
+            wallet_id: occurrence.wallet_id,
+            transaction_id: occurrence.transaction_id,
+            action_row
+        }
+    }
+
+    fn build_label(text: &str, is_dimmed: bool) -> gtk::Label {
+        let label = gtk::Label::new(Some(text));
+            label.set_halign(gtk::Align::End);
+            label.set_valign(gtk::Align::Center);
+            label.add_css_class("numeric");
+            label.add_css_class("caption");
+        if is_dimmed {
+            label.add_css_class("dim-label");
+        }
+        label
+    }
+
+    fn build_amounts_box(occurrence: &Occurrence, currency: Currency) -> gtk::Box {
+        let v_box = gtk::Box::new(gtk::Orientation::Vertical, 5);
+        v_box.set_valign(gtk::Align::Center);
+        v_box.set_halign(gtk::Align::End);
+        v_box.append(&Self::build_label(&occurrence.currency.format_amount(occurrence.amount), false));
+        if let Some(converted_amount) = occurrence.convert_amount(currency).filter(|_| occurrence.currency != currency) {
+            v_box.append(&Self::build_label(&currency.format_amount(converted_amount), true));
+        }
+        v_box
+    }
+
+    fn build_suffix(occurrence: &Occurrence, currency: Currency, projected_balance: f64) -> gtk::Box {
+        let projected_label = Self::build_label(&currency.format_amount(projected_balance), false);
+            projected_label.set_tooltip_text(Some(&gettext("Projected balance")));
+            projected_label.add_css_class("heading");
+
+        let h_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+            h_box.set_valign(gtk::Align::Center);
+            h_box.set_halign(gtk::Align::End);
+            h_box.append(&Self::build_amounts_box(occurrence, currency));
+            h_box.append(&gtk::Separator::builder().halign(gtk::Align::Center).build());
+            h_box.append(&projected_label);
+        h_box
+    }
+
+    fn build_subtitle(occurrence: &Occurrence, wallet: &Wallet) -> String {
+        let subtitle = format!("{} · {}", Preferences::current().locale.format_date(occurrence.date), wallet.name);
+        if occurrence.status == OccurrenceStatus::Scheduled {
+            subtitle
+        } else {
+            format!("{} · {}", subtitle, occurrence.status)
+        }
+    }
+
+    fn build_action_row(
+        occurrence: &Occurrence,
+        transaction: &Transaction,
+        wallet: &Wallet,
+        currency: Currency,
+        projected_balance: f64
+    ) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+            action_row.set_activatable(true);
+            action_row.set_title(&transaction.name);
+            action_row.set_subtitle(&Self::build_subtitle(occurrence, wallet));
+            action_row.add_prefix(&gtk::Image::from_icon_name(transaction.cycle.icon_name()));
+            action_row.add_suffix(&Self::build_suffix(occurrence, currency, projected_balance));
+            action_row
+    }
+
+    pub fn connect_activated(&self, callback: impl Fn(usize, usize) + 'static) {
+        let wallet_id = self.wallet_id;
+        let transaction_id = self.transaction_id;
+        self.action_row.connect_activated(move |_| {
+            callback(wallet_id, transaction_id);
+        });
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for OccurrenceRow {
+    fn widget(&self) -> &gtk::Widget {
+        self.action_row.upcast_ref()
+    }
+}
@@ -0,0 +1,176 @@
+use crate::prelude::*;
+use crate::data::*;
+use crate::utils::{DatePicker, LocaleSpinExtension};
+
+use chrono::NaiveDate;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type AddedCallback = Rc<RefCell<Option<Rc<dyn Fn(BalanceAssertion)>>>>;
+type RemovedCallback = Rc<RefCell<Option<Rc<dyn Fn(NaiveDate)>>>>;
+
+pub struct BalanceChecks {
+    container: gtk::Box,
+    list_box: gtk::ListBox,
+    date_picker: Rc<DatePicker>,
+    balance_spin_row: adw::SpinRow,
+    add_button_row: adw::ButtonRow,
+    added_callback: AddedCallback,
+    removed_callback: RemovedCallback,
+}
+
+impl BalanceChecks {
+
+    pub fn new() -> Self {
+        let list_box = Self::build_list_box(Some(&gettext("No balance checks")));
+        let date_picker = DatePicker::new();
+        let balance_spin_row = Self::build_balance_spin_row();
+        let add_button_row = Self::build_add_button_row();
+
+        let form_list_box = Self::build_list_box(None);
+        form_list_box.append(&Self::build_date_row(&date_picker));
+        form_list_box.append(&balance_spin_row);
+        form_list_box.append(&add_button_row);
+
+        let container = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        container.append(&list_box);
+        container.append(&form_list_box);
+
+        let this = Self {
+            container,
+            list_box,
+            date_picker,
+            balance_spin_row,
+            add_button_row,
+            added_callback: Rc::new(RefCell::new(None)),
+            removed_callback: Rc::new(RefCell::new(None)),
+        };
+        this.connect_add_button_activated();
+        this
+    }
+
+    fn build_list_box(placeholder_text: Option<&str>) -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        if let Some(text) = placeholder_text {
+            let placeholder = gtk::Label::new(Some(text));
+            placeholder.add_css_class("dim-label");
+            placeholder.set_margin_top(12);
+            placeholder.set_margin_bottom(12);
+            list_box.set_placeholder(Some(&placeholder));
+        }
+        list_box
+    }
+
+    fn build_date_row(date_picker: &Rc<DatePicker>) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("Statement Date"));
+        action_row.add_suffix(date_picker.widget());
+        action_row
+    }
+
+    fn build_balance_spin_row() -> adw::SpinRow {
+        let adjustment = gtk::Adjustment::new(0.0, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Statement Balance"));
+        spin_row
+    }
+
+    fn build_add_button_row() -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(&gettext("Add Balance Check"));
+        button_row.set_start_icon_name(Some("list-add-symbolic"));
+        button_row
+    }
+
+    fn build_difference_label(discrepancy: &Discrepancy, currency: Currency) -> gtk::Label {
+        let text = if discrepancy.is_balanced() {
+            gettext("Balanced")
+        } else {
+            gettext_f("{amount} off", &[("amount", &currency.format_amount(discrepancy.difference()))])
+        };
+        let label = gtk::Label::new(Some(&text));
+        label.set_valign(gtk::Align::Center);
+        label.add_css_class("caption");
+        label.add_css_class("numeric");
+        if discrepancy.is_balanced() {
+            label.add_css_class("success");
+        } else {
+            label.add_css_class("error");
+        }
+        label
+    }
+
+    fn build_remove_button(date: NaiveDate, callback: RemovedCallback) -> gtk::Button {
+        let button = gtk::Button::from_icon_name("user-trash-symbolic");
+        button.set_valign(gtk::Align::Center);
+        button.add_css_class("flat");
+        button.set_tooltip_text(Some(&gettext("Remove balance check")));
+        button.connect_clicked(move |_| {
+            let callback = callback.borrow().clone();
+            if let Some(callback) = callback {
+                callback(date);
+            }
+        });
+        button
+    }
+
+    fn build_discrepancy_row(&self, discrepancy: &Discrepancy, currency: Currency) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&currency.format_amount(discrepancy.assertion.balance));
+        action_row.set_subtitle(&gettext_f("{date} · computed {amount}", &[
+            ("date", &Preferences::current().locale.format_date(discrepancy.assertion.date)),
+            ("amount", &currency.format_amount(discrepancy.computed_balance))
+        ]));
+        action_row.add_suffix(&Self::build_difference_label(discrepancy, currency));
+        action_row.add_suffix(&Self::build_remove_button(discrepancy.assertion.date, self.removed_callback.clone()));
+        action_row
+    }
+
+    fn connect_add_button_activated(&self) {
+        let callback = self.added_callback.clone();
+        let date_picker = self.date_picker.clone();
+        let balance_spin_row = self.balance_spin_row.clone();
+        self.add_button_row.connect_activated(move |_| {
+            let assertion = BalanceAssertion {
+                date: date_picker.get_date(),
+                balance: balance_spin_row.value(),
+            };
+            let callback = callback.borrow().clone();
+            if let Some(callback) = callback {
+                callback(assertion);
+            }
+        });
+    }
+
+    pub fn set_discrepancies(&self, discrepancies: &[Discrepancy], currency: Currency) {
+        self.list_box.remove_all();
+        for discrepancy in discrepancies {
+            self.list_box.append(&self.build_discrepancy_row(discrepancy, currency));
+        }
+    }
+
+    pub fn clear(&self) {
+        self.list_box.remove_all();
+        self.date_picker.set_default_date();
+        self.balance_spin_row.set_value(0.0);
+    }
+
+    pub fn connect_added(&self, callback: impl Fn(BalanceAssertion) + 'static) {
+        self.added_callback.borrow_mut().replace(Rc::new(callback));
+    }
+
+    pub fn connect_removed(&self, callback: impl Fn(NaiveDate) + 'static) {
+        self.removed_callback.borrow_mut().replace(Rc::new(callback));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for BalanceChecks {
+    fn widget(&self) -> &gtk::Widget {
+        self.container.upcast_ref()
+    }
+}
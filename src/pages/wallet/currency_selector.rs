@@ -10,7 +10,7 @@ static MAX_CURRENCY_WIDTH: LazyLock<i32> = LazyLock::new(|| {
     let label = gtk::Label::new(None);
     let max_width = Currency::as_slice().iter()
         .map(|currency| {
-            label.set_text(&currency.as_long_str());
+            label.set_text(&gettext(currency.as_long_str()));
             label.measure(gtk::Orientation::Horizontal, -1).1
         })
         .max()
@@ -56,7 +56,7 @@ impl CurrencySelector {
         let button_list = ButtonList::new(true);
 
         for currency in Currency::as_slice() {
-            button_list.add_with_prefixed_label(currency, currency.as_symbol(), currency.as_long_str());
+            button_list.add_with_prefixed_label(currency, currency.as_symbol(), &gettext(currency.as_long_str()));
         }
 
         button_list
@@ -65,7 +65,7 @@ impl CurrencySelector {
     fn build_action_row(suffix: &impl IsA<gtk::Widget>) -> adw::ActionRow {
         let action_row = adw::ActionRow::new();
         action_row.set_activatable(true);
-        action_row.set_title("Currency");
+        action_row.set_title(&gettext("Currency"));
         action_row.add_suffix(suffix);
         action_row
     }
@@ -73,7 +73,7 @@ impl CurrencySelector {
     fn build_button_child(currency: Currency) -> gtk::Box {
         let symbol = gtk::Label::new(Some(currency.as_symbol()));
         symbol.set_halign(gtk::Align::Start);
-        let text = gtk::Label::new(Some(currency.as_long_str()));
+        let text = gtk::Label::new(Some(&gettext(currency.as_long_str())));
         text.set_halign(gtk::Align::Center);
         text.set_hexpand(true);
         text.set_width_request(MAX_CURRENCY_WIDTH.to_owned());
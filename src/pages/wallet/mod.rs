@@ -1,11 +1,16 @@
+mod balance_checks;
 mod currency_selector;
+mod reconcile;
 mod transaction_row;
 
 use crate::prelude::*;
 use crate::context::*;
+use crate::settings::Settings;
 use crate::data::*;
-use crate::utils::{ScrollablePane, AlertButton, AlertButtonType};
+use crate::utils::{ScrollablePane, AlertButton, AlertButtonType, DatePicker, LocaleSpinExtension, ViewOptionsRow};
 
+use chrono::NaiveDate;
+use gtk::gio;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -20,6 +25,7 @@ struct State {
     context: Context,
     wallet: Wallet,
     rows: Vec<transaction_row::TransactionRow>,
+    assertions: Vec<BalanceAssertion>,
 }
 
 pub struct WalletPage {
@@ -27,33 +33,69 @@ pub struct WalletPage {
     name_entry_row: adw::EntryRow,
     description_entry_row: adw::EntryRow,
     currency_row: currency_selector::CurrencySelector,
-    save_button_row: adw::ButtonRow,
-    remove_button_row: adw::ButtonRow,
-    insert_transaction_button_row: adw::ButtonRow,
+    opening_balance_spin_row: adw::SpinRow,
+    opening_date_picker: Rc<DatePicker>,
+    balance_checks: balance_checks::BalanceChecks,
+    reconcile: Rc<reconcile::Reconcile>,
+    save_action: gio::SimpleAction,
+    remove_action: gio::SimpleAction,
+    insert_transaction_action: gio::SimpleAction,
+    action_group: gio::SimpleActionGroup,
+    rules_button_row: adw::ButtonRow,
+    view_options_row: Rc<ViewOptionsRow>,
     transactions_list_box: gtk::ListBox,
+    settings: Settings,
 
     state: RefCell<State>,
 }
 
 impl WalletPage {
-    pub fn new() -> Rc<Self> {
+    pub fn new(settings: Settings) -> Rc<Self> {
         let name_entry_row = Self::build_name_entry_row();
         let description_entry_row = Self::build_description_entry_row();
         let transactions_list_box = Self::build_list_box();
         let currency_row = currency_selector::CurrencySelector::new();
-
-        let save_button_row = Self::build_button_row("Save", "document-save-symbolic", ButtonType::Suggested);
-        let remove_button_row = Self::build_button_row("Remove", "user-trash-symbolic", ButtonType::Destructive);
-        let insert_transaction_button_row = Self::build_button_row("Insert", "list-add-symbolic", ButtonType::None);
+        let opening_balance_spin_row = Self::build_opening_balance_spin_row();
+        let opening_date_picker = DatePicker::new();
+        let opening_date_row = Self::build_opening_date_row(&opening_date_picker);
+        let balance_checks = balance_checks::BalanceChecks::new();
+        let reconcile = reconcile::Reconcile::new();
+
+        let save_button_row = Self::build_button_row(&gettext("Save"), "document-save-symbolic", ButtonType::Suggested);
+            save_button_row.set_action_name(Some(&Shortcut::Save.detailed_name()));
+        let remove_button_row = Self::build_button_row(&gettext("Remove"), "user-trash-symbolic", ButtonType::Destructive);
+            remove_button_row.set_action_name(Some(&Shortcut::Delete.detailed_name()));
+        let insert_transaction_button_row = Self::build_button_row(&gettext("Insert"), "list-add-symbolic", ButtonType::None);
+            insert_transaction_button_row.set_action_name(Some(&Shortcut::NewTransaction.detailed_name()));
+        let save_action = Shortcut::Save.build_action();
+        let remove_action = Shortcut::Delete.build_action();
+        let insert_transaction_action = Shortcut::NewTransaction.build_action();
+        let action_group = build_action_group(&[&save_action, &remove_action, &insert_transaction_action]);
+        let rules_button_row = Self::build_button_row(&gettext("Apply Rules"), "edit-find-replace-symbolic", ButtonType::None);
+        let view_options_row = ViewOptionsRow::new();
 
         let mut scrollable_pane = ScrollablePane::new();
-            scrollable_pane.add_header("Wallet");
+            scrollable_pane.add_header(&gettext("Wallet"));
             scrollable_pane.add_group(vec![&name_entry_row, &description_entry_row]);
-            scrollable_pane.add_group(vec![currency_row.widget()]);
+            scrollable_pane.add_group(vec![
+                currency_row.widget(),
+                opening_balance_spin_row.upcast_ref(),
+                opening_date_row.upcast_ref(),
+            ]);
             scrollable_pane.add_separator();
-            scrollable_pane.add_header("Transactions");
+            scrollable_pane.add_header(&gettext("Transactions"));
             scrollable_pane.add_group(vec![&transactions_list_box]);
-            scrollable_pane.add_group(vec![&insert_transaction_button_row]);
+            scrollable_pane.add_group(vec![
+                view_options_row.widget(),
+                insert_transaction_button_row.upcast_ref(),
+                rules_button_row.upcast_ref(),
+            ]);
+            scrollable_pane.add_separator();
+            scrollable_pane.add_header(&gettext("Balance Checks"));
+            scrollable_pane.add_group(vec![balance_checks.widget()]);
+            scrollable_pane.add_separator();
+            scrollable_pane.add_header(&gettext("Reconcile"));
+            scrollable_pane.add_group(vec![reconcile.widget()]);
             scrollable_pane.add_separator();
             scrollable_pane.add_group(vec![&remove_button_row]);
             scrollable_pane.add_group(vec![&save_button_row]);
@@ -61,19 +103,31 @@ impl WalletPage {
         let this = Rc::new(Self {
             name_entry_row,
             description_entry_row,
-            insert_transaction_button_row,
-            save_button_row,
-            remove_button_row,
+            rules_button_row,
+            view_options_row,
+            save_action,
+            remove_action,
+            insert_transaction_action,
+            action_group,
             currency_row,
+            opening_balance_spin_row,
+            opening_date_picker,
+            balance_checks,
+            reconcile,
             transactions_list_box,
+            settings,
             scrollable_pane,
 
             state: RefCell::new(State::default()),
         });
-        this.connect_insert_transaction_row_event();
+        this.connect_insert_transaction_action();
+        this.connect_rules_row_event();
+        this.connect_view_options_changed();
         this.connect_entries_change_event();
         this.connect_save_event();
         this.connect_remove_event();
+        this.connect_balance_checks_events();
+        this.connect_reconcile_finished();
         this
     }
 
@@ -87,7 +141,7 @@ impl WalletPage {
 
     fn build_name_entry_row() -> adw::EntryRow {
         let entry_row = adw::EntryRow::new();
-        entry_row.set_title("Name");
+        entry_row.set_title(&gettext("Name"));
         entry_row
     }
 
@@ -95,10 +149,26 @@ impl WalletPage {
         let entry_row = adw::EntryRow::new();
         entry_row.set_enable_emoji_completion(true);
         entry_row.set_show_apply_button(false);
-        entry_row.set_title("Description");
+        entry_row.set_title(&gettext("Description"));
         entry_row
     }
 
+    fn build_opening_balance_spin_row() -> adw::SpinRow {
+        let adjustment = gtk::Adjustment::new(0.0, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Opening Balance"));
+        spin_row
+    }
+
+    fn build_opening_date_row(date_picker: &Rc<DatePicker>) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("Opening Date"));
+        action_row.set_subtitle(&gettext("Transactions up to this date are part of the opening balance"));
+        action_row.add_suffix(date_picker.widget());
+        action_row
+    }
+
     fn build_button_row(title: &str, icon: &str, button_type: ButtonType) -> adw::ButtonRow {
         let button_row = adw::ButtonRow::new();
         button_row.set_title(title);
@@ -111,17 +181,32 @@ impl WalletPage {
         button_row
     }
 
-    fn create_transaction_rows(&self, wallet: &Wallet) {
-        let mut wallet = wallet.clone();
-        wallet.sort_transactions_by_amount();
-        let rows = wallet.transactions
-            .iter()
-            .map(|t| {
-                let transaction_row = transaction_row::TransactionRow::new(t, &wallet);
+    fn build_section_row(title: &str) -> gtk::ListBoxRow {
+        let label = gtk::Label::new(Some(title));
+        label.set_halign(gtk::Align::Start);
+        label.add_css_class("heading");
+        label.set_margin_top(8);
+        label.set_margin_bottom(8);
+        label.set_margin_start(12);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_activatable(false);
+        row.set_child(Some(&label));
+        row
+    }
+
+    fn create_transaction_rows(&self, wallet: &Wallet, view_options: ViewOptions) {
+        let mut rows = Vec::new();
+        for (title, transactions) in view_options.arrange(wallet.transactions.iter()) {
+            if let Some(title) = title {
+                self.transactions_list_box.append(&Self::build_section_row(&title));
+            }
+            for transaction in transactions {
+                let transaction_row = transaction_row::TransactionRow::new(transaction, wallet);
                 self.transactions_list_box.append(transaction_row.widget());
-                transaction_row
-            })
-            .collect::<Vec<_>>();
+                rows.push(transaction_row);
+            }
+        }
 
         self.state.borrow_mut().rows = rows;
     }
@@ -131,14 +216,14 @@ impl WalletPage {
         self.state.borrow_mut().rows.clear();
     }
 
-    fn set_data(&self, data: &Wallet) {
+    fn set_data(&self, data: &Wallet, view_options: ViewOptions) {
         if let Some(description) = &data.description {
             self.description_entry_row.set_text(description);
         }
 
         let scroll_page_children = self.scrollable_pane.get_children();
         for (index, child) in scroll_page_children.iter().enumerate() {
-            if !data.is_created() && [3, 4, 5, 6, 8].contains(&index) {
+            if !data.is_created() && [3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 14].contains(&index) {
                 child.set_visible(false);
             } else if data.transactions.is_empty() && index == 5 {
                 child.set_visible(false);
@@ -148,12 +233,26 @@ impl WalletPage {
         }
 
         if !data.transactions.is_empty() {
-            self.create_transaction_rows(&data);
+            self.create_transaction_rows(&data, view_options);
         }
+        self.view_options_row.set_options(view_options);
 
         self.name_entry_row.set_text(&data.name);
         self.currency_row.set_activated(data.currency);
-        self.save_button_row.set_sensitive(false);
+        self.opening_balance_spin_row.set_value(data.opening_balance);
+        match data.opening_date {
+            Some(date) => self.opening_date_picker.set_date(date),
+            None => self.opening_date_picker.set_default_date(),
+        }
+        self.reconcile.set_wallet(data);
+        self.save_action.set_enabled(false);
+        self.remove_action.set_enabled(data.is_created());
+        self.insert_transaction_action.set_enabled(data.is_created());
+    }
+
+    fn render_balance_checks(&self) {
+        let wallet = self.get_data();
+        self.balance_checks.set_discrepancies(&wallet.check_assertions(), wallet.currency);
     }
 
     fn get_data(&self) -> Wallet {
@@ -161,11 +260,17 @@ impl WalletPage {
         let description_text = self.description_entry_row.text().to_string();
         let description = if description_text.is_empty() { None } else { Some(description_text) };
         let currency = self.currency_row.get_activated();
+        let opening_balance = self.opening_balance_spin_row.value();
+        let opening_date = if opening_balance == 0.0 { None } else { Some(self.opening_date_picker.get_date()) };
+        let state = self.state.borrow();
         Wallet {
             name,
             description,
             currency,
-            ..self.state.borrow().wallet.clone()
+            opening_balance,
+            opening_date,
+            assertions: state.assertions.clone(),
+            ..state.wallet.clone()
         }
     }
 
@@ -173,6 +278,10 @@ impl WalletPage {
         self.name_entry_row.set_text("");
         self.description_entry_row.set_text("");
         self.currency_row.set_activated(Currency::default());
+        self.opening_balance_spin_row.set_value(0.0);
+        self.opening_date_picker.set_default_date();
+        self.balance_checks.clear();
+        self.reconcile.clear();
         self.remove_transaction_rows();
     }
 
@@ -180,9 +289,9 @@ impl WalletPage {
         let previous_data = &self.state.borrow().wallet;
         let data = self.get_data();
         if data.is_valid() && data.is_different(&previous_data) {
-            self.save_button_row.set_sensitive(true);
+            self.save_action.set_enabled(true);
         } else {
-            self.save_button_row.set_sensitive(false);
+            self.save_action.set_enabled(false);
         }
     }
 
@@ -201,11 +310,43 @@ impl WalletPage {
         self.currency_row.connect_activate_event(move |_| {
             this_clone.handle_changes();
         });
+
+        let this_clone = self.clone();
+        self.opening_balance_spin_row.connect_value_notify(move |_| {
+            this_clone.render_balance_checks();
+            this_clone.handle_changes();
+        });
+
+        let this_clone = self.clone();
+        self.opening_date_picker.connect_changed(move |_, _| {
+            this_clone.render_balance_checks();
+            this_clone.handle_changes();
+        });
+    }
+
+    fn update_assertions(&self, update: impl FnOnce(&mut Wallet)) {
+        let mut wallet = self.get_data();
+        update(&mut wallet);
+        self.state.borrow_mut().assertions = wallet.assertions;
+        self.render_balance_checks();
+        self.handle_changes();
     }
 
-    fn connect_insert_transaction_row_event(self: &Rc<Self>) {
+    fn connect_balance_checks_events(self: &Rc<Self>) {
         let this = self.clone();
-        self.insert_transaction_button_row.connect_activated(move |_| {
+        self.balance_checks.connect_added(move |assertion| {
+            this.update_assertions(|wallet| wallet.add_or_update_assertion(assertion));
+        });
+
+        let this = self.clone();
+        self.balance_checks.connect_removed(move |date: NaiveDate| {
+            this.update_assertions(|wallet| wallet.remove_assertion_by_date(date));
+        });
+    }
+
+    fn connect_insert_transaction_action(self: &Rc<Self>) {
+        let this = self.clone();
+        self.insert_transaction_action.connect_activate(move |_, _| {
             let state = this.state.borrow();
             let context = state.context.clone();
             let wallet_id = state.wallet.id;
@@ -217,6 +358,35 @@ impl WalletPage {
         });
     }
 
+    fn connect_view_options_changed(self: &Rc<Self>) {
+        let this = Rc::downgrade(self);
+        self.view_options_row.connect_changed(move |view_options| {
+            let Some(this) = this.upgrade() else { return };
+            let context = this.state.borrow().context.clone();
+            let mut views = Preferences::current().views;
+            views.wallet = view_options;
+            this.settings.set_views(views);
+
+            context
+                .with_navigation_action(NavigationAction::NavigateToCurrent)
+                .propagate();
+        });
+    }
+
+    fn connect_rules_row_event(self: &Rc<Self>) {
+        let this = self.clone();
+        self.rules_button_row.connect_activated(move |_| {
+            let state = this.state.borrow();
+            let context = state.context.clone();
+            let wallet_id = state.wallet.id;
+            drop(state);
+
+            context
+                .with_navigation_action(NavigationAction::navigate_to_rules(Some(wallet_id)))
+                .propagate();
+        });
+    }
+
     fn connect_transaction_row_activate_event(self: &Rc<Self>) {
         for transaction_row in self.state.borrow().rows.iter() {
             let this = self.clone();
@@ -235,7 +405,7 @@ impl WalletPage {
 
     fn connect_save_event(self: &Rc<Self>) {
         let this = self.clone();
-        self.save_button_row.connect_activated(move |_| {
+        self.save_action.connect_activate(move |_, _| {
             let context = this.state.borrow().context.clone();
             let mut data = context.data().clone();
             let wallet = this.get_data().assign_global_id();
@@ -244,7 +414,7 @@ impl WalletPage {
             
             if data.wallets.iter().any(|w| w.id != wallet_id && w.name.to_lowercase() == wallet_name_lowercase) {
                 return context
-                    .with_ui_action(UiAction::push_notification("Wallet name already exists"))
+                    .with_ui_action(UiAction::push_notification(gettext("Wallet name already exists")))
                     .propagate();
             }
 
@@ -253,11 +423,27 @@ impl WalletPage {
             context
                 .with_data(data)
                 .with_navigation_action(NavigationAction::navigate_to_wallet(wallet_id))
-                .with_ui_action(UiAction::push_notification("Wallet saved"))
+                .with_ui_action(UiAction::push_notification(gettext("Wallet saved")))
                 .propagate()
         });
     }
 
+    fn connect_reconcile_finished(self: &Rc<Self>) {
+        let this = self.clone();
+        self.reconcile.connect_finished(move |wallet| {
+            let context = this.state.borrow().context.clone();
+            let wallet_id = wallet.id;
+            let mut data = context.data().clone();
+            data.add_or_update_wallet(wallet);
+
+            context
+                .with_data(data)
+                .with_navigation_action(NavigationAction::navigate_to_wallet(wallet_id))
+                .with_ui_action(UiAction::push_notification(gettext("Wallet reconciled")))
+                .propagate();
+        });
+    }
+
     fn handle_remove_confirmation(self: &Rc<Self>) {
         let state = self.state.borrow();
         let context = state.context.clone();
@@ -269,19 +455,19 @@ impl WalletPage {
 
         context
             .with_data(data)
-            .with_ui_action(UiAction::push_notification("Wallet removed"))
+            .with_ui_action(UiAction::push_notification(gettext("Wallet removed")))
             .with_navigation_action(NavigationAction::NavigateToPrevious)
             .propagate();
     }
 
     fn connect_remove_event(self: &Rc<Self>) {
         let this = self.clone();
-        self.remove_button_row.connect_activated(move |_| {
+        self.remove_action.connect_activate(move |_, _| {
             let context = this.state.borrow().context.clone();
             let this_clone = this.clone();
             context.with_ui_action(UiAction::OpenAlertDialog {
-                title: "Remove wallet".into(),
-                message: "Are you sure you want to remove this wallet?".into(),
+                title: gettext("Remove wallet"),
+                message: gettext("Are you sure you want to remove this wallet?"),
                 buttons: vec![
                     AlertButton::cancel(),
                     AlertButton::remove().destructive(),
@@ -315,25 +501,26 @@ impl LifeCycle<NavigationAction> for Rc<WalletPage> {
         };
 
         let wallet = match wallet_id {
-            Some(id) => context
-                .data()
-                .find_wallet_by_id(id)
-                .cloned()
-                .unwrap(),
-            None => {
-                let mut w = Wallet::default();
-                w.currency = context.data().currency;
-                w
+            Some(id) => {
+                let Some(wallet) = context.data().find_wallet_by_id(id).cloned() else { return };
+                wallet
+            },
+            None => Wallet {
+                currency: Preferences::current().default_currency,
+                ..Wallet::default()
             },
         };
 
-        self.set_data(&wallet);
+        self.set_data(&wallet, Preferences::current().views.wallet);
         self.connect_transaction_row_activate_event();
         *self.state.borrow_mut() = State {
             context: context.clone(),
             rows: Vec::new(),
+            assertions: wallet.assertions.clone(),
             wallet
         };
+        self.render_balance_checks();
+        self.handle_changes();
     }
 
     fn deactivate(&self) {
@@ -344,7 +531,11 @@ impl LifeCycle<NavigationAction> for Rc<WalletPage> {
 }
 
 impl PageContent for Rc<WalletPage> {
-    fn title(&self) -> &str {
-        "Wallet"
+    fn title(&self) -> String {
+        gettext("Wallet")
+    }
+
+    fn action_group(&self) -> Option<&gio::SimpleActionGroup> {
+        Some(&self.action_group)
     }
 }
\ No newline at end of file
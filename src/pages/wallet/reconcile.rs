@@ -0,0 +1,327 @@
+use crate::prelude::*;
+use crate::data::*;
+use crate::utils::{DatePicker, LocaleSpinExtension};
+
+use gtk::glib::clone::Downgrade;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type FinishedCallback = RefCell<Option<Box<dyn Fn(Wallet)>>>;
+
+#[derive(Default)]
+struct State {
+    wallet: Wallet,
+    reconciliation: Option<Reconciliation>,
+}
+
+pub struct Reconcile {
+    container: gtk::Box,
+    date_picker: Rc<DatePicker>,
+    balance_spin_row: adw::SpinRow,
+    start_button_row: adw::ButtonRow,
+    mode_box: gtk::Box,
+    occurrences_list_box: gtk::ListBox,
+    cleared_balance_label: gtk::Label,
+    difference_label: gtk::Label,
+    finish_button_row: adw::ButtonRow,
+    cancel_button_row: adw::ButtonRow,
+
+    state: RefCell<State>,
+    callback: FinishedCallback,
+}
+
+impl Reconcile {
+
+    pub fn new() -> Rc<Self> {
+        let date_picker = DatePicker::new();
+        let balance_spin_row = Self::build_balance_spin_row();
+        let start_button_row = Self::build_button_row(&gettext("Reconcile"), "object-select-symbolic");
+        let occurrences_list_box = Self::build_list_box(Some(&gettext("No occurrences to reconcile")));
+        let (cleared_balance_row, cleared_balance_label) = Self::build_label_row(&gettext("Cleared Balance"));
+        let (difference_row, difference_label) = Self::build_label_row(&gettext("Difference"));
+        let finish_button_row = Self::build_button_row(&gettext("Finish Reconciliation"), "emblem-ok-symbolic");
+        finish_button_row.add_css_class("suggested-action");
+        let cancel_button_row = Self::build_button_row(&gettext("Cancel"), "window-close-symbolic");
+
+        let form_list_box = Self::build_list_box(None);
+        form_list_box.append(&Self::build_date_row(&date_picker));
+        form_list_box.append(&balance_spin_row);
+        form_list_box.append(&start_button_row);
+
+        let summary_list_box = Self::build_list_box(None);
+        summary_list_box.append(&cleared_balance_row);
+        summary_list_box.append(&difference_row);
+        summary_list_box.append(&finish_button_row);
+        summary_list_box.append(&cancel_button_row);
+
+        let mode_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        mode_box.append(&occurrences_list_box);
+        mode_box.append(&summary_list_box);
+        mode_box.set_visible(false);
+
+        let container = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        container.append(&form_list_box);
+        container.append(&mode_box);
+
+        let this = Rc::new(Self {
+            container,
+            date_picker,
+            balance_spin_row,
+            start_button_row,
+            mode_box,
+            occurrences_list_box,
+            cleared_balance_label,
+            difference_label,
+            finish_button_row,
+            cancel_button_row,
+
+            state: RefCell::new(State::default()),
+            callback: RefCell::new(None),
+        });
+        this.connect_start_button_activated();
+        this.connect_statement_changed();
+        this.connect_finish_button_activated();
+        this.connect_cancel_button_activated();
+        this
+    }
+
+    fn build_list_box(placeholder_text: Option<&str>) -> gtk::ListBox {
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::None);
+        list_box.add_css_class("boxed-list");
+        list_box.set_focusable(false);
+        if let Some(text) = placeholder_text {
+            let placeholder = gtk::Label::new(Some(text));
+            placeholder.add_css_class("dim-label");
+            placeholder.set_margin_top(12);
+            placeholder.set_margin_bottom(12);
+            list_box.set_placeholder(Some(&placeholder));
+        }
+        list_box
+    }
+
+    fn build_date_row(date_picker: &Rc<DatePicker>) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("Statement End Date"));
+        action_row.add_suffix(date_picker.widget());
+        action_row
+    }
+
+    fn build_balance_spin_row() -> adw::SpinRow {
+        let adjustment = gtk::Adjustment::new(0.0, i32::MIN as f64, u32::MAX as f64, 1.0, 10.0, 0.0);
+        let spin_row = adw::SpinRow::new(Some(&adjustment), 10.0, 2);
+        spin_row.set_locale_format();
+        spin_row.set_title(&gettext("Statement Balance"));
+        spin_row
+    }
+
+    fn build_button_row(title: &str, icon: &str) -> adw::ButtonRow {
+        let button_row = adw::ButtonRow::new();
+        button_row.set_title(title);
+        button_row.set_start_icon_name(Some(icon));
+        button_row
+    }
+
+    fn build_label_row(title: &str) -> (adw::ActionRow, gtk::Label) {
+        let label = gtk::Label::new(None);
+        label.add_css_class("numeric");
+        label.set_valign(gtk::Align::Center);
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(title);
+        action_row.add_suffix(&label);
+        (action_row, label)
+    }
+
+    fn build_occurrence_row(self: &Rc<Self>, occurrence: &Occurrence) -> adw::ActionRow {
+        let wallet = &self.state.borrow().wallet;
+        let title = wallet
+            .find_transaction_by_id(occurrence.transaction_id)
+            .map(|t| t.name.clone())
+            .unwrap_or_default();
+
+        let check_button = gtk::CheckButton::new();
+        check_button.set_valign(gtk::Align::Center);
+        check_button.set_active(occurrence.status.is_cleared());
+
+        let pending_button = gtk::ToggleButton::new();
+        pending_button.set_icon_name("content-loading-symbolic");
+        pending_button.set_tooltip_text(Some(&gettext("Pending")));
+        pending_button.set_valign(gtk::Align::Center);
+        pending_button.add_css_class("flat");
+        pending_button.set_active(occurrence.status == OccurrenceStatus::Pending);
+
+        let amount_label = gtk::Label::new(Some(&wallet.currency.format_amount(occurrence.amount)));
+        amount_label.add_css_class("numeric");
+        amount_label.add_css_class(if occurrence.amount < 0.0 { "error" } else { "success" });
+
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&title);
+        action_row.set_subtitle(&Preferences::current().locale.format_date(occurrence.date));
+        action_row.add_prefix(&check_button);
+        action_row.add_suffix(&amount_label);
+        action_row.add_suffix(&pending_button);
+        action_row.set_activatable_widget(Some(&check_button));
+
+        let this = self.downgrade();
+        let occurrence_clone = occurrence.clone();
+        let pending_button_weak = ObjectExt::downgrade(&pending_button);
+        check_button.connect_toggled(move |check_button| {
+            let Some(this) = this.upgrade() else { return };
+            let Some(pending_button_clone) = pending_button_weak.upgrade() else { return };
+            let status = if check_button.is_active() {
+                pending_button_clone.set_active(false);
+                OccurrenceStatus::Cleared
+            } else if pending_button_clone.is_active() {
+                OccurrenceStatus::Pending
+            } else {
+                OccurrenceStatus::Scheduled
+            };
+            this.set_occurrence_status(&occurrence_clone, status);
+        });
+
+        let this = self.downgrade();
+        let occurrence_clone = occurrence.clone();
+        let check_button_weak = ObjectExt::downgrade(&check_button);
+        pending_button.connect_toggled(move |pending_button| {
+            let Some(this) = this.upgrade() else { return };
+            let Some(check_button) = check_button_weak.upgrade() else { return };
+            if pending_button.is_active() {
+                check_button.set_active(false);
+                this.set_occurrence_status(&occurrence_clone, OccurrenceStatus::Pending);
+            } else if !check_button.is_active() {
+                this.set_occurrence_status(&occurrence_clone, OccurrenceStatus::Scheduled);
+            }
+        });
+
+        action_row
+    }
+
+    fn set_occurrence_status(&self, occurrence: &Occurrence, status: OccurrenceStatus) {
+        if let Some(reconciliation) = self.state.borrow_mut().reconciliation.as_mut() {
+            reconciliation.set_status(occurrence, status);
+        }
+        self.update_summary();
+    }
+
+    fn update_summary(&self) {
+        let state = self.state.borrow();
+        let Some(reconciliation) = state.reconciliation.as_ref() else { return };
+        let currency = reconciliation.wallet.currency;
+
+        self.cleared_balance_label.set_text(&currency.format_amount(reconciliation.cleared_balance()));
+        self.difference_label.set_text(&currency.format_amount(reconciliation.difference()));
+        if reconciliation.is_balanced() {
+            self.difference_label.remove_css_class("error");
+            self.difference_label.add_css_class("success");
+        } else {
+            self.difference_label.remove_css_class("success");
+            self.difference_label.add_css_class("error");
+        }
+        self.finish_button_row.set_sensitive(reconciliation.is_balanced());
+    }
+
+    fn start(self: &Rc<Self>) {
+        let reconciliation = Reconciliation::new(
+            self.state.borrow().wallet.clone(),
+            self.date_picker.get_date(),
+            self.balance_spin_row.value(),
+        );
+        let occurrences = reconciliation.occurrences();
+        self.state.borrow_mut().reconciliation = Some(reconciliation);
+
+        self.occurrences_list_box.remove_all();
+        for occurrence in occurrences.iter() {
+            self.occurrences_list_box.append(&self.build_occurrence_row(occurrence));
+        }
+
+        self.mode_box.set_visible(true);
+        self.start_button_row.set_visible(false);
+        self.update_summary();
+    }
+
+    fn stop(&self) {
+        self.state.borrow_mut().reconciliation = None;
+        self.occurrences_list_box.remove_all();
+        self.mode_box.set_visible(false);
+        self.start_button_row.set_visible(true);
+    }
+
+    fn connect_start_button_activated(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.start_button_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.start();
+        });
+    }
+
+    fn connect_statement_changed(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.balance_spin_row.connect_value_notify(move |spin_row| {
+            let Some(this) = this.upgrade() else { return };
+            if let Some(reconciliation) = this.state.borrow_mut().reconciliation.as_mut() {
+                reconciliation.statement_balance = spin_row.value();
+            }
+            this.update_summary();
+        });
+
+        let this = self.downgrade();
+        self.date_picker.connect_changed(move |_, _| {
+            let Some(this) = this.upgrade() else { return };
+            if this.state.borrow().reconciliation.is_some() {
+                this.start();
+            }
+        });
+    }
+
+    fn connect_finish_button_activated(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.finish_button_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            if !this.state.borrow().reconciliation.as_ref().is_some_and(|r| r.is_balanced()) {
+                return;
+            }
+            let Some(reconciliation) = this.state.borrow_mut().reconciliation.take() else { return };
+
+            let wallet = reconciliation.finish();
+            this.stop();
+            let callback = this.callback.borrow();
+            if let Some(callback) = callback.as_ref() {
+                callback(wallet);
+            }
+        });
+    }
+
+    fn connect_cancel_button_activated(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.cancel_button_row.connect_activated(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.stop();
+        });
+    }
+
+    pub fn set_wallet(&self, wallet: &Wallet) {
+        self.stop();
+        self.state.borrow_mut().wallet = wallet.clone();
+        self.date_picker.set_default_date();
+        self.balance_spin_row.set_value(wallet.balance_at(self.date_picker.get_date()));
+    }
+
+    pub fn clear(&self) {
+        self.stop();
+        self.state.replace(State::default());
+        self.date_picker.set_default_date();
+        self.balance_spin_row.set_value(0.0);
+    }
+
+    pub fn connect_finished(&self, callback: impl Fn(Wallet) + 'static) {
+        self.callback.replace(Some(Box::new(callback)));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<Reconcile> {
+    fn widget(&self) -> &gtk::Widget {
+        self.container.upcast_ref()
+    }
+}
@@ -3,6 +3,7 @@ use crate::context::{Context, NavigationAction};
 pub use adw::prelude::*;
 pub use std::error::Error;
 pub use crate::context::Action;
+pub use crate::i18n::{gettext, gettext_f, ngettext_f};
 
 pub trait HasWidget<W: IsA<gtk::Widget> + Sized> {
     fn widget(&self) -> &W;
@@ -22,7 +23,12 @@ pub trait LifeCycle<A: Action> {
 }
 
 pub trait PageContent: LifeCycle<NavigationAction> + HasWidget<gtk::Widget> {
-    fn title(&self) -> &str;
+    fn title(&self) -> String;
+
+    /// Page actions (see `Shortcut`) handled while this page is visible.
+    fn action_group(&self) -> Option<&gtk::gio::SimpleActionGroup> {
+        None
+    }
 }
 
 pub trait Propagator<A: Action> {
@@ -0,0 +1,197 @@
+use crate::context::{DataDirectory, DataFile};
+use crate::data::*;
+use crate::prelude::*;
+
+use chrono::{Local, NaiveDate};
+use gtk::gio;
+use gtk::glib::{self, Variant};
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+const CHECK_INTERVAL_SECONDS: u32 = 60 * 60;
+
+pub const OPEN_ACTION: &str = "open-occurrence";
+pub const PAY_ACTION: &str = "pay-occurrence";
+
+/// The occurrence a notification is about, found again by the ids of its
+/// wallet and transaction in the document at `path`.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub path: PathBuf,
+    pub wallet: usize,
+    pub transaction: usize,
+    pub date: NaiveDate,
+}
+
+impl Target {
+
+    pub fn new(path: &Path, wallet: &Wallet, transaction: &Transaction, date: NaiveDate) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            wallet: wallet.id,
+            transaction: transaction.id,
+            date,
+        }
+    }
+
+    pub fn variant_type() -> &'static glib::VariantTy {
+        glib::VariantTy::new("(stts)").expect("Invalid variant type")
+    }
+
+    pub fn from_variant(variant: Option<&Variant>) -> Option<Self> {
+        let (path, wallet, transaction, date) = variant?.get::<(String, u64, u64, String)>()?;
+        let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?;
+        Some(Self {
+            path: PathBuf::from(path),
+            wallet: wallet as usize,
+            transaction: transaction as usize,
+            date
+        })
+    }
+
+    pub fn to_variant(&self) -> Variant {
+        (
+            self.path.to_string_lossy().into_owned(),
+            self.wallet as u64,
+            self.transaction as u64,
+            self.date.format("%Y-%m-%d").to_string()
+        ).to_variant()
+    }
+
+    pub fn notification_id(&self) -> String {
+        format!("{}/{}/{}/{}", self.path.display(), self.wallet, self.transaction, self.date.format("%Y-%m-%d"))
+    }
+
+    pub fn file(&self) -> DataFile {
+        DataFile::new(self.path.clone())
+    }
+
+    pub fn exists_in(&self, data: &Data) -> bool {
+        data.find_wallet_by_id(self.wallet)
+            .and_then(|wallet| wallet.find_transaction_by_id(self.transaction))
+            .is_some()
+    }
+
+    pub fn mark_paid(&self, data: &mut Data) -> bool {
+        let Some(mut wallet) = data.find_wallet_by_id(self.wallet).cloned() else { return false };
+        let Some(mut transaction) = wallet.find_transaction_by_id(self.transaction).cloned() else { return false };
+
+        transaction.mark_paid(self.date);
+        wallet.add_or_update_transaction(transaction);
+        data.add_or_update_wallet(wallet);
+        true
+    }
+
+}
+
+#[derive(Clone)]
+pub struct Reminders {
+    application: adw::Application,
+    directory: DataDirectory,
+    sent: Rc<RefCell<HashSet<String>>>,
+}
+
+impl Reminders {
+
+    pub fn new(application: &adw::Application, directory: DataDirectory) -> Self {
+        Self {
+            application: application.clone(),
+            directory,
+            sent: Rc::new(RefCell::new(HashSet::new())),
+        }
+    }
+
+    /// Every document of the data directory that can be read, leaving out
+    /// the encrypted ones, whose passphrase is not known here.
+    fn load_documents(directory: &DataDirectory) -> Vec<(PathBuf, Data)> {
+        directory
+            .load_valid()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(file, data)| Some((file.path, data?)))
+            .collect()
+    }
+
+    fn format_title(name: &str, date: NaiveDate) -> String {
+        let days = (date - Local::now().naive_local().date()).num_days();
+        match days {
+            0 => gettext_f("{name} is due today", &[("name", name)]),
+            1 => gettext_f("{name} is due tomorrow", &[("name", name)]),
+            _ => gettext_f("{name} is due on {date}", &[
+                ("name", name),
+                ("date", &Preferences::current().locale.format_date(date))
+            ]),
+        }
+    }
+
+    fn build_notification(target: &Target, occurrence: &Occurrence, wallet: &Wallet, transaction: &Transaction) -> gio::Notification {
+        let target_variant = target.to_variant();
+        let notification = gio::Notification::new(&Self::format_title(&transaction.name, occurrence.date));
+        notification.set_body(Some(&format!(
+            "{} · {}",
+            occurrence.currency.format_amount(occurrence.amount),
+            wallet.name
+        )));
+        notification.set_default_action_and_target_value(
+            &format!("app.{}", OPEN_ACTION),
+            Some(&target_variant)
+        );
+        notification.add_button_with_target_value(
+            &gettext("Open"),
+            &format!("app.{}", OPEN_ACTION),
+            Some(&target_variant)
+        );
+        notification.add_button_with_target_value(
+            &gettext("Mark as Paid"),
+            &format!("app.{}", PAY_ACTION),
+            Some(&target_variant)
+        );
+        notification
+    }
+
+    pub fn notify_due(&self, path: &Path, data: &Data) {
+        let mut sent = self.sent.borrow_mut();
+        for occurrence in data.due_occurrences() {
+            let Some(wallet) = data.find_wallet_by_id(occurrence.wallet_id) else { continue };
+            let Some(transaction) = wallet.find_transaction_by_id(occurrence.transaction_id) else { continue };
+            let target = Target::new(path, wallet, transaction, occurrence.date);
+            let notification_id = target.notification_id();
+            if sent.contains(&notification_id) {
+                continue;
+            }
+
+            let notification = Self::build_notification(&target, &occurrence, wallet, transaction);
+            self.application.send_notification(Some(&notification_id), &notification);
+            sent.insert(notification_id);
+        }
+    }
+
+    pub fn withdraw(&self, target: &Target) {
+        self.application.withdraw_notification(&target.notification_id());
+    }
+
+    /// Reads the documents on a worker thread, then notifies about what is due in each.
+    pub fn check(&self) {
+        let directory = self.directory.clone();
+        let this = self.clone();
+        glib::spawn_future_local(async move {
+            let documents = gio::spawn_blocking(move || Self::load_documents(&directory)).await;
+            for (path, data) in documents.unwrap_or_default() {
+                this.notify_due(&path, &data);
+            }
+        });
+    }
+
+    pub fn start(&self) {
+        self.check();
+        let this = self.clone();
+        glib::timeout_add_seconds_local(CHECK_INTERVAL_SECONDS, move || {
+            this.check();
+            glib::ControlFlow::Continue
+        });
+    }
+
+}
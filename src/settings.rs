@@ -0,0 +1,529 @@
+use crate::context::{Backup, BackupPolicy};
+use crate::data::{Currency, DateFormat, GroupBy, Locale, NegativeStyle, NumberFormat, Preferences, RateProvider, SortKey, SymbolPosition, ViewOptions, ViewSettings};
+use crate::i18n::gettext_noop;
+use crate::metadata;
+use crate::pages::AutoLock;
+use crate::prelude::*;
+
+use chrono::Weekday;
+use gtk::{gio, glib};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+
+const COLOR_SCHEME: &str = "color-scheme";
+const DEFAULT_CURRENCY: &str = "default-currency";
+const FIRST_WEEKDAY: &str = "first-weekday";
+const NUMBER_FORMAT: &str = "number-format";
+const SYMBOL_POSITION: &str = "symbol-position";
+const NEGATIVE_STYLE: &str = "negative-style";
+const DATE_FORMAT: &str = "date-format";
+const BACKUP_POLICY: &str = "backup-policy";
+const BACKUP_COUNT: &str = "backup-count";
+const RATE_PROVIDER: &str = "rate-provider";
+const NOTIFICATION_TIMEOUT: &str = "notification-timeout";
+const AUTO_LOCK_MINUTES: &str = "auto-lock-minutes";
+const WALLET_VIEW: &str = "wallet-view";
+const OVERVIEW_VIEW: &str = "overview-view";
+const WINDOW_STATES: &str = "window-states";
+
+/// Keys read by `apply`, the only ones whose changes are pushed to the rest of the application.
+const APPLIED_KEYS: [&str; 14] = [
+    COLOR_SCHEME,
+    DEFAULT_CURRENCY,
+    FIRST_WEEKDAY,
+    NUMBER_FORMAT,
+    SYMBOL_POSITION,
+    NEGATIVE_STYLE,
+    DATE_FORMAT,
+    BACKUP_POLICY,
+    BACKUP_COUNT,
+    RATE_PROVIDER,
+    NOTIFICATION_TIMEOUT,
+    AUTO_LOCK_MINUTES,
+    WALLET_VIEW,
+    OVERVIEW_VIEW,
+];
+
+/// The schema the defaults are read from when it is not installed.
+const SCHEMA_TEMPLATE: &str = include_str!("../resources/templates/gschema.xml.template");
+
+/// Width, height and maximized state of a window.
+type WindowState = (i32, i32, bool);
+
+const DEFAULT_WINDOW_STATE: WindowState = (800, 600, false);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    System,
+    Light,
+    #[default]
+    Dark,
+}
+
+impl ColorScheme {
+
+    const SCHEMES: [(&'static str, &'static str); 3] = [
+        ("default", gettext_noop("Follow System")),
+        ("light", gettext_noop("Light")),
+        ("dark", gettext_noop("Dark")),
+    ];
+
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        Self::SCHEMES[*self as usize].1
+    }
+
+    #[inline]
+    pub const fn as_slice() -> [Self; 3] {
+        [
+            Self::System,
+            Self::Light,
+            Self::Dark
+        ]
+    }
+
+    const fn as_adw(&self) -> adw::ColorScheme {
+        match self {
+            Self::System => adw::ColorScheme::Default,
+            Self::Light => adw::ColorScheme::ForceLight,
+            Self::Dark => adw::ColorScheme::PreferDark,
+        }
+    }
+
+}
+
+/// Maps a settings value to one of the variants of an enum, using the
+/// position of the value in `keys` as the index into `variants`.
+fn from_optional_key<T: Copy>(keys: &[&str], variants: &[T], key: &str) -> Option<T> {
+    keys.iter()
+        .position(|k| *k == key)
+        .and_then(|index| variants.get(index).copied())
+}
+
+fn from_key<T: Copy + Default>(keys: &[&str], variants: &[T], key: &str) -> T {
+    from_optional_key(keys, variants, key).unwrap_or_default()
+}
+
+fn to_key<T: PartialEq>(keys: &[&'static str], variants: &[T], value: &T) -> &'static str {
+    variants
+        .iter()
+        .position(|v| v == value)
+        .and_then(|index| keys.get(index).copied())
+        .unwrap_or_default()
+}
+
+const WEEKDAY_KEYS: [&str; 2] = ["monday", "sunday"];
+const WEEKDAYS: [Weekday; 2] = [Weekday::Mon, Weekday::Sun];
+const SYSTEM_KEY: &str = "system";
+const NUMBER_FORMAT_KEYS: [&str; 4] = ["space-dot", "comma-dot", "dot-comma", "space-comma"];
+const SYMBOL_POSITION_KEYS: [&str; 2] = ["before", "after"];
+const NEGATIVE_STYLE_KEYS: [&str; 2] = ["minus", "parentheses"];
+const DATE_FORMAT_KEYS: [&str; 5] = ["iso", "dmy-dot", "dmy-slash", "dmy-dash", "mdy-slash"];
+const BACKUP_POLICY_KEYS: [&str; 3] = ["never", "on-save", "daily"];
+const RATE_PROVIDER_KEYS: [&str; 2] = ["frankfurter", "open-er-api"];
+const SORT_KEY_KEYS: [&str; 5] = ["amount", "date", "name", "cycle", "next-occurrence"];
+const GROUP_BY_KEYS: [&str; 4] = ["none", "month", "cycle", "direction"];
+
+type ChangedCallback = Rc<dyn Fn(&str)>;
+
+/// Values kept for the running application only, used when the schema
+/// is not installed so it still starts with the defaults of the schema.
+#[derive(Default)]
+struct MemoryStore {
+    values: RefCell<HashMap<String, glib::Variant>>,
+    callbacks: RefCell<Vec<ChangedCallback>>,
+}
+
+impl MemoryStore {
+
+    fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+        let (_, rest) = attributes.split_once(&format!("{}=\"", name))?;
+        rest.split_once('"').map(|(value, _)| value)
+    }
+
+    /// Reads the default of each key from the schema template.
+    fn from_schema(template: &str) -> Self {
+        let mut values = HashMap::new();
+        let mut key = None;
+        for line in template.lines().map(str::trim) {
+            if let Some(attributes) = line.strip_prefix("<key ") {
+                key = Self::attribute(attributes, "name").zip(Self::attribute(attributes, "type"));
+            }
+            let Some(default) = line.strip_prefix("<default>").and_then(|l| l.strip_suffix("</default>")) else { continue };
+            let Some((name, type_string)) = key.take() else { continue };
+            let value_type = glib::VariantTy::new(type_string).ok();
+            if let Ok(value) = glib::Variant::parse(value_type, default) {
+                values.insert(name.to_owned(), value);
+            }
+        }
+        Self { values: RefCell::new(values), ..Self::default() }
+    }
+
+}
+
+/// Where the values are kept: the installed schema, or memory when it is missing.
+#[derive(Clone)]
+enum Store {
+    Schema(gio::Settings),
+    Memory(Rc<MemoryStore>),
+}
+
+impl Store {
+
+    fn value(&self, key: &str) -> glib::Variant {
+        match self {
+            Self::Schema(settings) => settings.value(key),
+            Self::Memory(store) => store.values
+                .borrow()
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| ().to_variant()),
+        }
+    }
+
+    fn set_value(&self, key: &str, value: &glib::Variant) -> Result<(), glib::BoolError> {
+        match self {
+            Self::Schema(settings) => settings.set_value(key, value),
+            Self::Memory(store) => {
+                let previous = store.values.borrow_mut().insert(key.to_owned(), value.clone());
+                if previous.as_ref() != Some(value) {
+                    let callbacks = store.callbacks.borrow().clone();
+                    callbacks.iter().for_each(|callback| callback(key));
+                }
+                Ok(())
+            },
+        }
+    }
+
+    fn string(&self, key: &str) -> String {
+        self.value(key).str().unwrap_or_default().to_owned()
+    }
+
+    fn set_string(&self, key: &str, value: &str) -> Result<(), glib::BoolError> {
+        self.set_value(key, &value.to_variant())
+    }
+
+    fn uint(&self, key: &str) -> u32 {
+        self.value(key).get().unwrap_or_default()
+    }
+
+    fn set_uint(&self, key: &str, value: u32) -> Result<(), glib::BoolError> {
+        self.set_value(key, &value.to_variant())
+    }
+
+    fn connect_changed(&self, callback: impl Fn(&str) + 'static) {
+        match self {
+            Self::Schema(settings) => {
+                settings.connect_changed(None, move |_, key| callback(key));
+            },
+            Self::Memory(store) => store.callbacks.borrow_mut().push(Rc::new(callback)),
+        }
+    }
+
+}
+
+#[derive(Clone)]
+pub struct Settings {
+    settings: Store,
+    window_slots: Rc<RefCell<BTreeSet<usize>>>,
+}
+
+impl Settings {
+
+    pub fn new() -> Self {
+        let settings = match Self::lookup_schema() {
+            Some(schema) => Store::Schema(gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None)),
+            None => {
+                glib::g_warning!(metadata::APP_NAME, "Settings schema {} not found, changes will not be kept", metadata::APP_ID);
+                Store::Memory(Rc::new(MemoryStore::from_schema(SCHEMA_TEMPLATE)))
+            },
+        };
+        Self {
+            settings,
+            window_slots: Rc::new(RefCell::new(BTreeSet::new())),
+        }
+    }
+
+    fn schema_directories() -> Vec<PathBuf> {
+        let installed = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent()?.parent().map(|prefix| prefix.to_path_buf()))
+            .map(|prefix| prefix.join("share").join(metadata::APP_NAME).join("schemas"));
+
+        installed
+            .into_iter()
+            .chain((!metadata::APP_SCHEMAS.is_empty()).then(|| PathBuf::from(metadata::APP_SCHEMAS)))
+            .collect()
+    }
+
+    fn lookup_schema() -> Option<gio::SettingsSchema> {
+        let default_source = gio::SettingsSchemaSource::default();
+        if let Some(schema) = default_source.as_ref().and_then(|s| s.lookup(metadata::APP_ID, true)) {
+            return Some(schema);
+        }
+
+        Self::schema_directories()
+            .into_iter()
+            .filter(|directory| directory.join("gschemas.compiled").exists())
+            .filter_map(|directory| gio::SettingsSchemaSource::from_directory(&directory, default_source.as_ref(), false).ok())
+            .find_map(|source| source.lookup(metadata::APP_ID, false))
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        let keys = ColorScheme::SCHEMES.map(|(key, _)| key);
+        from_key(&keys, &ColorScheme::as_slice(), &self.settings.string(COLOR_SCHEME))
+    }
+
+    pub fn set_color_scheme(&self, color_scheme: ColorScheme) {
+        let keys = ColorScheme::SCHEMES.map(|(key, _)| key);
+        let _ = self.settings.set_string(COLOR_SCHEME, to_key(&keys, &ColorScheme::as_slice(), &color_scheme));
+    }
+
+    pub fn default_currency(&self) -> Currency {
+        Currency::from_str(&self.settings.string(DEFAULT_CURRENCY)).unwrap_or_default()
+    }
+
+    pub fn set_default_currency(&self, currency: Currency) {
+        let _ = self.settings.set_string(DEFAULT_CURRENCY, currency.as_short_str());
+    }
+
+    pub fn first_weekday(&self) -> Weekday {
+        WEEKDAY_KEYS
+            .iter()
+            .position(|k| *k == self.settings.string(FIRST_WEEKDAY))
+            .map_or(Weekday::Mon, |index| WEEKDAYS[index])
+    }
+
+    pub fn set_first_weekday(&self, weekday: Weekday) {
+        let key = WEEKDAYS
+            .iter()
+            .position(|w| *w == weekday)
+            .map_or(WEEKDAY_KEYS[0], |index| WEEKDAY_KEYS[index]);
+        let _ = self.settings.set_string(FIRST_WEEKDAY, key);
+    }
+
+    /// Returns `None` when the number format follows the system locale.
+    pub fn number_format(&self) -> Option<NumberFormat> {
+        from_optional_key(&NUMBER_FORMAT_KEYS, &NumberFormat::as_slice(), &self.settings.string(NUMBER_FORMAT))
+    }
+
+    pub fn set_number_format(&self, number_format: Option<NumberFormat>) {
+        let key = number_format.map_or(SYSTEM_KEY, |f| to_key(&NUMBER_FORMAT_KEYS, &NumberFormat::as_slice(), &f));
+        let _ = self.settings.set_string(NUMBER_FORMAT, key);
+    }
+
+    /// Returns `None` when the symbol position follows the system locale.
+    pub fn symbol_position(&self) -> Option<SymbolPosition> {
+        from_optional_key(&SYMBOL_POSITION_KEYS, &SymbolPosition::as_slice(), &self.settings.string(SYMBOL_POSITION))
+    }
+
+    pub fn set_symbol_position(&self, symbol_position: Option<SymbolPosition>) {
+        let key = symbol_position.map_or(SYSTEM_KEY, |p| to_key(&SYMBOL_POSITION_KEYS, &SymbolPosition::as_slice(), &p));
+        let _ = self.settings.set_string(SYMBOL_POSITION, key);
+    }
+
+    pub fn negative_style(&self) -> NegativeStyle {
+        from_key(&NEGATIVE_STYLE_KEYS, &NegativeStyle::as_slice(), &self.settings.string(NEGATIVE_STYLE))
+    }
+
+    pub fn set_negative_style(&self, negative_style: NegativeStyle) {
+        let _ = self.settings.set_string(NEGATIVE_STYLE, to_key(&NEGATIVE_STYLE_KEYS, &NegativeStyle::as_slice(), &negative_style));
+    }
+
+    /// Returns `None` when the date format follows the system locale.
+    pub fn date_format(&self) -> Option<DateFormat> {
+        from_optional_key(&DATE_FORMAT_KEYS, &DateFormat::as_slice(), &self.settings.string(DATE_FORMAT))
+    }
+
+    pub fn set_date_format(&self, date_format: Option<DateFormat>) {
+        let key = date_format.map_or(SYSTEM_KEY, |f| to_key(&DATE_FORMAT_KEYS, &DateFormat::as_slice(), &f));
+        let _ = self.settings.set_string(DATE_FORMAT, key);
+    }
+
+    /// Conventions of the system locale, read from the C library once the
+    /// application has set its locale from the environment.
+    #[cfg(unix)]
+    fn system_locale() -> Locale {
+        use std::ffi::{c_char, CStr};
+
+        let text = |pointer: *const c_char| if pointer.is_null() {
+            String::new()
+        } else {
+            // SAFETY: the C library returns valid strings, copied before the locale can change.
+            unsafe { CStr::from_ptr(pointer) }.to_string_lossy().into_owned()
+        };
+        // SAFETY: `localeconv` returns a static structure, only read on the main thread.
+        let conventions = unsafe { &*libc::localeconv() };
+        let is_symbol_before = match conventions.p_cs_precedes {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        };
+        let (group, decimal) = match text(conventions.mon_decimal_point).as_str() {
+            "" => (text(conventions.thousands_sep), text(conventions.decimal_point)),
+            decimal => (text(conventions.mon_thousands_sep), decimal.to_owned()),
+        };
+        // SAFETY: as above, `nl_langinfo` returns a string of the C library.
+        let date_pattern = text(unsafe { libc::nl_langinfo(libc::D_FMT) });
+        Locale::from_conventions(&group, &decimal, is_symbol_before, &date_pattern)
+    }
+
+    /// The C library gives no conventions to read here, so only the overrides apply.
+    #[cfg(not(unix))]
+    fn system_locale() -> Locale {
+        Locale::DEFAULT
+    }
+
+    /// Combines the conventions of the system locale with the overrides.
+    pub fn locale(&self) -> Locale {
+        let system = Self::system_locale();
+        Locale {
+            number_format: self.number_format().unwrap_or(system.number_format),
+            symbol_position: self.symbol_position().unwrap_or(system.symbol_position),
+            negative_style: self.negative_style(),
+            date_format: self.date_format().unwrap_or(system.date_format),
+        }
+    }
+
+    pub fn backup_policy(&self) -> BackupPolicy {
+        from_key(&BACKUP_POLICY_KEYS, &BackupPolicy::as_slice(), &self.settings.string(BACKUP_POLICY))
+    }
+
+    pub fn set_backup_policy(&self, policy: BackupPolicy) {
+        let _ = self.settings.set_string(BACKUP_POLICY, to_key(&BACKUP_POLICY_KEYS, &BackupPolicy::as_slice(), &policy));
+    }
+
+    pub fn backup_count(&self) -> u32 {
+        self.settings.uint(BACKUP_COUNT)
+    }
+
+    pub fn set_backup_count(&self, count: u32) {
+        let _ = self.settings.set_uint(BACKUP_COUNT, count);
+    }
+
+    pub fn rate_provider(&self) -> RateProvider {
+        from_key(&RATE_PROVIDER_KEYS, &RateProvider::as_slice(), &self.settings.string(RATE_PROVIDER))
+    }
+
+    pub fn set_rate_provider(&self, provider: RateProvider) {
+        let _ = self.settings.set_string(RATE_PROVIDER, to_key(&RATE_PROVIDER_KEYS, &RateProvider::as_slice(), &provider));
+    }
+
+    pub fn notification_timeout(&self) -> u32 {
+        self.settings.uint(NOTIFICATION_TIMEOUT)
+    }
+
+    pub fn set_notification_timeout(&self, seconds: u32) {
+        let _ = self.settings.set_uint(NOTIFICATION_TIMEOUT, seconds);
+    }
+
+    pub fn auto_lock_minutes(&self) -> u32 {
+        self.settings.uint(AUTO_LOCK_MINUTES)
+    }
+
+    pub fn set_auto_lock_minutes(&self, minutes: u32) {
+        let _ = self.settings.set_uint(AUTO_LOCK_MINUTES, minutes);
+    }
+
+    /// Stored as a `(sort key, descending, group by)` tuple per page.
+    fn view_options(&self, key: &str) -> ViewOptions {
+        let (sort_key, descending, group_by) = self.settings
+            .value(key)
+            .get::<(String, bool, String)>()
+            .unwrap_or_default();
+        ViewOptions {
+            sort_key: from_key(&SORT_KEY_KEYS, &SortKey::as_slice(), &sort_key),
+            descending,
+            group_by: from_key(&GROUP_BY_KEYS, &GroupBy::as_slice(), &group_by),
+        }
+    }
+
+    fn set_view_options(&self, key: &str, options: ViewOptions) {
+        let value = (
+            to_key(&SORT_KEY_KEYS, &SortKey::as_slice(), &options.sort_key),
+            options.descending,
+            to_key(&GROUP_BY_KEYS, &GroupBy::as_slice(), &options.group_by),
+        );
+        let _ = self.settings.set_value(key, &value.to_variant());
+    }
+
+    pub fn views(&self) -> ViewSettings {
+        ViewSettings {
+            wallet: self.view_options(WALLET_VIEW),
+            overview: self.view_options(OVERVIEW_VIEW),
+        }
+    }
+
+    pub fn set_views(&self, views: ViewSettings) {
+        self.set_view_options(WALLET_VIEW, views.wallet);
+        self.set_view_options(OVERVIEW_VIEW, views.overview);
+    }
+
+    fn window_states(&self) -> Vec<WindowState> {
+        self.settings
+            .value(WINDOW_STATES)
+            .get::<Vec<WindowState>>()
+            .unwrap_or_default()
+    }
+
+    /// Gives `window` the first slot of the saved window states that no other open
+    /// window uses, restores its size from it and saves it back there on close,
+    /// so each open window keeps its own size.
+    pub fn bind_window_state(&self, window: &adw::ApplicationWindow) {
+        let slot = (0..)
+            .find(|slot| !self.window_slots.borrow().contains(slot))
+            .unwrap_or_default();
+        self.window_slots.borrow_mut().insert(slot);
+
+        let (width, height, maximized) = self.window_states()
+            .get(slot)
+            .copied()
+            .unwrap_or(DEFAULT_WINDOW_STATE);
+        window.set_default_size(width, height);
+        window.set_maximized(maximized);
+
+        let this = self.clone();
+        window.connect_close_request(move |window| {
+            let mut states = this.window_states();
+            if states.len() <= slot {
+                states.resize(slot + 1, DEFAULT_WINDOW_STATE);
+            }
+            states[slot] = (window.default_width(), window.default_height(), window.is_maximized());
+            let _ = this.settings.set_value(WINDOW_STATES, &states.to_variant());
+            this.window_slots.borrow_mut().remove(&slot);
+            glib::Propagation::Proceed
+        });
+    }
+
+    /// Pushes the current values to the places that read them: the style
+    /// manager, the data layer preferences, the backup policy and the auto-lock timeout.
+    pub fn apply(&self) {
+        adw::StyleManager::default().set_color_scheme(self.color_scheme().as_adw());
+        Preferences::set_current(Preferences {
+            locale: self.locale(),
+            first_weekday: self.first_weekday(),
+            default_currency: self.default_currency(),
+            rate_provider: self.rate_provider(),
+            views: self.views(),
+        });
+        Backup::set_current(Backup {
+            policy: self.backup_policy(),
+            count: self.backup_count(),
+        });
+        AutoLock::set_timeout_minutes(self.auto_lock_minutes());
+    }
+
+    /// Calls `callback` when one of the keys read by `apply` changes.
+    pub fn connect_changed(&self, callback: impl Fn(&Self) + 'static) {
+        let this = self.clone();
+        self.settings.connect_changed(move |key| {
+            if APPLIED_KEYS.contains(&key) {
+                callback(&this);
+            }
+        });
+    }
+
+}
@@ -1,8 +1,13 @@
+use crate::prelude::*;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AlertButtonType {
     Ok,
     Cancel,
     Remove,
+    Reload,
+    Overwrite,
+    Merge,
 }
 
 impl AlertButtonType {
@@ -11,6 +16,9 @@ impl AlertButtonType {
             AlertButtonType::Ok => "ok",
             AlertButtonType::Cancel => "cancel",
             AlertButtonType::Remove => "remove",
+            AlertButtonType::Reload => "reload",
+            AlertButtonType::Overwrite => "overwrite",
+            AlertButtonType::Merge => "merge",
         }
     }
 
@@ -63,15 +71,27 @@ impl AlertButton {
     }
 
     pub fn ok() -> Self {
-        Self::new("OK", AlertButtonType::Ok).suggested()
+        Self::new(gettext("OK"), AlertButtonType::Ok).suggested()
     }
 
     pub fn cancel() -> Self {
-        Self::new("Cancel", AlertButtonType::Cancel)
+        Self::new(gettext("Cancel"), AlertButtonType::Cancel)
     }
 
     pub fn remove() -> Self {
-        Self::new("Remove", AlertButtonType::Remove).destructive()
+        Self::new(gettext("Remove"), AlertButtonType::Remove).destructive()
+    }
+
+    pub fn reload() -> Self {
+        Self::new(gettext("Reload"), AlertButtonType::Reload)
+    }
+
+    pub fn overwrite() -> Self {
+        Self::new(gettext("Keep My Changes"), AlertButtonType::Overwrite).destructive()
+    }
+
+    pub fn merge() -> Self {
+        Self::new(gettext("Merge"), AlertButtonType::Merge).suggested()
     }
 
 }
@@ -0,0 +1,31 @@
+/// Scores `text` against `query` when every query character appears in order,
+/// ignoring case and whitespace in the query. Characters at the start of a word
+/// and runs of consecutive characters score higher; `None` means no match.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<u32> {
+    let mut text_chars = text.chars().flat_map(char::to_lowercase).enumerate();
+    let mut previous_char = ' ';
+    let mut previous_match: Option<usize> = None;
+    let mut score = 0;
+
+    for query_char in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        loop {
+            let (index, text_char) = text_chars.next()?;
+            let is_word_start = !previous_char.is_alphanumeric();
+            previous_char = text_char;
+            if text_char != query_char {
+                continue;
+            }
+
+            score += 1;
+            if is_word_start {
+                score += 3;
+            }
+            if previous_match.is_some_and(|p| p + 1 == index) {
+                score += 2;
+            }
+            previous_match = Some(index);
+            break;
+        }
+    }
+    Some(score)
+}
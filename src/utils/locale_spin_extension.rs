@@ -0,0 +1,55 @@
+use crate::prelude::*;
+use crate::data::Preferences;
+
+fn parse_text(text: &str) -> Option<Result<f64, ()>> {
+    Some(Preferences::current().locale.parse_number(text).ok_or(()))
+}
+
+fn format_value(value: f64, digits: u32) -> String {
+    Preferences::current().locale.format_number(value, digits as usize)
+}
+
+/// Makes a spin widget read and write its value with the separators of the
+/// current locale instead of the ones of the C locale.
+pub trait LocaleSpinExtension {
+    fn set_locale_format(&self);
+
+    /// The value being typed, which `value()` only catches up with once the entry is left.
+    fn locale_value(&self) -> f64;
+}
+
+impl LocaleSpinExtension for adw::SpinRow {
+    fn set_locale_format(&self) {
+        self.set_numeric(false);
+        self.connect_input(|spin_row| parse_text(&spin_row.text()));
+        self.connect_output(|spin_row| {
+            spin_row.set_text(&format_value(spin_row.value(), spin_row.digits()));
+            true
+        });
+    }
+
+    fn locale_value(&self) -> f64 {
+        Preferences::current()
+            .locale
+            .parse_number(&self.text())
+            .unwrap_or_else(|| self.value())
+    }
+}
+
+impl LocaleSpinExtension for gtk::SpinButton {
+    fn set_locale_format(&self) {
+        self.set_numeric(false);
+        self.connect_input(|spin_button| parse_text(&spin_button.text()));
+        self.connect_output(|spin_button| {
+            spin_button.set_text(&format_value(spin_button.value(), spin_button.digits()));
+            gtk::glib::Propagation::Stop
+        });
+    }
+
+    fn locale_value(&self) -> f64 {
+        Preferences::current()
+            .locale
+            .parse_number(&self.text())
+            .unwrap_or_else(|| self.value())
+    }
+}
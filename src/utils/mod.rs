@@ -2,8 +2,16 @@ mod scrollable_pane;
 mod button_list;
 mod popover_extension;
 mod alert_button;
+mod date_picker;
+mod view_options_row;
+mod locale_spin_extension;
+mod fuzzy_match;
 
 pub use scrollable_pane::ScrollablePane;
 pub use button_list::ButtonList;
 pub use popover_extension::PopoverExtension;
-pub use alert_button::{AlertButton, AlertButtonType};
\ No newline at end of file
+pub use alert_button::{AlertButton, AlertButtonType};
+pub use date_picker::DatePicker;
+pub use view_options_row::ViewOptionsRow;
+pub use locale_spin_extension::LocaleSpinExtension;
+pub use fuzzy_match::fuzzy_match;
\ No newline at end of file
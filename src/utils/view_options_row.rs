@@ -0,0 +1,145 @@
+use crate::prelude::*;
+use crate::data::{GroupBy, SortKey, ViewOptions};
+
+use gtk::glib::clone::Downgrade;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+type ChangedCallback = RefCell<Option<Box<dyn Fn(ViewOptions)>>>;
+
+pub struct ViewOptionsRow {
+    action_row: adw::ActionRow,
+    sort_drop_down: gtk::DropDown,
+    direction_button: gtk::ToggleButton,
+    group_drop_down: gtk::DropDown,
+
+    is_updating: Cell<bool>,
+    callback: ChangedCallback,
+}
+
+impl ViewOptionsRow {
+
+    pub fn new() -> Rc<Self> {
+        let sort_keys: Vec<String> = SortKey::as_slice().iter().map(|k| gettext(k.as_str())).collect();
+        let sort_drop_down = Self::build_drop_down(&sort_keys, &gettext("Sort by"));
+        let direction_button = Self::build_direction_button();
+        let groups: Vec<String> = GroupBy::as_slice().iter().map(|g| gettext(g.as_str())).collect();
+        let group_drop_down = Self::build_drop_down(&groups, &gettext("Group by"));
+        let action_row = Self::build_action_row(&sort_drop_down, &direction_button, &group_drop_down);
+
+        let this = Rc::new(Self {
+            action_row,
+            sort_drop_down,
+            direction_button,
+            group_drop_down,
+
+            is_updating: Cell::new(false),
+            callback: RefCell::new(None),
+        });
+        this.connect_controls_changed();
+        this
+    }
+
+    fn build_drop_down(items: &[String], tooltip: &str) -> gtk::DropDown {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let drop_down = gtk::DropDown::from_strings(&items);
+        drop_down.set_valign(gtk::Align::Center);
+        drop_down.set_tooltip_text(Some(tooltip));
+        drop_down
+    }
+
+    fn build_direction_button() -> gtk::ToggleButton {
+        let button = gtk::ToggleButton::new();
+        button.set_valign(gtk::Align::Center);
+        button.add_css_class("flat");
+        button
+    }
+
+    fn build_action_row(sort_drop_down: &gtk::DropDown, direction_button: &gtk::ToggleButton, group_drop_down: &gtk::DropDown) -> adw::ActionRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&gettext("View"));
+        action_row.add_suffix(sort_drop_down);
+        action_row.add_suffix(direction_button);
+        action_row.add_suffix(group_drop_down);
+        action_row
+    }
+
+    fn update_direction_button(&self) {
+        if self.direction_button.is_active() {
+            self.direction_button.set_icon_name("view-sort-descending-symbolic");
+            self.direction_button.set_tooltip_text(Some(&gettext("Descending")));
+        } else {
+            self.direction_button.set_icon_name("view-sort-ascending-symbolic");
+            self.direction_button.set_tooltip_text(Some(&gettext("Ascending")));
+        }
+    }
+
+    pub fn get_options(&self) -> ViewOptions {
+        let sort_key = SortKey::as_slice()
+            .get(self.sort_drop_down.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+        let group_by = GroupBy::as_slice()
+            .get(self.group_drop_down.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+
+        ViewOptions {
+            sort_key,
+            descending: self.direction_button.is_active(),
+            group_by,
+        }
+    }
+
+    pub fn set_options(&self, options: ViewOptions) {
+        self.is_updating.set(true);
+        let sort_position = SortKey::as_slice().iter().position(|k| *k == options.sort_key).unwrap_or(0);
+        let group_position = GroupBy::as_slice().iter().position(|g| *g == options.group_by).unwrap_or(0);
+        self.sort_drop_down.set_selected(sort_position as u32);
+        self.group_drop_down.set_selected(group_position as u32);
+        self.direction_button.set_active(options.descending);
+        self.update_direction_button();
+        self.is_updating.set(false);
+    }
+
+    fn handle_changes(&self) {
+        self.update_direction_button();
+        if self.is_updating.get() {
+            return;
+        }
+        if let Some(callback) = self.callback.borrow().as_ref() {
+            callback(self.get_options());
+        }
+    }
+
+    fn connect_controls_changed(self: &Rc<Self>) {
+        let this = self.downgrade();
+        self.sort_drop_down.connect_selected_notify(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+
+        let this = self.downgrade();
+        self.group_drop_down.connect_selected_notify(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+
+        let this = self.downgrade();
+        self.direction_button.connect_toggled(move |_| {
+            let Some(this) = this.upgrade() else { return };
+            this.handle_changes();
+        });
+    }
+
+    pub fn connect_changed(&self, callback: impl Fn(ViewOptions) + 'static) {
+        self.callback.replace(Some(Box::new(callback)));
+    }
+
+}
+
+impl HasWidget<gtk::Widget> for Rc<ViewOptionsRow> {
+    fn widget(&self) -> &gtk::Widget {
+        self.action_row.upcast_ref()
+    }
+}
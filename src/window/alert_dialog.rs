@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use crate::context::*;
-use crate::utils::AlertButton;
+use crate::utils::{AlertButton, AlertButtonType};
 use super::WindowComponent;
 
 pub struct AlertDialog {
@@ -49,21 +49,44 @@ impl AlertDialog {
         });
     }
 
+    /// Asks for a line of text, answering `None` when cancelled.
+    fn show_entry_dialog(&self, text: &str, button: AlertButton, callback: Box<dyn FnOnce(Option<String>)>) {
+        let entry = gtk::Entry::new();
+        entry.set_text(text);
+        entry.set_activates_default(true);
+        self.alert_dialog.set_extra_child(Some(&entry));
+
+        let buttons = vec![AlertButton::cancel(), button];
+        self.add_buttons(&buttons);
+        self.alert_dialog.set_default_response(Some(buttons[1].tag()));
+        entry.grab_focus();
+
+        let alert_dialog = self.alert_dialog.clone();
+        self.show_dialog(buttons, Box::new(move |button| {
+            alert_dialog.set_extra_child(gtk::Widget::NONE);
+            let is_confirmed = button.is_some_and(|b| b.button_type != AlertButtonType::Cancel);
+            callback(is_confirmed.then(|| entry.text().to_string()));
+        }));
+    }
+
 }
 
 impl LifeCycle<UiAction> for AlertDialog {
     fn activate(&self, action: UiAction, _: &Context) {
-        let UiAction::OpenAlertDialog {
-            title,
-            message,
-            buttons,
-            callback
-        } = action else { unreachable!() };
-
-        self.alert_dialog.set_heading(Some(&title));
-        self.alert_dialog.set_body(&message);
-        self.add_buttons(&buttons);
-        self.show_dialog(buttons, callback);
+        match action {
+            UiAction::OpenAlertDialog { title, message, buttons, callback } => {
+                self.alert_dialog.set_heading(Some(&title));
+                self.alert_dialog.set_body(&message);
+                self.add_buttons(&buttons);
+                self.show_dialog(buttons, callback);
+            },
+            UiAction::OpenEntryDialog { title, text, button, callback } => {
+                self.alert_dialog.set_heading(Some(&title));
+                self.alert_dialog.set_body("");
+                self.show_entry_dialog(&text, button, callback);
+            },
+            _ => unreachable!(),
+        }
     }
 
     fn deactivate(&self) {
@@ -0,0 +1,548 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::{Currency, Data, DataError, DocumentIcon, Period, Preferences};
+use crate::pages::DEFAULT_UPCOMING_DAYS;
+use crate::utils::{fuzzy_match, AlertButton, AlertButtonType};
+
+use gtk::{gdk, glib};
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::WindowComponent;
+use super::UiAction;
+
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, Copy)]
+enum CommandKind {
+    Navigate(NavigationAction),
+    SetPeriod(Period),
+    SetCurrency(Currency),
+    SetIcon(DocumentIcon),
+    RenameDocument,
+    ConvertDocument(StorageFormat),
+    ExportCsv,
+    MergeDocument,
+    ChangePassphrase,
+    LockDocument,
+    OpenPreferences,
+    OpenShortcuts,
+    OpenAbout,
+}
+
+struct Command {
+    title: String,
+    subtitle: String,
+    icon_name: &'static str,
+    kind: CommandKind,
+}
+
+impl Command {
+
+    fn new(title: impl Into<String>, subtitle: impl Into<String>, icon_name: &'static str, kind: CommandKind) -> Self {
+        Self {
+            title: title.into(),
+            subtitle: subtitle.into(),
+            icon_name,
+            kind,
+        }
+    }
+
+    /// Matches on the title first; subtitle matches rank below any title match.
+    fn score(&self, query: &str) -> Option<u32> {
+        let title_score = fuzzy_match(query, &self.title).map(|s| s * 2);
+        let subtitle_score = fuzzy_match(query, &self.subtitle);
+        title_score.max(subtitle_score)
+    }
+
+}
+
+pub struct CommandPalette {
+    window: gtk::Window,
+    dialog: adw::Dialog,
+    search_entry: gtk::SearchEntry,
+    list_box: gtk::ListBox,
+    commands: Rc<RefCell<Vec<Command>>>,
+    results: Rc<RefCell<Vec<usize>>>,
+    is_active: Rc<Cell<bool>>,
+    context: Rc<RefCell<Context>>,
+}
+
+impl CommandPalette {
+
+    pub fn new(window: impl IsA<gtk::Window>) -> Self {
+        let search_entry = Self::build_search_entry();
+        let list_box = Self::build_list_box();
+        let dialog = Self::build_dialog(&search_entry, &list_box);
+
+        let this = Self {
+            window: window.upcast(),
+            dialog,
+            search_entry,
+            list_box,
+            commands: Rc::new(RefCell::new(Vec::new())),
+            results: Rc::new(RefCell::new(Vec::new())),
+            is_active: Rc::new(Cell::new(false)),
+            context: Rc::new(RefCell::new(Context::default())),
+        };
+        this.connect_search_changed();
+        this.connect_search_activated();
+        this.connect_key_pressed();
+        this.connect_row_activated();
+        this.connect_closed();
+        this
+    }
+
+    fn build_search_entry() -> gtk::SearchEntry {
+        let search_entry = gtk::SearchEntry::new();
+        search_entry.set_placeholder_text(Some(&gettext("Search wallets, transactions and commands")));
+        search_entry.set_hexpand(true);
+        search_entry
+    }
+
+    fn build_list_box() -> gtk::ListBox {
+        let placeholder = gtk::Label::new(Some(&gettext("No matching commands")));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_margin_top(12);
+        placeholder.set_margin_bottom(12);
+
+        let list_box = gtk::ListBox::new();
+        list_box.set_selection_mode(gtk::SelectionMode::Single);
+        list_box.add_css_class("navigation-sidebar");
+        list_box.set_placeholder(Some(&placeholder));
+        list_box
+    }
+
+    fn build_dialog(search_entry: &gtk::SearchEntry, list_box: &gtk::ListBox) -> adw::Dialog {
+        let header_bar = adw::HeaderBar::new();
+        header_bar.set_title_widget(Some(search_entry));
+        header_bar.set_show_end_title_buttons(false);
+
+        let scrolled_window = gtk::ScrolledWindow::new();
+        scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+        scrolled_window.set_vexpand(true);
+        scrolled_window.set_child(Some(list_box));
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header_bar);
+        toolbar_view.set_content(Some(&scrolled_window));
+
+        let dialog = adw::Dialog::new();
+        dialog.set_title(&gettext("Command Palette"));
+        dialog.set_content_width(520);
+        dialog.set_content_height(440);
+        dialog.set_child(Some(&toolbar_view));
+        dialog.set_focus(Some(search_entry));
+        dialog
+    }
+
+    fn build_row(command: &Command) -> gtk::ListBoxRow {
+        let action_row = adw::ActionRow::new();
+        action_row.set_title(&glib::markup_escape_text(&command.title));
+        action_row.set_subtitle(&glib::markup_escape_text(&command.subtitle));
+        action_row.add_prefix(&gtk::Image::from_icon_name(command.icon_name));
+        action_row.set_activatable(true);
+        action_row.upcast()
+    }
+
+    fn build_commands(context: &Context, is_data_open: bool) -> Vec<Command> {
+        let mut commands = Vec::new();
+        if is_data_open {
+            let data = context.data();
+            commands.extend(Self::build_page_commands());
+            commands.extend(Self::build_wallet_commands(data));
+            commands.extend(Self::build_transaction_commands(data));
+            commands.extend(Self::build_period_commands(data));
+            commands.extend(Self::build_currency_commands(data));
+            commands.extend(Self::build_icon_commands(data));
+            commands.push(Command::new(gettext("Rename Document"), gettext("Change the name shown for this document"), "document-edit-symbolic", CommandKind::RenameDocument));
+            commands.push(Command::new(gettext("Export CSV"), gettext("Save all transactions as a spreadsheet"), "document-save-symbolic", CommandKind::ExportCsv));
+            commands.push(Command::new(gettext("Merge Document"), gettext("Combine another copy of this document into it"), "emblem-synchronizing-symbolic", CommandKind::MergeDocument));
+            commands.extend(Self::build_format_commands(context));
+            if context.file().has_passphrase() {
+                commands.push(Command::new(gettext("Change Passphrase"), gettext("Change or remove the passphrase of this document"), "dialog-password-symbolic", CommandKind::ChangePassphrase));
+                commands.push(Command::new(gettext("Lock Document"), gettext("Close this document until its passphrase is entered"), "channel-secure-symbolic", CommandKind::LockDocument));
+            } else if context.file().format() == StorageFormat::Json {
+                commands.push(Command::new(gettext("Set Passphrase"), gettext("Encrypt this document with a passphrase"), "dialog-password-symbolic", CommandKind::ChangePassphrase));
+            }
+        }
+        commands.extend([
+            Command::new(gettext("Preferences"), gettext("Command"), "preferences-system-symbolic", CommandKind::OpenPreferences),
+            Command::new(gettext("Keyboard Shortcuts"), gettext("Command"), "preferences-desktop-keyboard-shortcuts-symbolic", CommandKind::OpenShortcuts),
+            Command::new(gettext("About"), gettext("Command"), "help-about-symbolic", CommandKind::OpenAbout),
+        ]);
+        commands
+    }
+
+    fn build_page_commands() -> Vec<Command> {
+        let page = gettext("Page");
+        vec![
+            Command::new(gettext("Overview"), &page, "go-home-symbolic", CommandKind::Navigate(NavigationAction::NavigateToOverview)),
+            Command::new(gettext("Upcoming Payments"), &page, "month-symbolic", CommandKind::Navigate(NavigationAction::navigate_to_upcoming(DEFAULT_UPCOMING_DAYS))),
+            Command::new(gettext("Payees"), &page, "system-users-symbolic", CommandKind::Navigate(NavigationAction::NavigateToPayees)),
+            Command::new(gettext("Rules"), &page, "edit-find-replace-symbolic", CommandKind::Navigate(NavigationAction::navigate_to_rules(None))),
+            Command::new(gettext("Search"), &page, "system-search-symbolic", CommandKind::Navigate(NavigationAction::NavigateToSearch)),
+            Command::new(gettext("New Wallet"), gettext("Command"), "list-add-symbolic", CommandKind::Navigate(NavigationAction::navigate_to_new_wallet())),
+        ]
+    }
+
+    fn build_wallet_commands(data: &Data) -> Vec<Command> {
+        data.wallets
+            .iter()
+            .map(|wallet| Command::new(
+                &wallet.name,
+                gettext("Wallet"),
+                "go-next-symbolic",
+                CommandKind::Navigate(NavigationAction::navigate_to_wallet(wallet.id))
+            ))
+            .collect()
+    }
+
+    fn build_transaction_commands(data: &Data) -> Vec<Command> {
+        let locale = Preferences::current().locale;
+        data.wallets
+            .iter()
+            .flat_map(|wallet| wallet.transactions.iter().map(move |transaction| (wallet, transaction)))
+            .map(|(wallet, transaction)| Command::new(
+                &transaction.name,
+                format!("{} · {}", wallet.name, locale.format_date(transaction.start_date)),
+                transaction.cycle.icon_name(),
+                CommandKind::Navigate(NavigationAction::navigate_to_transaction(wallet.id, transaction.id))
+            ))
+            .collect()
+    }
+
+    fn build_period_commands(data: &Data) -> Vec<Command> {
+        Period::as_slice()
+            .into_iter()
+            .filter(|period| *period != data.period)
+            .map(|period| Command::new(
+                gettext_f("Switch period to {period}", &[("period", &period.to_string())]),
+                gettext("Period"),
+                "month-symbolic",
+                CommandKind::SetPeriod(period)
+            ))
+            .collect()
+    }
+
+    fn build_currency_commands(data: &Data) -> Vec<Command> {
+        Currency::as_slice()
+            .into_iter()
+            .filter(|currency| *currency != data.currency)
+            .map(|currency| Command::new(
+                gettext_f("Switch to {currency}", &[("currency", currency.as_short_str())]),
+                gettext(currency.as_long_str()),
+                "graph-symbolic",
+                CommandKind::SetCurrency(currency)
+            ))
+            .collect()
+    }
+
+    fn build_icon_commands(data: &Data) -> Vec<Command> {
+        DocumentIcon::as_slice()
+            .into_iter()
+            .filter(|icon| *icon != data.icon)
+            .map(|icon| Command::new(
+                gettext_f("Change icon to {icon}", &[("icon", &icon.to_string())]),
+                gettext("Document"),
+                icon.icon_name(),
+                CommandKind::SetIcon(icon)
+            ))
+            .collect()
+    }
+
+    /// Conversions to the other storage formats, except for encrypted documents which have to stay in JSON.
+    fn build_format_commands(context: &Context) -> Vec<Command> {
+        if context.file().has_passphrase() {
+            return Vec::new();
+        }
+        let current_format = context.file().format();
+        StorageFormat::as_slice()
+            .into_iter()
+            .filter(|format| *format != current_format)
+            .map(|format| Command::new(
+                gettext_f("Convert to {format}", &[("format", &gettext(format.as_str()))]),
+                gettext("Save a copy of this document in another format and open it"),
+                "document-save-as-symbolic",
+                CommandKind::ConvertDocument(format)
+            ))
+            .collect()
+    }
+
+    /// Lists the best matches for `query`, keeping the original order between equal scores.
+    fn update_results(list_box: &gtk::ListBox, commands: &[Command], results: &mut Vec<usize>, query: &str) {
+        let mut matches: Vec<(usize, u32)> = commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| command.score(query).map(|score| (index, score)))
+            .collect();
+        matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+        results.clear();
+        results.extend(matches.into_iter().take(MAX_RESULTS).map(|(index, _)| index));
+
+        list_box.remove_all();
+        for index in results.iter() {
+            list_box.append(&Self::build_row(&commands[*index]));
+        }
+        list_box.select_row(list_box.row_at_index(0).as_ref());
+    }
+
+    fn connect_search_changed(&self) {
+        let list_box = self.list_box.clone();
+        let commands = self.commands.clone();
+        let results = self.results.clone();
+        self.search_entry.connect_search_changed(move |search_entry| {
+            Self::update_results(&list_box, &commands.borrow(), &mut results.borrow_mut(), &search_entry.text());
+        });
+    }
+
+    fn connect_search_activated(&self) {
+        let list_box = self.list_box.clone();
+        self.search_entry.connect_activate(move |_| {
+            if let Some(row) = list_box.selected_row().or_else(|| list_box.row_at_index(0)) {
+                row.activate();
+            }
+        });
+
+        let dialog = self.dialog.clone();
+        self.search_entry.connect_stop_search(move |_| {
+            dialog.close();
+        });
+    }
+
+    /// Moves the selection with the arrow keys while the search entry keeps the focus.
+    fn connect_key_pressed(&self) {
+        let list_box = self.list_box.clone();
+        let controller = gtk::EventControllerKey::new();
+        controller.connect_key_pressed(move |_, key, _, _| {
+            let offset = match key {
+                gdk::Key::Down => 1,
+                gdk::Key::Up => -1,
+                _ => return glib::Propagation::Proceed,
+            };
+            let index = list_box.selected_row().map_or(0, |row| row.index() + offset);
+            if let Some(row) = list_box.row_at_index(index.max(0)) {
+                list_box.select_row(Some(&row));
+            }
+            glib::Propagation::Stop
+        });
+        self.search_entry.add_controller(controller);
+    }
+
+    fn connect_row_activated(&self) {
+        let dialog = self.dialog.clone();
+        let commands = self.commands.clone();
+        let results = self.results.clone();
+        let context = self.context.clone();
+        self.list_box.connect_row_activated(move |_, row| {
+            let Some(index) = results.borrow().get(row.index() as usize).copied() else { return };
+            let kind = commands.borrow()[index].kind;
+            let context = context.take();
+            dialog.close();
+            Self::run_command(kind, context);
+        });
+    }
+
+    fn connect_closed(&self) {
+        let is_active = self.is_active.clone();
+        self.dialog.connect_closed(move |_| {
+            is_active.set(false);
+        });
+    }
+
+    fn run_command(kind: CommandKind, context: Context) {
+        match kind {
+            CommandKind::Navigate(action) => context
+                .with_navigation_action(action)
+                .propagate(),
+            CommandKind::SetPeriod(period) => {
+                let mut new_data = context.data().clone();
+                new_data.period = period;
+                context
+                    .with_data(new_data)
+                    .with_ui_action(UiAction::push_notification(gettext("Period changed")))
+                    .with_navigation_action(NavigationAction::NavigateToCurrent)
+                    .propagate()
+            },
+            CommandKind::SetCurrency(currency) => {
+                let mut new_data = context.data().clone();
+                new_data.currency = currency;
+                context
+                    .with_data(new_data)
+                    .with_ui_action(UiAction::push_notification(gettext("Currency changed")))
+                    .with_navigation_action(NavigationAction::NavigateToCurrent)
+                    .propagate()
+            },
+            CommandKind::SetIcon(icon) => {
+                let mut new_data = context.data().clone();
+                new_data.icon = icon;
+                context
+                    .with_data(new_data)
+                    .with_ui_action(UiAction::push_notification(gettext("Icon changed")))
+                    .with_navigation_action(NavigationAction::NavigateToCurrent)
+                    .propagate()
+            },
+            CommandKind::RenameDocument => Self::rename_document(context),
+            CommandKind::ConvertDocument(format) => Self::convert_document(context, format),
+            CommandKind::ExportCsv => Self::export_csv(context),
+            CommandKind::MergeDocument => context
+                .with_ui_action(UiAction::open_file_chooser(Self::handle_merge_chosen))
+                .propagate(),
+            CommandKind::ChangePassphrase => context
+                .with_ui_action(UiAction::OpenPassphraseDialog)
+                .propagate(),
+            CommandKind::LockDocument => context.lock(),
+            CommandKind::OpenPreferences => context
+                .with_ui_action(UiAction::OpenPreferencesDialog)
+                .propagate(),
+            CommandKind::OpenShortcuts => context
+                .with_ui_action(UiAction::OpenShortcutsWindow)
+                .propagate(),
+            CommandKind::OpenAbout => context
+                .with_ui_action(UiAction::OpenAboutDialog)
+                .propagate(),
+        }
+    }
+
+    fn rename_document(context: Context) {
+        let callback_context = context.clone();
+        let name = match context.data().name.as_str() {
+            "" => RecentDocuments::file_display_name(&context.file().path),
+            name => name.to_owned(),
+        };
+        context.with_ui_action(UiAction::OpenEntryDialog {
+            title: gettext("Rename Document"),
+            text: name,
+            button: AlertButton::new(gettext("Rename"), AlertButtonType::Ok).suggested(),
+            callback: Box::new(move |name| {
+                let Some(name) = name.filter(|name| !name.trim().is_empty()) else { return };
+                let mut new_data = callback_context.data().clone();
+                new_data.name = name.trim().to_owned();
+                callback_context
+                    .with_data(new_data)
+                    .with_ui_action(UiAction::push_notification(gettext("Document renamed")))
+                    .with_navigation_action(NavigationAction::NavigateToCurrent)
+                    .propagate();
+            }),
+        }).propagate();
+    }
+
+    /// Opens a copy of the document in `format`, keeping the original file.
+    /// The copy is written by the context like any other change, from the data in memory.
+    fn convert_document(context: Context, format: StorageFormat) {
+        let file = match context.file().convert(format) {
+            Ok(file) => file,
+            Err(e) => return context
+                .with_ui_action(UiAction::push_error(gettext("Failed to convert document"), e))
+                .propagate(),
+        };
+        let data = context.data().clone();
+        let message = gettext_f("Converted to {format}", &[("format", &gettext(format.as_str()))]);
+        context
+            .with_file(file)
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(message))
+            .with_navigation_action(NavigationAction::NavigateToCurrent)
+            .propagate();
+    }
+
+    fn export_csv(context: Context) {
+        let file_stem = context.file().path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_owned)
+            .unwrap_or_else(|| gettext("transactions"));
+        context
+            .with_ui_action(UiAction::save_file_chooser(format!("{}.csv", file_stem), Self::handle_csv_exported))
+            .propagate();
+    }
+
+    /// Merges the chosen copy, using the latest backup of the open document as the common ancestor.
+    /// Both are read on a worker thread.
+    fn handle_merge_chosen(path: Option<PathBuf>, context: &Context) {
+        let Some(path) = path else { return };
+        let file = context.file().clone();
+        let callback_context = context.clone();
+        context.background().spawn(
+            move || {
+                let theirs = file.clone().with_path(path).load()?;
+                let base = Backup::find_latest(&file.path).and_then(|backup| file.with_path(backup).load().ok());
+                Ok::<_, DataError>((base, theirs))
+            },
+            move |result| match result {
+                Ok((Some(base), theirs)) => callback_context.merge(Some(&base), &theirs),
+                Ok((None, theirs)) => Self::confirm_merge_without_base(callback_context, theirs),
+                Err(e) => callback_context
+                    .with_ui_action(UiAction::push_error(gettext("Failed to read document"), e))
+                    .propagate(),
+            }
+        );
+    }
+
+    /// Without a backup, removed items can't be told from added ones, so the user is asked first.
+    fn confirm_merge_without_base(context: Context, theirs: Data) {
+        let callback_context = context.clone();
+        context.with_ui_action(UiAction::OpenAlertDialog {
+            title: gettext("Merge Without a Backup?"),
+            message: gettext("No backup of this document was found to compare both copies with. Items removed from either copy will be kept, and every value that differs will be listed as a conflict."),
+            buttons: vec![AlertButton::cancel(), AlertButton::merge()],
+            callback: Box::new(move |button| {
+                if button.is_some_and(|b| b.button_type == AlertButtonType::Merge) {
+                    callback_context.merge(None, &theirs);
+                }
+            }),
+        }).propagate();
+    }
+
+    /// Writes the transactions on a worker thread.
+    fn handle_csv_exported(path: Option<PathBuf>, context: &Context) {
+        let Some(path) = path else { return };
+        let csv = context.data().transactions_csv();
+        let callback_context = context.clone();
+        context.background().spawn(
+            {
+                let path = path.clone();
+                move || std::fs::write(&path, csv)
+            },
+            move |result| {
+                let action = match result {
+                    Ok(()) => {
+                        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                        UiAction::push_notification(gettext_f("Exported {file}", &[("file", &file_name)]))
+                    },
+                    Err(e) => UiAction::push_error(gettext("Failed to export transactions"), e.into()),
+                };
+                callback_context
+                    .with_ui_action(action)
+                    .propagate();
+            }
+        );
+    }
+
+}
+
+impl LifeCycle<UiAction> for CommandPalette {
+    fn activate(&self, action: UiAction, context: &Context) {
+        let UiAction::OpenCommandPalette { is_data_open } = action else { unreachable!() };
+        self.commands.replace(Self::build_commands(context, is_data_open));
+        self.context.replace(context.clone());
+        self.is_active.set(true);
+
+        self.search_entry.set_text("");
+        Self::update_results(&self.list_box, &self.commands.borrow(), &mut self.results.borrow_mut(), "");
+        self.dialog.present(Some(&self.window));
+        self.search_entry.grab_focus();
+    }
+
+    fn deactivate(&self) {
+        self.dialog.close();
+    }
+}
+
+impl WindowComponent for CommandPalette {
+    fn is_active(&self) -> bool {
+        self.is_active.get()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::DataError;
+use crate::metadata;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::WindowComponent;
+use super::UiAction;
+
+/// Shows what went wrong behind a failure notification, with a report that can be copied into a bug report.
+pub struct ErrorDialog {
+    window: gtk::Window,
+    dialog: adw::Dialog,
+    message_label: gtk::Label,
+    report_view: gtk::TextView,
+    copy_button: gtk::Button,
+    report: Rc<RefCell<String>>,
+    is_active: Rc<Cell<bool>>,
+}
+
+impl ErrorDialog {
+
+    pub fn new(window: impl IsA<gtk::Window>) -> Self {
+        let message_label = gtk::Label::new(None);
+            message_label.set_wrap(true);
+            message_label.set_xalign(0.0);
+            message_label.add_css_class("heading");
+        let report_view = gtk::TextView::new();
+            report_view.set_editable(false);
+            report_view.set_monospace(true);
+            report_view.set_wrap_mode(gtk::WrapMode::WordChar);
+            report_view.add_css_class("card");
+            report_view.set_top_margin(12);
+            report_view.set_bottom_margin(12);
+            report_view.set_left_margin(12);
+            report_view.set_right_margin(12);
+        let copy_button = gtk::Button::with_label(&gettext("Copy Report"));
+            copy_button.add_css_class("pill");
+            copy_button.set_halign(gtk::Align::Center);
+
+        let content = Self::build_content(&message_label, &report_view, &copy_button);
+        let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&adw::HeaderBar::new());
+            toolbar_view.set_content(Some(&content));
+        let dialog = adw::Dialog::new();
+            dialog.set_title(&gettext("Error Details"));
+            dialog.set_content_width(480);
+            dialog.set_content_height(420);
+            dialog.set_child(Some(&toolbar_view));
+
+        let this = Self {
+            window: window.upcast(),
+            dialog,
+            message_label,
+            report_view,
+            copy_button,
+            report: Rc::new(RefCell::new(String::new())),
+            is_active: Rc::new(Cell::new(false)),
+        };
+        this.connect_copy_button();
+        this.connect_closed();
+        this
+    }
+
+    fn build_content(message_label: &gtk::Label, report_view: &gtk::TextView, copy_button: &gtk::Button) -> gtk::Box {
+        let scrolled_window = gtk::ScrolledWindow::new();
+        scrolled_window.set_vexpand(true);
+        scrolled_window.set_child(Some(report_view));
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 18);
+        content.set_margin_top(12);
+        content.set_margin_bottom(24);
+        content.set_margin_start(24);
+        content.set_margin_end(24);
+        content.append(message_label);
+        content.append(&scrolled_window);
+        content.append(copy_button);
+        content
+    }
+
+    /// Plain text with the application version and platform, followed by the error and its cause.
+    fn build_report(message: &str, error: &DataError) -> String {
+        format!(
+            "{} {}\n{} {}\n\n{}\n{}\n\n{:?}",
+            metadata::APP_TITLE,
+            metadata::APP_VERSION,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            message,
+            error,
+            error
+        )
+    }
+
+    fn connect_copy_button(&self) {
+        let report = self.report.clone();
+        self.copy_button.connect_clicked(move |button| {
+            button.clipboard().set_text(&report.borrow());
+            button.set_label(&gettext("Copied"));
+        });
+    }
+
+    fn connect_closed(&self) {
+        let is_active = self.is_active.clone();
+        self.dialog.connect_closed(move |_| {
+            is_active.set(false);
+        });
+    }
+
+}
+
+impl LifeCycle<UiAction> for ErrorDialog {
+    fn activate(&self, action: UiAction, _: &Context) {
+        let UiAction::OpenErrorDialog { message, error } = action else { unreachable!() };
+        let report = Self::build_report(&message, &error);
+        self.message_label.set_label(&error.localized());
+        self.report_view.buffer().set_text(&report);
+        self.report.replace(report);
+        self.copy_button.set_label(&gettext("Copy Report"));
+        self.is_active.set(true);
+        self.dialog.present(Some(&self.window));
+    }
+
+    fn deactivate(&self) {
+        self.dialog.close();
+    }
+}
+
+impl WindowComponent for ErrorDialog {
+    fn is_active(&self) -> bool {
+        self.is_active.get()
+    }
+}
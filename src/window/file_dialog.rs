@@ -15,19 +15,19 @@ pub struct FileDialog {
 
 impl FileDialog {
     pub fn new(window: impl IsA<gtk::Window>) -> Self {
-        let this = Self {
+        Self {
             dialog: gtk::FileDialog::new(),
             window: window.upcast(),
             cancellable: gtk::gio::Cancellable::new(),
-        };
-        this.configure_filters();
-        this
+        }
     }
 
-    fn configure_filters(&self) {
+    fn configure_filters(&self, name: &str, mime_types: &[&str]) {
         let filter = gtk::FileFilter::new();
-        filter.set_name(Some("Documents"));
-        filter.add_mime_type("application/json");
+        filter.set_name(Some(name));
+        for mime_type in mime_types {
+            filter.add_mime_type(mime_type);
+        }
 
         let filter_list = gtk::gio::ListStore::new::<gtk::FileFilter>();
         filter_list.append(&filter);
@@ -37,12 +37,28 @@ impl FileDialog {
     }
 
     fn configure_dialog(&self, context: &Context) {
+        self.configure_filters(&gettext("Documents"), &["application/json", "application/vnd.sqlite3", "application/x-sqlite3"]);
         self.set_initial_path(context);
         self.set_dialog_properties();
     }
 
+    fn configure_save_dialog(&self, initial_name: &str) {
+        self.configure_filters(&gettext("CSV Files"), &["text/csv"]);
+        self.dialog.set_initial_name(Some(initial_name));
+        if let Some(documents) = gtk::glib::user_special_dir(gtk::glib::UserDirectory::Documents) {
+            self.dialog.set_initial_folder(Some(&File::for_path(documents)));
+        }
+        self.dialog.set_accept_label(Some(&gettext("Export")));
+        self.dialog.set_title(&gettext("Export transactions"));
+        self.dialog.set_modal(false);
+    }
+
     fn set_initial_path(&self, context: &Context) {
-        let initial_file_path = context.directory().generate_unique_file_path_or_default();
+        let name = match context.data().name.as_str() {
+            "" => gettext("Untitled Document"),
+            name => name.to_owned(),
+        };
+        let initial_file_path = context.directory().generate_unique_file_path_or_default(&name);
         
         if let Some(file_name) = initial_file_path
             .file_name()
@@ -55,8 +71,8 @@ impl FileDialog {
     }
 
     fn set_dialog_properties(&self) {
-        self.dialog.set_accept_label(Some("Select"));
-        self.dialog.set_title("Select document file");
+        self.dialog.set_accept_label(Some(&gettext("Select")));
+        self.dialog.set_title(&gettext("Select document file"));
         self.dialog.set_modal(false);
     }
 
@@ -72,16 +88,34 @@ impl FileDialog {
         );
     }
 
+    fn save_file(&self, context: &Context, callback: Box<dyn FnOnce(Option<PathBuf>, &Context) + 'static>) {
+        let context = context.clone();
+        self.dialog.save(
+            Some(&self.window),
+            Some(&self.cancellable),
+            move |result| {
+                let result = result.ok().and_then(|file| file.path());
+                callback(result, &context);
+            },
+        );
+    }
+
 }
 
 impl LifeCycle<UiAction> for FileDialog {
 
     fn activate(&self, action: UiAction, context: &Context) {
-        let UiAction::OpenFileChooserDialog {
-            callback
-        } = action else { return };
-        self.configure_dialog(context);
-        self.choose_file(context, callback);
+        match action {
+            UiAction::OpenFileChooserDialog { callback } => {
+                self.configure_dialog(context);
+                self.choose_file(context, callback);
+            },
+            UiAction::SaveFileChooserDialog { initial_name, callback } => {
+                self.configure_save_dialog(&initial_name);
+                self.save_file(context, callback);
+            },
+            _ => {}
+        }
     }
 
     fn deactivate(&self) {
@@ -0,0 +1,164 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::data::{Data, Merge, MergeConflict};
+
+use gtk::glib;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::WindowComponent;
+use super::UiAction;
+
+type MergeCallback = Box<dyn FnOnce(Data, &Context) + 'static>;
+
+/// Lists the fields both documents changed, letting the user pick a version of each.
+pub struct MergeDialog {
+    window: gtk::Window,
+    dialog: adw::Dialog,
+    toolbar_view: adw::ToolbarView,
+    cancel_button: gtk::Button,
+    merge_button: gtk::Button,
+    rows: Rc<RefCell<Vec<adw::ComboRow>>>,
+    pending: Rc<RefCell<Option<(Merge, MergeCallback)>>>,
+    is_active: Rc<Cell<bool>>,
+    context: Rc<RefCell<Context>>,
+}
+
+impl MergeDialog {
+
+    pub fn new(window: impl IsA<gtk::Window>) -> Self {
+        let cancel_button = gtk::Button::with_label(&gettext("Cancel"));
+        let merge_button = gtk::Button::with_label(&gettext("Merge"));
+        merge_button.add_css_class("suggested-action");
+        let toolbar_view = Self::build_toolbar_view(&cancel_button, &merge_button);
+        let dialog = Self::build_dialog(&toolbar_view);
+
+        let this = Self {
+            window: window.upcast(),
+            dialog,
+            toolbar_view,
+            cancel_button,
+            merge_button,
+            rows: Rc::new(RefCell::new(Vec::new())),
+            pending: Rc::new(RefCell::new(None)),
+            is_active: Rc::new(Cell::new(false)),
+            context: Rc::new(RefCell::new(Context::default())),
+        };
+        this.connect_buttons();
+        this.connect_closed();
+        this
+    }
+
+    fn build_toolbar_view(cancel_button: &gtk::Button, merge_button: &gtk::Button) -> adw::ToolbarView {
+        let header_bar = adw::HeaderBar::new();
+        header_bar.set_show_start_title_buttons(false);
+        header_bar.set_show_end_title_buttons(false);
+        header_bar.pack_start(cancel_button);
+        header_bar.pack_end(merge_button);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header_bar);
+        toolbar_view
+    }
+
+    fn build_dialog(toolbar_view: &adw::ToolbarView) -> adw::Dialog {
+        let dialog = adw::Dialog::new();
+        dialog.set_title(&gettext("Resolve Conflicts"));
+        dialog.set_content_width(560);
+        dialog.set_content_height(520);
+        dialog.set_child(Some(toolbar_view));
+        dialog
+    }
+
+    fn format_value(label: &str, value: &str) -> String {
+        let value = if value.is_empty() { gettext("(empty)") } else { value.to_string() };
+        format!("{}: {}", label, value)
+    }
+
+    fn build_row(conflict: &MergeConflict) -> adw::ComboRow {
+        let items = [
+            Self::format_value(&gettext("Mine"), &conflict.ours),
+            Self::format_value(&gettext("Theirs"), &conflict.theirs),
+        ];
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let combo_row = adw::ComboRow::new();
+        combo_row.set_title(&glib::markup_escape_text(&conflict.field.to_string()));
+        combo_row.set_model(Some(&gtk::StringList::new(&items)));
+        combo_row
+    }
+
+    /// One group per wallet, transaction, payee or rule, with a row for each conflicting field.
+    fn build_page(conflicts: &[MergeConflict]) -> (adw::PreferencesPage, Vec<adw::ComboRow>) {
+        let page = adw::PreferencesPage::new();
+        page.set_description(&gettext("Both documents changed these fields since they were last in sync. Choose the version to keep."));
+
+        let mut rows = Vec::new();
+        let mut current_item = None;
+        let mut group = adw::PreferencesGroup::new();
+        for conflict in conflicts {
+            let item = conflict.item;
+            if current_item != Some(item) {
+                current_item = Some(item);
+                group = adw::PreferencesGroup::new();
+                group.set_title(&glib::markup_escape_text(&conflict.title()));
+                page.add(&group);
+            }
+            let row = Self::build_row(conflict);
+            group.add(&row);
+            rows.push(row);
+        }
+        (page, rows)
+    }
+
+    fn connect_buttons(&self) {
+        let dialog = self.dialog.clone();
+        self.cancel_button.connect_clicked(move |_| {
+            dialog.close();
+        });
+
+        let dialog = self.dialog.clone();
+        let rows = self.rows.clone();
+        let pending = self.pending.clone();
+        let context = self.context.clone();
+        self.merge_button.connect_clicked(move |_| {
+            let Some((merge, callback)) = pending.take() else { return };
+            let use_theirs: Vec<bool> = rows.borrow().iter().map(|row| row.selected() == 1).collect();
+            let context = context.take();
+            dialog.close();
+            callback(merge.resolve(&use_theirs), &context);
+        });
+    }
+
+    fn connect_closed(&self) {
+        let is_active = self.is_active.clone();
+        let pending = self.pending.clone();
+        self.dialog.connect_closed(move |_| {
+            is_active.set(false);
+            pending.take();
+        });
+    }
+
+}
+
+impl LifeCycle<UiAction> for MergeDialog {
+    fn activate(&self, action: UiAction, context: &Context) {
+        let UiAction::OpenMergeDialog { merge, callback } = action else { unreachable!() };
+        let (page, rows) = Self::build_page(&merge.conflicts);
+        self.toolbar_view.set_content(Some(&page));
+        self.rows.replace(rows);
+        self.pending.replace(Some((merge, callback)));
+        self.context.replace(context.clone());
+        self.is_active.set(true);
+        self.dialog.present(Some(&self.window));
+    }
+
+    fn deactivate(&self) {
+        self.dialog.close();
+    }
+}
+
+impl WindowComponent for MergeDialog {
+    fn is_active(&self) -> bool {
+        self.is_active.get()
+    }
+}
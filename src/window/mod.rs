@@ -2,6 +2,12 @@ mod notifications;
 mod file_dialog;
 mod about_dialog;
 mod alert_dialog;
+mod preferences_dialog;
+mod shortcuts_window;
+mod command_palette;
+mod merge_dialog;
+mod passphrase_dialog;
+mod error_dialog;
 
 use crate::prelude::*;
 use crate::context::*;
@@ -10,6 +16,7 @@ use std::rc::Rc;
 use std::cell::Cell;
 use std::collections::HashMap;
 use crate::metadata;
+use crate::settings::Settings;
 
 trait WindowComponent: LifeCycle<UiAction> {
     fn is_active(&self) -> bool;
@@ -23,6 +30,12 @@ enum ComponentType {
     AlertDialog,
     FileDialog,
     About,
+    Preferences,
+    Shortcuts,
+    CommandPalette,
+    MergeDialog,
+    PassphraseDialog,
+    ErrorDialog,
 }
 
 pub struct Window {
@@ -31,13 +44,19 @@ pub struct Window {
 }
 
 impl Window {
-    pub fn new(application: &adw::Application, child: &impl HasWidget<gtk::Widget>) -> Rc<Self> {
-        let notifications = notifications::Notifications::new(child);
+    pub fn new(application: &adw::Application, child: &impl HasWidget<gtk::Widget>, settings: &Settings) -> Rc<Self> {
+        let notifications = notifications::Notifications::new(child, settings.clone());
 
-        let window = Self::build_window(application, &notifications);
+        let window = Self::build_window(application, &notifications, settings);
         let file_dialog = file_dialog::FileDialog::new(window.clone());
         let about_dialog = about_dialog::AboutDialog::new(window.clone());
         let alert_dialog = alert_dialog::AlertDialog::new(window.clone());
+        let preferences_dialog = preferences_dialog::PreferencesDialog::new(window.clone(), settings.clone());
+        let shortcuts_window = shortcuts_window::ShortcutsWindow::new(window.clone());
+        let command_palette = command_palette::CommandPalette::new(window.clone());
+        let merge_dialog = merge_dialog::MergeDialog::new(window.clone());
+        let passphrase_dialog = passphrase_dialog::PassphraseDialog::new(window.clone());
+        let error_dialog = error_dialog::ErrorDialog::new(window.clone());
 
         Rc::new(Self {
             components: HashMap::from([
@@ -45,15 +64,21 @@ impl Window {
                 (ComponentType::FileDialog, Box::new(file_dialog) as Box<dyn WindowComponent>),
                 (ComponentType::About, Box::new(about_dialog) as Box<dyn WindowComponent>),
                 (ComponentType::AlertDialog, Box::new(alert_dialog) as Box<dyn WindowComponent>),
+                (ComponentType::Preferences, Box::new(preferences_dialog) as Box<dyn WindowComponent>),
+                (ComponentType::Shortcuts, Box::new(shortcuts_window) as Box<dyn WindowComponent>),
+                (ComponentType::CommandPalette, Box::new(command_palette) as Box<dyn WindowComponent>),
+                (ComponentType::MergeDialog, Box::new(merge_dialog) as Box<dyn WindowComponent>),
+                (ComponentType::PassphraseDialog, Box::new(passphrase_dialog) as Box<dyn WindowComponent>),
+                (ComponentType::ErrorDialog, Box::new(error_dialog) as Box<dyn WindowComponent>),
             ]),
             previous_component: Cell::new(ComponentType::default()),
         })
     }
 
-    fn build_window(application: &adw::Application, child: &impl HasWidget<gtk::Widget>) -> adw::ApplicationWindow {
+    fn build_window(application: &adw::Application, child: &impl HasWidget<gtk::Widget>, settings: &Settings) -> adw::ApplicationWindow {
         let window = adw::ApplicationWindow::new(application);
         window.set_content(Some(child.widget()));
-        window.set_default_size(800, 600);
+        settings.bind_window_state(&window);
         window.set_icon_name(Some(metadata::APP_ICON_NAME));
         window.present();
         window
@@ -63,8 +88,16 @@ impl Window {
         match action {
             UiAction::PushNotification { .. } => ComponentType::Notifications,
             UiAction::OpenFileChooserDialog { .. } => ComponentType::FileDialog,
+            UiAction::SaveFileChooserDialog { .. } => ComponentType::FileDialog,
             UiAction::OpenAboutDialog { .. } => ComponentType::About,
             UiAction::OpenAlertDialog { .. } => ComponentType::AlertDialog,
+            UiAction::OpenEntryDialog { .. } => ComponentType::AlertDialog,
+            UiAction::OpenPreferencesDialog => ComponentType::Preferences,
+            UiAction::OpenShortcutsWindow => ComponentType::Shortcuts,
+            UiAction::OpenCommandPalette { .. } => ComponentType::CommandPalette,
+            UiAction::OpenMergeDialog { .. } => ComponentType::MergeDialog,
+            UiAction::OpenPassphraseDialog => ComponentType::PassphraseDialog,
+            UiAction::OpenErrorDialog { .. } => ComponentType::ErrorDialog,
         }
     }
 
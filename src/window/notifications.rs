@@ -1,20 +1,26 @@
 use crate::prelude::*;
 use crate::context::*;
+use crate::settings::Settings;
+use crate::metadata;
+
+use gtk::glib;
 use std::cell::RefCell;
 use super::WindowComponent;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Notifications {
     toast_overlay: adw::ToastOverlay,
+    settings: Settings,
     last_toast: RefCell<Option<adw::Toast>>
 }
 
 impl Notifications {
 
-    pub fn new(child: &impl HasWidget<gtk::Widget>) -> Self {
+    pub fn new(child: &impl HasWidget<gtk::Widget>, settings: Settings) -> Self {
         let toast_overlay = Self::build_toast_overlay(child.widget());
         Self {
             toast_overlay,
+            settings,
             last_toast: RefCell::new(None)
         }
     }
@@ -34,10 +40,21 @@ impl HasWidget<gtk::Widget> for Notifications {
 }
 
 impl LifeCycle<UiAction> for Notifications {
-    fn activate(&self, action: UiAction, _: &Context) {
-        let UiAction::PushNotification { message } = action else { unreachable!() };
+    fn activate(&self, action: UiAction, context: &Context) {
+        let UiAction::PushNotification { message, error } = action else { unreachable!() };
         let toast = adw::Toast::new(&message);
-        toast.set_timeout(2);
+        toast.set_timeout(self.settings.notification_timeout());
+        if let Some(error) = error {
+            glib::g_warning!(metadata::APP_NAME, "{}: {}", message, error);
+            toast.set_button_label(Some(&gettext("Details")));
+            let context = context.clone();
+            toast.connect_button_clicked(move |_| {
+                context
+                    .clone()
+                    .with_ui_action(UiAction::OpenErrorDialog { message: message.clone(), error: error.clone() })
+                    .propagate();
+            });
+        }
         self.toast_overlay.add_toast(toast.clone());
         self.last_toast.borrow_mut().replace(toast);
     }
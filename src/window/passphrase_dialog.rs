@@ -0,0 +1,233 @@
+use crate::prelude::*;
+use crate::context::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::WindowComponent;
+use super::UiAction;
+
+/// Sets, changes or removes the passphrase of the open document.
+/// The current passphrase is asked again before an encrypted document is changed.
+pub struct PassphraseDialog {
+    window: gtk::Window,
+    dialog: adw::Dialog,
+    current_group: adw::PreferencesGroup,
+    current_row: adw::PasswordEntryRow,
+    new_row: adw::PasswordEntryRow,
+    confirm_row: adw::PasswordEntryRow,
+    cancel_button: gtk::Button,
+    save_button: gtk::Button,
+    remove_group: adw::PreferencesGroup,
+    remove_row: adw::ButtonRow,
+    is_active: Rc<Cell<bool>>,
+    context: Rc<RefCell<Context>>,
+}
+
+impl PassphraseDialog {
+
+    pub fn new(window: impl IsA<gtk::Window>) -> Self {
+        let current_row = Self::build_password_row(&gettext("Current Passphrase"));
+        let new_row = Self::build_password_row(&gettext("New Passphrase"));
+        let confirm_row = Self::build_password_row(&gettext("Confirm Passphrase"));
+        let cancel_button = gtk::Button::with_label(&gettext("Cancel"));
+        let save_button = gtk::Button::with_label(&gettext("Save"));
+            save_button.add_css_class("suggested-action");
+            save_button.set_sensitive(false);
+        let remove_row = adw::ButtonRow::new();
+            remove_row.set_title(&gettext("Remove Passphrase"));
+            remove_row.add_css_class("destructive-action");
+        let current_group = adw::PreferencesGroup::new();
+            current_group.add(&current_row);
+        let remove_group = adw::PreferencesGroup::new();
+            remove_group.add(&remove_row);
+
+        let page = Self::build_page(&current_group, &new_row, &confirm_row, &remove_group);
+        let toolbar_view = Self::build_toolbar_view(&cancel_button, &save_button, &page);
+        let dialog = adw::Dialog::new();
+            dialog.set_content_width(420);
+            dialog.set_child(Some(&toolbar_view));
+
+        let this = Self {
+            window: window.upcast(),
+            dialog,
+            current_group,
+            current_row,
+            new_row,
+            confirm_row,
+            cancel_button,
+            save_button,
+            remove_group,
+            remove_row,
+            is_active: Rc::new(Cell::new(false)),
+            context: Rc::new(RefCell::new(Context::default())),
+        };
+        this.connect_rows();
+        this.connect_buttons();
+        this.connect_closed();
+        this
+    }
+
+    fn build_password_row(title: &str) -> adw::PasswordEntryRow {
+        let row = adw::PasswordEntryRow::new();
+        row.set_title(title);
+        row
+    }
+
+    fn build_page(
+        current_group: &adw::PreferencesGroup,
+        new_row: &adw::PasswordEntryRow,
+        confirm_row: &adw::PasswordEntryRow,
+        remove_group: &adw::PreferencesGroup
+    ) -> adw::PreferencesPage {
+        let new_group = adw::PreferencesGroup::new();
+        new_group.set_description(Some(&gettext("The document can't be opened without its passphrase, and a forgotten passphrase can't be recovered.")));
+        new_group.add(new_row);
+        new_group.add(confirm_row);
+
+        let page = adw::PreferencesPage::new();
+        page.add(current_group);
+        page.add(&new_group);
+        page.add(remove_group);
+        page
+    }
+
+    fn build_toolbar_view(cancel_button: &gtk::Button, save_button: &gtk::Button, page: &adw::PreferencesPage) -> adw::ToolbarView {
+        let header_bar = adw::HeaderBar::new();
+        header_bar.set_show_start_title_buttons(false);
+        header_bar.set_show_end_title_buttons(false);
+        header_bar.pack_start(cancel_button);
+        header_bar.pack_end(save_button);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header_bar);
+        toolbar_view.set_content(Some(page));
+        toolbar_view
+    }
+
+    fn connect_rows(&self) {
+        for row in [&self.new_row, &self.confirm_row] {
+            let new_row = self.new_row.clone();
+            let confirm_row = self.confirm_row.clone();
+            let save_button = self.save_button.clone();
+            row.connect_changed(move |_| {
+                let is_confirmed = !new_row.text().is_empty() && new_row.text() == confirm_row.text();
+                save_button.set_sensitive(is_confirmed);
+            });
+        }
+
+        let current_row = self.current_row.clone();
+        self.current_row.connect_changed(move |_| {
+            current_row.remove_css_class("error");
+        });
+    }
+
+    /// Checks the current passphrase of an encrypted document.
+    fn is_current_passphrase(current_row: &adw::PasswordEntryRow, context: &Context) -> bool {
+        if !context.file().has_passphrase() {
+            return true;
+        }
+        let is_valid = context.file().clone().unlock(&current_row.text()).is_ok();
+        if !is_valid {
+            current_row.add_css_class("error");
+            current_row.grab_focus();
+        }
+        is_valid
+    }
+
+    fn apply(dialog: &adw::Dialog, context: &Context, passphrase: Option<&str>, message: String) {
+        let file = match context.file().clone().with_passphrase(passphrase) {
+            Ok(file) => file,
+            Err(e) => return context
+                .clone()
+                .with_ui_action(UiAction::push_error(gettext("Failed to encrypt document"), e))
+                .propagate(),
+        };
+        dialog.close();
+        let data = context.data().clone();
+        context
+            .clone()
+            .with_file(file)
+            .with_data(data)
+            .with_ui_action(UiAction::push_notification(message))
+            .with_navigation_action(NavigationAction::NavigateToCurrent)
+            .propagate();
+    }
+
+    fn connect_buttons(&self) {
+        let dialog = self.dialog.clone();
+        self.cancel_button.connect_clicked(move |_| {
+            dialog.close();
+        });
+
+        let dialog = self.dialog.clone();
+        let context = self.context.clone();
+        let current_row = self.current_row.clone();
+        let new_row = self.new_row.clone();
+        self.save_button.connect_clicked(move |_| {
+            let context = context.borrow().clone();
+            if !Self::is_current_passphrase(&current_row, &context) {
+                return;
+            }
+            let message = if context.file().has_passphrase() {
+                gettext("Passphrase changed")
+            } else {
+                gettext("Document encrypted")
+            };
+            Self::apply(&dialog, &context, Some(&new_row.text()), message);
+        });
+
+        let dialog = self.dialog.clone();
+        let context = self.context.clone();
+        let current_row = self.current_row.clone();
+        self.remove_row.connect_activated(move |_| {
+            let context = context.borrow().clone();
+            if Self::is_current_passphrase(&current_row, &context) {
+                Self::apply(&dialog, &context, None, gettext("Passphrase removed"));
+            }
+        });
+    }
+
+    fn connect_closed(&self) {
+        let is_active = self.is_active.clone();
+        let context = self.context.clone();
+        let rows = [self.current_row.clone(), self.new_row.clone(), self.confirm_row.clone()];
+        self.dialog.connect_closed(move |_| {
+            is_active.set(false);
+            context.take();
+            for row in &rows {
+                row.set_text("");
+                row.remove_css_class("error");
+            }
+        });
+    }
+
+}
+
+impl LifeCycle<UiAction> for PassphraseDialog {
+    fn activate(&self, action: UiAction, context: &Context) {
+        if !matches!(action, UiAction::OpenPassphraseDialog) { unreachable!() };
+        let is_encrypted = context.file().has_passphrase();
+        self.dialog.set_title(&if is_encrypted { gettext("Change Passphrase") } else { gettext("Set Passphrase") });
+        self.current_group.set_visible(is_encrypted);
+        self.remove_group.set_visible(is_encrypted);
+        self.context.replace(context.clone());
+        self.is_active.set(true);
+        self.dialog.present(Some(&self.window));
+        if is_encrypted {
+            self.current_row.grab_focus();
+        } else {
+            self.new_row.grab_focus();
+        }
+    }
+
+    fn deactivate(&self) {
+        self.dialog.close();
+    }
+}
+
+impl WindowComponent for PassphraseDialog {
+    fn is_active(&self) -> bool {
+        self.is_active.get()
+    }
+}
@@ -0,0 +1,270 @@
+use crate::prelude::*;
+use crate::context::*;
+use crate::i18n::gettext_noop;
+use crate::data::{Currency, DateFormat, NegativeStyle, NumberFormat, RateProvider, SymbolPosition};
+use crate::settings::{ColorScheme, Settings};
+
+use chrono::Weekday;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::WindowComponent;
+use super::UiAction;
+
+const WEEKDAYS: [Weekday; 2] = [Weekday::Mon, Weekday::Sun];
+const FOLLOW_SYSTEM: &str = gettext_noop("Follow System");
+
+/// Prepends a "follow the system" choice, stored as `None`, to a list of variants.
+fn with_system<T: Copy>(variants: &[T]) -> Vec<Option<T>> {
+    std::iter::once(None)
+        .chain(variants.iter().copied().map(Some))
+        .collect()
+}
+
+/// Translates the labels of a list of variants.
+fn labels<T>(variants: &[T], label: impl Fn(&T) -> &'static str) -> Vec<String> {
+    variants.iter().map(|v| gettext(label(v))).collect()
+}
+
+fn system_labels<T>(variants: &[T], label: impl Fn(&T) -> &'static str) -> Vec<String> {
+    std::iter::once(gettext(FOLLOW_SYSTEM))
+        .chain(labels(variants, label))
+        .collect()
+}
+
+pub struct PreferencesDialog {
+    window: gtk::Window,
+    settings: Settings,
+    preferences_dialog: adw::PreferencesDialog,
+    color_scheme_row: adw::ComboRow,
+    number_format_row: adw::ComboRow,
+    symbol_position_row: adw::ComboRow,
+    negative_style_row: adw::ComboRow,
+    date_format_row: adw::ComboRow,
+    first_weekday_row: adw::ComboRow,
+    default_currency_row: adw::ComboRow,
+    notification_timeout_row: adw::SpinRow,
+    backup_policy_row: adw::ComboRow,
+    backup_count_row: adw::SpinRow,
+    auto_lock_row: adw::SpinRow,
+    rate_provider_row: adw::ComboRow,
+    is_active: Rc<Cell<bool>>,
+    context: Rc<RefCell<Context>>,
+}
+
+impl PreferencesDialog {
+
+    pub fn new(window: impl IsA<gtk::Window>, settings: Settings) -> Self {
+        let color_scheme_row = Self::build_combo_row(&gettext("Style"), &labels(&ColorScheme::as_slice(), ColorScheme::as_str));
+        let number_format_row = Self::build_combo_row(&gettext("Number Format"), &system_labels(&NumberFormat::as_slice(), NumberFormat::as_str));
+        let symbol_position_row = Self::build_combo_row(&gettext("Currency Symbol"), &system_labels(&SymbolPosition::as_slice(), SymbolPosition::as_str));
+        let negative_style_row = Self::build_combo_row(&gettext("Negative Amounts"), &labels(&NegativeStyle::as_slice(), NegativeStyle::as_str));
+        let date_format_row = Self::build_combo_row(&gettext("Date Format"), &system_labels(&DateFormat::as_slice(), DateFormat::as_str));
+        let first_weekday_row = Self::build_combo_row(&gettext("First Day of the Week"), &[gettext("Monday"), gettext("Sunday")]);
+        let default_currency_row = Self::build_combo_row(&gettext("Default Currency"), &labels(&Currency::as_slice(), Currency::as_long_str));
+            default_currency_row.set_subtitle(&gettext("Used for new documents and wallets"));
+        let notification_timeout_row = Self::build_spin_row(&gettext("Notification Duration"), 1.0, 30.0);
+            notification_timeout_row.set_subtitle(&gettext("Seconds"));
+        let backup_policy_row = Self::build_combo_row(&gettext("Create Backups"), &labels(&BackupPolicy::as_slice(), BackupPolicy::as_str));
+        let backup_count_row = Self::build_spin_row(&gettext("Backups to Keep"), 1.0, 100.0);
+        let auto_lock_row = Self::build_spin_row(&gettext("Lock After Inactivity"), 0.0, 120.0);
+            auto_lock_row.set_subtitle(&gettext("Minutes before an encrypted document is locked, 0 to never lock"));
+        let rate_provider_row = Self::build_combo_row(&gettext("Provider"), &labels(&RateProvider::as_slice(), RateProvider::as_str));
+
+        let general_page = Self::build_page(&gettext("General"), "preferences-system-symbolic", vec![
+            Self::build_group(&gettext("Appearance"), vec![color_scheme_row.upcast_ref()]),
+            Self::build_group(&gettext("Formatting"), vec![
+                number_format_row.upcast_ref(),
+                symbol_position_row.upcast_ref(),
+                negative_style_row.upcast_ref(),
+                date_format_row.upcast_ref(),
+                first_weekday_row.upcast_ref()
+            ]),
+            Self::build_group(&gettext("Wallets"), vec![default_currency_row.upcast_ref()]),
+            Self::build_group(&gettext("Notifications"), vec![notification_timeout_row.upcast_ref()]),
+        ]);
+        let data_page = Self::build_page(&gettext("Data"), "drive-harddisk-symbolic", vec![
+            Self::build_group(&gettext("Backups"), vec![backup_policy_row.upcast_ref(), backup_count_row.upcast_ref()]),
+            Self::build_group(&gettext("Security"), vec![auto_lock_row.upcast_ref()]),
+            Self::build_group(&gettext("Exchange Rates"), vec![rate_provider_row.upcast_ref()]),
+        ]);
+
+        let preferences_dialog = adw::PreferencesDialog::new();
+            preferences_dialog.set_search_enabled(false);
+            preferences_dialog.add(&general_page);
+            preferences_dialog.add(&data_page);
+
+        let this = Self {
+            window: window.upcast(),
+            settings,
+            preferences_dialog,
+            color_scheme_row,
+            number_format_row,
+            symbol_position_row,
+            negative_style_row,
+            date_format_row,
+            first_weekday_row,
+            default_currency_row,
+            notification_timeout_row,
+            backup_policy_row,
+            backup_count_row,
+            auto_lock_row,
+            rate_provider_row,
+            is_active: Rc::new(Cell::new(false)),
+            context: Rc::new(RefCell::new(Context::default())),
+        };
+        this.load_settings();
+        this.connect_rows();
+        this.connect_closed();
+        this
+    }
+
+    fn build_combo_row(title: &str, items: &[String]) -> adw::ComboRow {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let combo_row = adw::ComboRow::new();
+        combo_row.set_title(title);
+        combo_row.set_model(Some(&gtk::StringList::new(&items)));
+        combo_row
+    }
+
+    fn build_spin_row(title: &str, min: f64, max: f64) -> adw::SpinRow {
+        let spin_row = adw::SpinRow::with_range(min, max, 1.0);
+        spin_row.set_title(title);
+        spin_row.set_digits(0);
+        spin_row
+    }
+
+    fn build_group(title: &str, rows: Vec<&gtk::Widget>) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(title);
+        for row in rows {
+            group.add(row);
+        }
+        group
+    }
+
+    fn build_page(title: &str, icon_name: &str, groups: Vec<adw::PreferencesGroup>) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::new();
+        page.set_title(title);
+        page.set_icon_name(Some(icon_name));
+        for group in groups {
+            page.add(&group);
+        }
+        page
+    }
+
+    fn position<T: PartialEq>(items: &[T], value: &T) -> u32 {
+        items.iter().position(|i| i == value).unwrap_or_default() as u32
+    }
+
+    fn load_settings(&self) {
+        let settings = &self.settings;
+        self.color_scheme_row.set_selected(Self::position(&ColorScheme::as_slice(), &settings.color_scheme()));
+        self.number_format_row.set_selected(Self::position(&with_system(&NumberFormat::as_slice()), &settings.number_format()));
+        self.symbol_position_row.set_selected(Self::position(&with_system(&SymbolPosition::as_slice()), &settings.symbol_position()));
+        self.negative_style_row.set_selected(Self::position(&NegativeStyle::as_slice(), &settings.negative_style()));
+        self.date_format_row.set_selected(Self::position(&with_system(&DateFormat::as_slice()), &settings.date_format()));
+        self.first_weekday_row.set_selected(Self::position(&WEEKDAYS, &settings.first_weekday()));
+        self.default_currency_row.set_selected(Self::position(&Currency::as_slice(), &settings.default_currency()));
+        self.notification_timeout_row.set_value(settings.notification_timeout() as f64);
+        self.backup_policy_row.set_selected(Self::position(&BackupPolicy::as_slice(), &settings.backup_policy()));
+        self.backup_count_row.set_value(settings.backup_count() as f64);
+        self.auto_lock_row.set_value(settings.auto_lock_minutes() as f64);
+        self.rate_provider_row.set_selected(Self::position(&RateProvider::as_slice(), &settings.rate_provider()));
+        self.backup_count_row.set_sensitive(settings.backup_policy() != BackupPolicy::Never);
+    }
+
+    fn connect_combo_row<T: Copy + 'static>(
+        combo_row: &adw::ComboRow,
+        items: impl Into<Vec<T>>,
+        callback: impl Fn(T) + 'static
+    ) {
+        let items = items.into();
+        combo_row.connect_selected_notify(move |row| {
+            if let Some(item) = items.get(row.selected() as usize) {
+                callback(*item);
+            }
+        });
+    }
+
+    fn connect_rows(&self) {
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.color_scheme_row, ColorScheme::as_slice(), move |s| settings.set_color_scheme(s));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.number_format_row, with_system(&NumberFormat::as_slice()), move |f| settings.set_number_format(f));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.symbol_position_row, with_system(&SymbolPosition::as_slice()), move |p| settings.set_symbol_position(p));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.negative_style_row, NegativeStyle::as_slice(), move |s| settings.set_negative_style(s));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.date_format_row, with_system(&DateFormat::as_slice()), move |f| settings.set_date_format(f));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.first_weekday_row, WEEKDAYS, move |w| settings.set_first_weekday(w));
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.default_currency_row, Currency::as_slice(), move |c| settings.set_default_currency(c));
+
+        let settings = self.settings.clone();
+        let backup_count_row = self.backup_count_row.clone();
+        Self::connect_combo_row(&self.backup_policy_row, BackupPolicy::as_slice(), move |p| {
+            backup_count_row.set_sensitive(p != BackupPolicy::Never);
+            settings.set_backup_policy(p);
+        });
+
+        let settings = self.settings.clone();
+        Self::connect_combo_row(&self.rate_provider_row, RateProvider::as_slice(), move |p| settings.set_rate_provider(p));
+
+        let settings = self.settings.clone();
+        self.notification_timeout_row.connect_value_notify(move |row| {
+            settings.set_notification_timeout(row.value() as u32);
+        });
+
+        let settings = self.settings.clone();
+        self.backup_count_row.connect_value_notify(move |row| {
+            settings.set_backup_count(row.value() as u32);
+        });
+
+        let settings = self.settings.clone();
+        self.auto_lock_row.connect_value_notify(move |row| {
+            settings.set_auto_lock_minutes(row.value() as u32);
+        });
+    }
+
+    fn connect_closed(&self) {
+        let is_active = self.is_active.clone();
+        let context = self.context.clone();
+        self.preferences_dialog.connect_closed(move |_| {
+            is_active.set(false);
+            context
+                .take()
+                .with_navigation_action(NavigationAction::NavigateToCurrent)
+                .propagate();
+        });
+    }
+
+}
+
+impl LifeCycle<UiAction> for PreferencesDialog {
+    fn activate(&self, action: UiAction, context: &Context) {
+        if !matches!(action, UiAction::OpenPreferencesDialog) { unreachable!() };
+        self.load_settings();
+        self.context.replace(context.clone());
+        self.is_active.set(true);
+        self.preferences_dialog.present(Some(&self.window));
+    }
+
+    fn deactivate(&self) {
+        self.preferences_dialog.close();
+    }
+}
+
+impl WindowComponent for PreferencesDialog {
+    fn is_active(&self) -> bool {
+        self.is_active.get()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use crate::context::*;
+
+use gtk::glib;
+
+use super::WindowComponent;
+use super::UiAction;
+
+pub struct ShortcutsWindow {
+    shortcuts_window: gtk::ShortcutsWindow,
+}
+
+impl ShortcutsWindow {
+
+    pub fn new(window: impl IsA<gtk::Window>) -> Self {
+        let shortcuts_window = Self::build_shortcuts_window();
+        shortcuts_window.set_transient_for(Some(&window));
+        Self {
+            shortcuts_window
+        }
+    }
+
+    fn build_shortcut(shortcut: Shortcut) -> String {
+        format!(
+            r#"<child><object class="GtkShortcutsShortcut"><property name="title">{}</property><property name="accelerator">{}</property></object></child>"#,
+            glib::markup_escape_text(&gettext(shortcut.title())),
+            glib::markup_escape_text(shortcut.accelerator())
+        )
+    }
+
+    fn build_group(section: &str) -> String {
+        let shortcuts: String = Shortcut::as_slice()
+            .into_iter()
+            .filter(|shortcut| shortcut.section() == section)
+            .map(Self::build_shortcut)
+            .collect();
+        format!(
+            r#"<child><object class="GtkShortcutsGroup"><property name="title">{}</property>{}</object></child>"#,
+            glib::markup_escape_text(&gettext(section)),
+            shortcuts
+        )
+    }
+
+    /// `GtkShortcutsSection` and `GtkShortcutsGroup` can only be filled from a builder
+    /// before GTK 4.14, so the interface is generated from the `Shortcut` table.
+    fn build_shortcuts_window() -> gtk::ShortcutsWindow {
+        let mut sections: Vec<&str> = Vec::new();
+        for shortcut in Shortcut::as_slice() {
+            if !sections.contains(&shortcut.section()) {
+                sections.push(shortcut.section());
+            }
+        }
+        let groups: String = sections.into_iter().map(Self::build_group).collect();
+        let interface = format!(
+            r#"<interface><object class="GtkShortcutsWindow" id="shortcuts_window"><property name="modal">true</property><property name="hide-on-close">true</property><child><object class="GtkShortcutsSection"><property name="section-name">shortcuts</property>{}</object></child></object></interface>"#,
+            groups
+        );
+
+        gtk::Builder::from_string(&interface)
+            .object::<gtk::ShortcutsWindow>("shortcuts_window")
+            .expect("Failed to build the shortcuts window")
+    }
+
+}
+
+impl LifeCycle<UiAction> for ShortcutsWindow {
+    fn activate(&self, action: UiAction, _: &Context) {
+        if !matches!(action, UiAction::OpenShortcutsWindow) { unreachable!() };
+        self.shortcuts_window.present();
+    }
+
+    fn deactivate(&self) {
+        self.shortcuts_window.close();
+    }
+}
+
+impl WindowComponent for ShortcutsWindow {
+    fn is_active(&self) -> bool {
+        self.shortcuts_window.is_visible()
+    }
+}